@@ -0,0 +1,66 @@
+use imgui::{Condition, Ui};
+use imgui_glow_renderer::glow;
+use imgui_glow_renderer::glow::HasContext;
+use winit::monitor::VideoModeHandle;
+use crate::consts::*;
+use crate::i18n::tr;
+use crate::plugins::plugins;
+
+/// Environment details for bug reports.
+pub struct About {
+    open: bool,
+    text: String,
+}
+
+impl About {
+    pub fn new(gl: &glow::Context, video_mode: &VideoModeHandle) -> Self {
+        // no safe way to achieve this
+        let (version, renderer, vendor) = unsafe {
+            (
+                gl.get_parameter_string(glow::VERSION),
+                gl.get_parameter_string(glow::RENDERER),
+                gl.get_parameter_string(glow::VENDOR),
+            )
+        };
+
+        let size = video_mode.size();
+        let plugins: Vec<&str> = plugins().iter().map(|plugin| plugin.name.as_str()).collect();
+
+        Self {
+            open: false,
+            text: format!(
+                "VeilDE-rs {}\nOpenGL {version}\nRenderer: {renderer}\nVendor: {vendor}\nDisplay: {}x{} @ {:.2} Hz\nPlugins: {}",
+                env!("CARGO_PKG_VERSION"),
+                size.width,
+                size.height,
+                video_mode.refresh_rate_millihertz() as f32 / 1000f32,
+                if plugins.is_empty() { "none".to_string() } else { plugins.join(", ") }
+            ),
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.open = true;
+    }
+
+    pub fn render(&mut self, ui: &Ui) {
+        if !self.open {
+            return;
+        }
+
+        ui.window("About VeilDE")
+            .always_auto_resize(true)
+            .collapsible(false)
+            .position_pivot([0.5f32, 0.5f32])
+            .position(ui.io().display_size.map(|size| size / 2f32), Condition::Appearing)
+            .opened(&mut self.open)
+            .build(|| {
+                ui.text(&self.text);
+                ui.separator();
+
+                if ui.button(tr("Copy")) {
+                    ui.set_clipboard_text(&self.text);
+                }
+            });
+    }
+}
@@ -0,0 +1,105 @@
+use crate::config::Config;
+use crate::i18n::tr;
+
+/// Something the user can do from a menu, the launcher or the command palette.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Settings,
+    About,
+    NewNote,
+    ChangeWallpaper,
+    Refresh,
+    ReloadConfig,
+    ToggleTaskbar,
+    ToggleNightLight,
+    ToggleDoNotDisturb,
+    CycleLayout,
+    TileWindows,
+    Screenshot,
+    RegionCapture,
+    ColorPicker,
+    LockScreen,
+    SignOut, // Windows only, like restarting and shutting down
+    Restart,
+    ShutDown,
+    Launch(usize), // index into `config.launcher`
+}
+
+/// Shown in the desktop's right-click menu, in order.
+pub const DESKTOP_MENU_ACTIONS: &[Action] = &[
+    Action::Settings,
+    Action::NewNote,
+    Action::ChangeWallpaper,
+    Action::Refresh,
+];
+
+/// Shown in the start button's right-click menu, in order.
+pub const TASKBAR_MENU_ACTIONS: &[Action] = &[
+    Action::Settings,
+    Action::About,
+    Action::LockScreen,
+];
+
+/// Shown in the launcher's power menu, in order.
+pub const POWER_MENU_ACTIONS: &[Action] = &[
+    Action::LockScreen,
+    Action::SignOut,
+    Action::Restart,
+    Action::ShutDown,
+];
+
+const COMMON_ACTIONS: &[Action] = &[
+    Action::Settings,
+    Action::About,
+    Action::NewNote,
+    Action::ChangeWallpaper,
+    Action::Refresh,
+    Action::ReloadConfig,
+    Action::ToggleTaskbar,
+    Action::ToggleNightLight,
+    Action::ToggleDoNotDisturb,
+    Action::CycleLayout,
+    Action::TileWindows,
+    Action::Screenshot,
+    Action::RegionCapture,
+    Action::ColorPicker,
+    Action::LockScreen,
+    Action::SignOut,
+    Action::Restart,
+    Action::ShutDown,
+];
+
+impl Action {
+    pub fn name<'a>(&self, config: &'a Config) -> &'a str {
+        match self {
+            Action::Settings => tr("Settings"),
+            Action::About => tr("About VeilDE"),
+            Action::NewNote => tr("New note"),
+            Action::ChangeWallpaper => tr("Change wallpaper"),
+            Action::Refresh => tr("Refresh"),
+            Action::ReloadConfig => tr("Reload config"),
+            Action::ToggleTaskbar => tr("Toggle taskbar auto-hide"),
+            Action::ToggleNightLight => tr("Toggle night light"),
+            Action::ToggleDoNotDisturb => tr("Toggle do not disturb"),
+            Action::CycleLayout => tr("Cycle window layout"),
+            Action::TileWindows => tr("Tile windows"),
+            Action::Screenshot => tr("Take screenshot"),
+            Action::RegionCapture => tr("Capture region"),
+            Action::ColorPicker => tr("Pick color"),
+            Action::LockScreen => tr("Lock screen"),
+            Action::SignOut => tr("Sign out"),
+            Action::Restart => tr("Restart"),
+            Action::ShutDown => tr("Shut down"),
+            Action::Launch(index) => config.launcher.get(*index).map_or("", |entry| entry.name.as_str()),
+        }
+    }
+}
+
+/// Every action, followed by a launch per configured program.
+pub fn all_actions(config: &Config) -> Vec<Action> {
+    COMMON_ACTIONS
+        .iter()
+        .copied()
+        .chain((0..config.launcher.len()).map(Action::Launch))
+        .collect()
+}
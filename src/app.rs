@@ -1,248 +1,2061 @@
 use winit::{
     application::ApplicationHandler,
     event::WindowEvent,
-    event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
-    raw_window_handle::HasWindowHandle,
+    event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy},
     window::Window,
-    window::{WindowAttributes, WindowId}
+    window::WindowId
 };
 use glutin::{
-    config::ConfigTemplateBuilder,
-    context::{ContextAttributesBuilder, NotCurrentGlContext, PossiblyCurrentContext as OpenGlContext},
-    display::{GetGlDisplay, GlDisplay},
-    surface::{GlSurface, Surface, SurfaceAttributesBuilder, SwapInterval, WindowSurface}
+    context::{PossiblyCurrentContext as OpenGlContext, PossiblyCurrentGlContext},
+    surface::{GlSurface, Surface, WindowSurface}
 };
-use imgui_winit_support::{HiDpiMode, WinitPlatform};
+use imgui_winit_support::WinitPlatform;
 use std::{
+    cell::RefCell,
+    collections::HashMap,
     num::NonZeroU32,
+    path::PathBuf,
     sync::mpsc::channel,
+    rc::Rc,
     sync::mpsc::Sender,
-    time::Instant
+    time::{Duration, Instant}
 };
 use imgui_glow_renderer::{
     glow,
     AutoRenderer
 };
-use imgui::{
-    internal::RawCast,
-    FontConfig,
-    FontSource,
-};
-use imgui_sys::{ImGuiFreeTypeBuilderFlags_Bitmap, ImGuiFreeType_GetBuilderForFreeType};
 use anyhow::{anyhow, bail, Context, Error, Result};
 use chrono::Local;
-use imgui::{Condition, Context as ImGuiContext};
+use imgui::{Condition, Context as ImGuiContext, MouseButton, Style, StyleColor, StyleStackToken, StyleVar, Ui, WindowHoveredFlags};
+use native_dialog::DialogBuilder;
+use crate::about::About;
+use crate::actions::{all_actions, Action, DESKTOP_MENU_ACTIONS, TASKBAR_MENU_ACTIONS};
+use crate::ipc::IpcCommand;
+use crate::capture::{read_framebuffer, save_screenshot, RegionCapture};
+use crate::color_picker::ColorPicker;
+use crate::command_palette::CommandPalette;
+use crate::lock_screen::{is_valid_hash, LockScreen};
+use crate::night_light::NightLight;
+use crate::notes::Notes;
+use crate::notifications::Notifications;
+use crate::panics;
+use crate::plugins::{load_plugins, plugins, PluginWidget};
+use crate::platform::{best_video_mode, init_glow, init_glutin, init_imgui, init_opengl, init_swap_interval, init_winit, is_recoverable, window_position};
 use crate::renderer::VeilDERenderer;
+use crate::screensaver::Screensaver;
+use crate::script::run_script;
+use crate::settings::{copy_edited, Settings, SettingsChange};
+use crate::slideshow::Slideshow;
+use crate::splash::Splash;
+use crate::theme::{accent_color, apply_theme, theme_name};
+use crate::time::SystemClock;
+use crate::widgets::Widget;
+use crate::widgets::clock::Clock;
+use crate::widgets::disks::Disks;
+use crate::widgets::launcher::{Launcher, LauncherEntry};
+use crate::widgets::network::Network;
+use crate::widgets::system::SystemUsage;
+use crate::widgets::system_info::SystemInfo;
+use crate::widgets::timer::Timer;
+use crate::widgets::weather::Weather;
+use crate::Panel;
+use crate::config::{Color, Config, ConfigOverrides, ConfigWatcher, RedrawMode, TaskbarPosition};
 use crate::consts::*;
-use glutin::config::Config;
-use imgui_glow_renderer::glow::HasContext;
-use winit::dpi::{LogicalSize, PhysicalSize, Position, Size};
-use winit::event::Event;
+use crate::fonts::{build_fonts, has_glyph, FontFiles};
+use crate::fps::FpsOverlay;
+use crate::hooks::{run_hooks, FrameHooks};
+use crate::i18n::{load_strings, tr};
+use crate::log_console::{self, LogConsole};
+use crate::input::forward_window_event;
+use crate::layout::Layout;
+use crate::utils::{confirm, resolve_path};
+use glutin::config::Config as GlConfig;
+use winit::dpi::PhysicalSize;
+use winit::event::{ElementState, KeyEvent, StartCause};
+use winit::keyboard::{Key, NamedKey};
 use winit::monitor::{MonitorHandle, VideoModeHandle};
+use winit::window::WindowLevel;
+
+#[allow(unused)] // contexts are all important, even if not currently used
+struct VeilDEContexts {
+    pub monitor: MonitorHandle,
+    pub video_mode: VideoModeHandle,
+    pub imgui: ImGuiContext,
+    pub winit: WinitPlatform,
+    pub window: Window,
+    pub opengl: OpenGlContext,
+    pub gl_config: GlConfig,
+    pub glow: AutoRenderer,
+    pub surface: Surface<WindowSurface>,
+}
+
+struct VeilDEApplication {
+    contexts: VeilDEContexts,
+    renderer: VeilDERenderer,
+    slideshow: Option<Slideshow>, // `None` without a slideshow directory
+    night_light: NightLight,
+    last_frame: Option<Instant>,
+    start: Instant,
+    resolution: PhysicalSize<u32>,
+    window_position: Option<[i32; 2]>, // `None` until moved
+    fonts: FontFiles,
+    default_style: Style, // what themes are applied on top of
+    accent_color: Option<Color>,
+    widgets: Vec<Box<dyn Widget>>,
+    launcher: Launcher,
+    command_palette: CommandPalette,
+    notifications: Notifications,
+    do_not_disturb: bool, // notifications are held and widgets dimmed
+    cursor: Option<[f32; 2]>, // `None` while outside the window
+    taskbar_reveal: f32, // 0 when auto-hidden, 1 when shown
+    screenshot_region: Option<([u32; 2], [u32; 2])>, // position and size, saved after the next frame
+    region_capture: Option<RegionCapture>, // `None` while not selecting
+    color_picker: Option<ColorPicker>, // `None` while not picking
+    fps_overlay: Option<FpsOverlay>, // `None` while hidden
+    lock_screen: Option<LockScreen>, // `Some` while locked, covering everything else
+    unlocked: bool, // taken by the handler to unlock every monitor
+    screensaver: Option<Screensaver>, // `Some` while idle, drawn instead of the desktop
+    log_console: LogConsole,
+    about: About,
+    settings: Settings,
+    settings_change: Option<SettingsChange>, // applied to every monitor by the handler
+    command: Option<IpcCommand>, // run by the handler as well, e.g. reloading the config
+    pending_frames: u32,
+    occluded: bool, // fully covered, so nothing is rendered
+    failed_recoveries: u32, // consecutive, reset by a successful frame
+    panels: Vec<Panel>,
+    notes: Option<Notes>, // on the primary monitor, like panels
+    hooks: Rc<RefCell<FrameHooks>>, // shared by every monitor
+    config: Config,
+}
+
+struct VeilDEApplicationHandler {
+    applications: HashMap<WindowId, VeilDEApplication>, // one per monitor
+    config: Config,
+    overrides: ConfigOverrides, // from the builder, applied over every config
+    config_path: Option<PathBuf>, // `None` when the executable's directory is unknown
+    config_watcher: Option<ConfigWatcher>,
+    panels: Vec<Panel>, // moved into the primary monitor's application
+    hooks: Rc<RefCell<FrameHooks>>,
+    notes: Option<Notes>, // as are these, `None` when they failed to load
+    last_monitor_sync: Instant,
+    do_not_disturb: bool, // not part of the config, so reloading it keeps this
+    locked: bool,
+    screensaver_idle: Option<Duration>, // the idle time when the screensaver started, `None` while off
+    #[cfg(not(windows))]
+    last_input: Instant, // for the idle timeout, Windows is asked instead
+    #[cfg(windows)]
+    tray_host: Option<crate::win32::tray::TrayHost>,
+    #[cfg(windows)]
+    clipboard_host: Option<crate::win32::clipboard::ClipboardHost>,
+    #[cfg(windows)]
+    hotkey_host: Option<crate::win32::hotkeys::HotkeyHost>,
+    #[cfg(windows)]
+    proxy: EventLoopProxy<IpcCommand>, // for hotkeys
+    error_tx: Sender<Error>,
+}
+
+impl VeilDEApplicationHandler {
+    #[cfg_attr(not(windows), allow(unused_variables))]
+    pub fn new(mut config: Config, overrides: ConfigOverrides, config_path: Option<PathBuf>, panels: Vec<Panel>, hooks: FrameHooks, proxy: EventLoopProxy<IpcCommand>, error_tx: Sender<Error>) -> Self {
+        overrides.apply(&mut config);
+
+        // hot reloading is a convenience,
+        // so the desktop runs without it
+        let config_path = config_path
+            .map_or_else(|| resolve_path(CONFIG_PATH), Ok)
+            .inspect_err(|e| log::warn!("{e:?}"))
+            .ok();
+        let config_watcher = config_path
+            .clone()
+            .map(ConfigWatcher::new)
+            .and_then(|watcher| watcher.inspect_err(|e| log::warn!("{e:?}")).ok());
+
+        Self {
+            applications: HashMap::new(),
+            config,
+            overrides,
+            config_path,
+            config_watcher,
+            panels,
+            hooks: Rc::new(RefCell::new(hooks)),
+            notes: None,
+            last_monitor_sync: Instant::now(),
+            do_not_disturb: false,
+            locked: false,
+            screensaver_idle: None,
+            #[cfg(not(windows))]
+            last_input: Instant::now(),
+            #[cfg(windows)]
+            tray_host: None,
+            #[cfg(windows)]
+            clipboard_host: None,
+            #[cfg(windows)]
+            hotkey_host: None,
+            #[cfg(windows)]
+            proxy,
+            error_tx,
+        }
+    }
+
+    /// Registers the configured hotkeys, replacing any registered before.
+    #[cfg(windows)]
+    fn register_hotkeys(&mut self) {
+        // unregistered first, or keys kept
+        // between configs would be in use
+        self.hotkey_host = None;
+
+        if self.config.hotkeys.is_empty() {
+            return;
+        }
+
+        match crate::win32::hotkeys::HotkeyHost::new(&self.config.hotkeys, self.proxy.clone()) {
+            Ok((host, errors)) => {
+                self.hotkey_host = Some(host);
+
+                for e in errors {
+                    self.notify_error(e);
+                }
+            }
+
+            Err(e) => self.notify_error(e.context("Failed to register hotkeys")),
+        }
+    }
+
+    fn apply_config(&mut self, mut config: Config) -> Result<()> {
+        // e.g. `--windowed`, which the config file doesn't know about
+        self.overrides.apply(&mut config);
+
+        if config.window_size != self.config.window_size {
+            log::warn!("Changing 'window_size' requires a restart");
+        }
+
+        if config.gl_debug != self.config.gl_debug {
+            log::warn!("Changing 'gl_debug' requires a restart");
+        }
+
+        #[cfg(windows)]
+        let hotkeys_changed = config.hotkeys != self.config.hotkeys;
+        let locale_changed = config.locale != self.config.locale;
+        let tiling_changed = config.tiling_layout != self.config.tiling_layout || config.tiling_gap != self.config.tiling_gap;
+
+        // the password that locked the screen unlocks it
+        let lock_password_hash = match self.locked {
+            true => self.config.lock_password_hash.clone(),
+            false => config.lock_password_hash,
+        };
+
+        self.config = Config {
+            lock_password_hash,
+            window_size: self.config.window_size,
+            window_position: self.config.window_position,
+            gl_debug: self.config.gl_debug,
+            windowed: self.config.windowed,
+            monitor: self.config.monitor,
+            ..config
+        };
+
+        // labels are looked up every frame,
+        // so they change with the next one
+        if locale_changed {
+            if let Err(e) = load_strings(&self.config) {
+                self.notify_error(e.context("Failed to load translations"));
+            }
+        }
+
+        for app in self.applications.values_mut() {
+            app.make_current()?;
+            app.apply_config(self.config.clone())?;
+        }
+
+        #[cfg(windows)]
+        if hotkeys_changed {
+            self.register_hotkeys();
+        }
+
+        #[cfg(windows)]
+        if let Some(host) = &self.clipboard_host {
+            host.set_limit(self.config.clipboard_history_limit);
+        }
+
+        if tiling_changed && self.config.tiling_layout != Layout::Floating {
+            self.tile_windows()?;
+        }
+
+        Ok(())
+    }
+
+    /// The monitors to run on, every one unless a single monitor was
+    /// chosen, or only the primary one when running in a window.
+    fn target_monitors(&self, event_loop: &ActiveEventLoop) -> Vec<MonitorHandle> {
+        let mut monitors = event_loop.available_monitors();
+
+        match self.config.monitor {
+            Some(index) => monitors.nth(index).into_iter().collect(),
+
+            None if self.config.windowed => event_loop
+                .primary_monitor()
+                .or_else(|| monitors.next())
+                .into_iter()
+                .collect(),
+
+            None => monitors.collect(),
+        }
+    }
+
+    fn create_applications(&mut self, event_loop: &ActiveEventLoop) -> Result<()> {
+        let primary = event_loop.primary_monitor();
+
+        // saving over a broken file would lose
+        // its notes, so they're left out instead
+        let notes_error = match Notes::load() {
+            Ok(notes) => {
+                self.notes = Some(notes);
+                None
+            }
+
+            Err(e) => Some(e),
+        };
+
+        // reported once there are notifications to show it
+        let script_error = match self.run_config_script(&self.config) {
+            Ok(config) => {
+                self.config = config;
+                None
+            }
+
+            Err(e) => Some(e),
+        };
+        let strings_error = load_strings(&self.config).err();
+        let monitors = self.target_monitors(event_loop);
+
+        if let (Some(index), true) = (self.config.monitor, monitors.is_empty()) {
+            bail!("Monitor {index} doesn't exist ({} available)", event_loop.available_monitors().count());
+        }
+
+        let single = monitors.len() == 1;
+
+        for monitor in monitors {
+            // custom panels only show on the primary monitor,
+            // or the first one when there's no notion of primary
+            let is_primary = single || primary
+                .as_ref()
+                .map_or(self.applications.is_empty(), |primary| *primary == monitor);
+            let panels = if is_primary { std::mem::take(&mut self.panels) } else { Vec::new() };
+            let notes = if is_primary { self.notes.take() } else { None };
+
+            self.add_application(event_loop, monitor, panels, notes)?;
+        }
+
+        if let Some(e) = notes_error {
+            self.notify_error(e.context("Failed to load notes"));
+        }
+
+        if let Some(e) = script_error {
+            self.notify_error(e);
+        }
+
+        // the desktop stays in English
+        if let Some(e) = strings_error {
+            self.notify_error(e.context("Failed to load translations"));
+        }
+
+        if self.applications.is_empty() {
+            bail!("Failed to get monitor");
+        }
+
+        // the tray is shared by every monitor's taskbar
+        #[cfg(windows)]
+        match crate::win32::tray::TrayHost::new() {
+            Ok(host) => self.tray_host = Some(host),
+            Err(e) => self.notify_error(e.context("Failed to create system tray")),
+        }
+
+        // as is the clipboard history
+        #[cfg(windows)]
+        match crate::win32::clipboard::ClipboardHost::new(self.config.clipboard_history_limit) {
+            Ok(host) => self.clipboard_host = Some(host),
+            Err(e) => self.notify_error(e.context("Failed to monitor clipboard")),
+        }
+
+        #[cfg(windows)]
+        self.register_hotkeys();
+
+        Ok(())
+    }
+
+    fn add_application(&mut self, event_loop: &ActiveEventLoop, monitor: MonitorHandle, panels: Vec<Panel>, notes: Option<Notes>) -> Result<()> {
+        let name = monitor.name().unwrap_or_default();
+        let mut app = VeilDEApplication::new(event_loop, monitor, self.config.clone(), panels, notes, self.hooks.clone())
+            .with_context(|| format!("Failed to create VeilDE application for monitor '{name}'"))?;
+
+        app.set_do_not_disturb(self.do_not_disturb);
+
+        if self.locked {
+            app.lock()?;
+        }
+
+        if self.screensaver_idle.is_some() {
+            app.start_screensaver()?;
+        }
+
+        self.applications.insert(app.contexts.window.id(), app);
+
+        Ok(())
+    }
+
+    /// Follows monitors being plugged in, unplugged or changing
+    /// resolution, as winit has no events for any of these.
+    fn sync_monitors(&mut self, event_loop: &ActiveEventLoop) -> Result<()> {
+        let monitors = self.target_monitors(event_loop);
+
+        let unplugged = self.applications
+            .iter()
+            .filter(|(_, app)| !monitors.contains(&app.contexts.monitor))
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        for id in unplugged {
+            let Some(mut app) = self.applications.remove(&id) else {
+                continue;
+            };
+
+            log::info!("Monitor '{}' was disconnected", app.contexts.monitor.name().unwrap_or_default());
+
+            app.make_current()?;
+            app.shutdown().context("Failed to shutdown VeilDE application")?;
+            self.panels.append(&mut app.panels);
+            self.notes = self.notes.take().or(app.notes.take());
+        }
+
+        for monitor in monitors {
+            let existing = self.applications.values_mut().find(|app| app.contexts.monitor == monitor);
+
+            match existing {
+                Some(app) => {
+                    app.make_current()?;
+                    app.update_monitor(monitor).context("Failed to follow monitor changes")?;
+                }
+
+                None => {
+                    log::info!("Monitor '{}' was connected", monitor.name().unwrap_or_default());
+                    self.add_application(event_loop, monitor, Vec::new(), None)?;
+                }
+            }
+        }
+
+        // panels from an unplugged monitor move to the
+        // primary one, or wait for a monitor to show up
+        if !self.panels.is_empty() || self.notes.is_some() {
+            let primary = event_loop.primary_monitor();
+            let id = self.applications
+                .iter()
+                .find(|(_, app)| primary.as_ref() == Some(&app.contexts.monitor))
+                .or_else(|| self.applications.iter().next())
+                .map(|(id, _)| *id);
+
+            if let Some(app) = id.and_then(|id| self.applications.get_mut(&id)) {
+                app.panels.append(&mut self.panels);
+                app.notes = app.notes.take().or(self.notes.take());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the window's position and size to the config file,
+    /// so the next windowed launch opens where this one closed.
+    fn save_window_geometry(&self) -> Result<()> {
+        let Some(app) = self.applications.values().next().filter(|_| self.config.windowed) else {
+            return Ok(());
+        };
+
+        self.edit_config_file(|config| {
+            config.window_size = [app.resolution.width, app.resolution.height];
+            config.window_position = app.window_position.or(config.window_position);
+        })
+    }
+
+    /// Changes the config file with `edit`, leaving the rest of it as it was.
+    fn edit_config_file(&self, edit: impl FnOnce(&mut Config)) -> Result<()> {
+        let path = self.config_path.as_ref().context("Config file location is unknown")?;
+
+        // the file rather than `self.config`, which has command
+        // line, script and IPC overrides and unsaved settings
+        let mut config = Config::load_from(path)?;
+        edit(&mut config);
+        config.save(path)
+    }
+
+    /// Applies a command sent from another process.
+    fn run_command(&mut self, command: IpcCommand) -> Result<()> {
+        // e.g. from a hotkey, which would reach around the lock screen
+        if self.locked && command != IpcCommand::LockScreen {
+            bail!("The screen is locked");
+        }
+
+        match command {
+            IpcCommand::SetWallpaper { path } => {
+                let config = Config { wallpaper_path: Some(path), ..self.config.clone() };
+                self.apply_config(config)
+            }
+
+            IpcCommand::ReloadConfig => {
+                let path = self.config_path.as_ref().context("Config file location is unknown")?;
+                let config = self.with_script(Config::load_from(path)?);
+                self.apply_config(config)
+            }
+
+            IpcCommand::ToggleTaskbar => {
+                let config = Config { taskbar_auto_hide: !self.config.taskbar_auto_hide, ..self.config.clone() };
+                self.apply_config(config)
+            }
+
+            IpcCommand::ToggleLauncher => {
+                let app = self.cursor_application().context("No monitor to open the launcher on")?;
+                app.launcher.toggle();
+                app.contexts.window.focus_window(); // for typing into the search
+                app.contexts.window.request_redraw();
+
+                Ok(())
+            }
+
+            IpcCommand::ToggleCommandPalette => {
+                let app = self.cursor_application().context("No monitor to open the command palette on")?;
+                app.command_palette.toggle();
+                app.contexts.window.focus_window();
+                app.contexts.window.request_redraw();
+
+                Ok(())
+            }
+
+            IpcCommand::ToggleNightLight => {
+                for app in self.applications.values_mut() {
+                    app.night_light.toggle();
+                    app.contexts.window.request_redraw();
+                }
+
+                Ok(())
+            }
+
+            IpcCommand::ToggleDoNotDisturb => {
+                self.do_not_disturb = !self.do_not_disturb;
+                log::info!("Do not disturb {}", if self.do_not_disturb { "on" } else { "off" });
+
+                for app in self.applications.values_mut() {
+                    app.set_do_not_disturb(self.do_not_disturb);
+                    app.contexts.window.request_redraw();
+                }
+
+                Ok(())
+            }
+
+            IpcCommand::CycleLayout => {
+                let config = Config { tiling_layout: self.config.tiling_layout.next(), ..self.config.clone() };
+                log::info!("Window layout {:?}", config.tiling_layout);
+
+                self.apply_config(config)
+            }
+
+            IpcCommand::TileWindows => self.tile_windows(),
+
+            IpcCommand::Screenshot => {
+                for app in self.applications.values_mut() {
+                    app.take_screenshot();
+                }
+
+                Ok(())
+            }
+
+            IpcCommand::LockScreen => self.lock(),
+        }
+    }
+
+    /// Covers every monitor with the lock screen until the password is entered.
+    fn lock(&mut self) -> Result<()> {
+        if self.locked {
+            return Ok(());
+        }
+
+        let hash = self.config.lock_password_hash.as_deref().context("Set 'lock_password_hash' to lock the screen")?;
+
+        if !is_valid_hash(hash) {
+            bail!("'lock_password_hash' isn't a salted hash, see the hash-password command");
+        }
+
+        for app in self.applications.values_mut() {
+            app.lock()?;
+        }
+
+        self.locked = true;
+        log::info!("Screen locked");
+
+        Ok(())
+    }
+
+    fn unlock(&mut self) -> Result<()> {
+        for app in self.applications.values_mut() {
+            app.unlock()?;
+        }
+
+        self.locked = false;
+        log::info!("Screen unlocked");
+
+        Ok(())
+    }
+
+    fn start_screensaver(&mut self) -> Result<()> {
+        for app in self.applications.values_mut() {
+            app.start_screensaver()?;
+        }
+
+        self.screensaver_idle = Some(self.idle_time());
+
+        Ok(())
+    }
+
+    fn stop_screensaver(&mut self) -> Result<()> {
+        for app in self.applications.values_mut() {
+            app.stop_screensaver()?;
+        }
+
+        self.screensaver_idle = None;
+
+        Ok(())
+    }
+
+    /// Time since the last keyboard or mouse input.
+    #[cfg(windows)]
+    fn idle_time(&self) -> Duration {
+        crate::win32::idle::idle_time()
+    }
+
+    /// Time since the last keyboard or mouse input to VeilDE, as other applications' can't be seen.
+    #[cfg(not(windows))]
+    fn idle_time(&self) -> Duration {
+        self.last_input.elapsed()
+    }
+
+    /// Arranges the open windows on each monitor into the configured layout.
+    #[cfg(windows)]
+    fn tile_windows(&self) -> Result<()> {
+        for app in self.applications.values() {
+            app.tile_windows()?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(windows))]
+    fn tile_windows(&self) -> Result<()> {
+        bail!("Tiling windows is only supported on Windows");
+    }
+
+    /// The application on the monitor with the cursor, as it's likely
+    /// where the user is looking, or any other when it's elsewhere.
+    fn cursor_application(&mut self) -> Option<&mut VeilDEApplication> {
+        self.applications
+            .values_mut()
+            .max_by_key(|app| app.cursor.is_some())
+    }
+
+    /// Applies settings edited on one monitor to all of them.
+    fn apply_settings(&mut self, change: SettingsChange) -> Result<()> {
+        let config = match change {
+            SettingsChange::Apply(config) => config,
+
+            SettingsChange::Save { draft, original } => {
+                self.edit_config_file(|config| copy_edited(&original, &draft, config))?;
+                draft
+            }
+
+            SettingsChange::WindowOpacity(window_opacity) => {
+                self.edit_config_file(|config| config.window_opacity = window_opacity.clone())?;
+                Config { window_opacity, ..self.config.clone() }
+            }
+
+            #[cfg(windows)]
+            SettingsChange::RunAtStartup(enabled) => return crate::win32::startup::set_enabled(enabled),
+        };
+
+        self.apply_config(config)
+    }
+
+    /// Runs the script next to the config file over `config`.
+    fn run_config_script(&self, config: &Config) -> Result<Config> {
+        match &self.config_path {
+            Some(path) => run_script(&path.with_file_name(CONFIG_SCRIPT_PATH), config),
+            None => Ok(config.clone()),
+        }
+    }
+
+    /// `config` with its script run, or unchanged when the script fails,
+    /// so a broken script falls back to the static config.
+    fn with_script(&mut self, config: Config) -> Config {
+        match self.run_config_script(&config) {
+            Ok(scripted) => scripted,
+
+            Err(e) => {
+                self.notify_error(e);
+                config
+            }
+        }
+    }
+
+    /// Shows a recoverable error on every monitor.
+    fn notify_error(&mut self, e: Error) {
+        let message = format!("{e:#}");
+
+        for app in self.applications.values_mut() {
+            app.notify_error(anyhow!(message.clone()));
+        }
+    }
+}
+
+impl VeilDEApplication {
+    pub fn new(event_loop: &ActiveEventLoop, monitor: MonitorHandle, config: Config, panels: Vec<Panel>, notes: Option<Notes>, hooks: Rc<RefCell<FrameHooks>>) -> Result<Self> {
+        let video_mode = best_video_mode(&monitor)?;
+        let resolution = video_mode.size();
+        let size = if config.windowed {
+            PhysicalSize::new(config.window_size[0], config.window_size[1])
+        } else {
+            PhysicalSize::new(resolution.width + FULLSCREEN_OVERSCAN, resolution.height + FULLSCREEN_OVERSCAN)
+        };
+        let position = if config.windowed {
+            window_position(event_loop, &monitor, config.window_position)
+        } else {
+            monitor.position()
+        };
+        let (window, gl_config) = init_glutin(event_loop, &config.window_title, size, position, config.windowed)?;
+        let resolution = if config.windowed { window.inner_size() } else { resolution };
+        let (opengl, surface) = init_opengl(&window, &gl_config, config.window_size, config.gl_debug)?;
+
+        // the rest takes a moment, the desktop starts without it
+        let splash = Splash::new(&opengl).inspect_err(|e| log::warn!("{e:?}")).ok();
+        let splash_size = window.inner_size();
+        let show_splash = |opengl: &OpenGlContext, surface: &Surface<WindowSurface>, progress: f32| {
+            let Some(splash) = &splash else {
+                return;
+            };
+
+            if let Err(e) = splash.show(opengl, surface, splash_size, progress) {
+                log::warn!("{:?}", e.context("Failed to show splash"));
+            }
+        };
+
+        show_splash(&opengl, &surface, 0f32);
+
+        let fonts = FontFiles::load(&config)?;
+
+        show_splash(&opengl, &surface, 0.25f32);
+
+        let mut imgui = init_imgui(&fonts, config.font_size, monitor.scale_factor())?;
+        let default_style = *imgui.style();
+        apply_theme(imgui.style_mut(), &default_style, &theme_name(&config)).context("Failed to apply theme")?;
+        let glow = init_glow(&opengl, &mut imgui, config.gl_debug)?;
+        let winit = init_winit(&mut imgui, &window)?;
+
+        init_swap_interval(&opengl, &surface)?;
+        show_splash(&opengl, &surface, 0.5f32);
+
+        let contexts = VeilDEContexts {
+            monitor,
+            video_mode,
+            glow,
+            imgui,
+            opengl,
+            gl_config,
+            winit,
+            window,
+            surface,
+        };
+
+        let mut renderer = VeilDERenderer::new(contexts.glow.gl_context()).context("Failed to create VeilDE renderer")?;
+
+        if let Some(path) = &config.wallpaper_path {
+            renderer.load_wallpaper(resolve_path(path)?, config.wallpaper_mode).context("Failed to load wallpaper")?;
+        }
+
+        show_splash(&contexts.opengl, &contexts.surface, 0.75f32);
+
+        let widgets = create_widgets(&config, contexts.glow.gl_context());
+        let about = About::new(contexts.glow.gl_context(), &contexts.video_mode);
+
+        show_splash(&contexts.opengl, &contexts.surface, 1f32);
+
+        // the first frame replaces it
+        drop(splash);
+
+        let mut app = Self {
+            renderer,
+            slideshow: None,
+            night_light: NightLight::new(),
+            contexts,
+            last_frame: None,
+            start: Instant::now(),
+            resolution,
+            window_position: None,
+            fonts,
+            default_style,
+            accent_color: accent_color(&config),
+            widgets,
+            launcher: Launcher::new(),
+            command_palette: CommandPalette::new(),
+            notifications: Notifications::new(),
+            do_not_disturb: false,
+            cursor: None,
+            taskbar_reveal: 1f32,
+            screenshot_region: None,
+            region_capture: None,
+            color_picker: None,
+            fps_overlay: None,
+            lock_screen: None,
+            unlocked: false,
+            screensaver: None,
+            log_console: LogConsole::new(),
+            about,
+            settings: Settings::new(),
+            settings_change: None,
+            command: None,
+            pending_frames: 0,
+            occluded: false,
+            failed_recoveries: 0,
+            panels,
+            notes,
+            hooks,
+            config,
+        };
+
+        // the desktop is usable without these
+        if let Err(e) = app.start_slideshow() {
+            app.notify_error(e);
+        }
+
+        if let Err(e) = app.night_light.apply_config(&app.config) {
+            app.notify_error(e);
+        }
+
+        Ok(app)
+    }
+
+    /// Makes this application's OpenGL context current, as
+    /// every monitor renders through its own context.
+    pub fn make_current(&self) -> Result<()> {
+        if !self.contexts.opengl.is_current() {
+            self.contexts.opengl
+                .make_current(&self.contexts.surface)
+                .context("Failed to make OpenGL context current")?;
+        }
+
+        Ok(())
+    }
+
+    pub fn pre_window_event(&mut self, event: &WindowEvent) {
+        forward_window_event(
+            &mut self.contexts.winit,
+            self.contexts.imgui.io_mut(),
+            &self.contexts.window,
+            event
+        );
+    }
+
+    pub fn post_window_event(&mut self, event: &WindowEvent) {
+        // `Occluded(false)` asks for the next frame
+        if self.occluded {
+            return;
+        }
+
+        match (self.config.redraw_mode, event) {
+            (RedrawMode::Continuous, _) => self.contexts.window.request_redraw(),
+
+            (RedrawMode::Reactive, WindowEvent::RedrawRequested) => {
+                if self.pending_frames > 0 {
+                    self.pending_frames -= 1;
+                    self.contexts.window.request_redraw();
+                }
+            }
+
+            (RedrawMode::Reactive, _) => {
+                self.pending_frames = REACTIVE_EXTRA_FRAMES;
+                self.contexts.window.request_redraw();
+            }
+        }
+    }
+
+    pub fn resize(&mut self, size: PhysicalSize<u32>) -> Result<()> {
+        // minimized windows report a zero size
+        let (Some(width), Some(height)) = (NonZeroU32::new(size.width), NonZeroU32::new(size.height)) else {
+            return Ok(());
+        };
+
+        self.contexts.surface.resize(&self.contexts.opengl, width, height);
+        self.resolution = size;
+
+        self.renderer.gl().viewport(0, 0, size.width as i32, size.height as i32)?;
+
+        Ok(())
+    }
+
+    pub fn rebuild_fonts(&mut self, scale: f64) -> Result<()> {
+        let glyph_offset = build_fonts(&mut self.contexts.imgui, &self.fonts, self.config.font_size, scale)?;
+
+        log::debug!("Calculated glyph offset: {glyph_offset}");
+
+        // the renderer uploads the font
+        // atlas texture on creation only
+        self.contexts.glow = init_glow(&self.contexts.opengl, &mut self.contexts.imgui, self.config.gl_debug)?;
+
+        Ok(())
+    }
+
+    /// Applies everything in `config` that can change while running.
+    pub fn apply_config(&mut self, config: Config) -> Result<()> {
+        let previous = std::mem::replace(&mut self.config, config);
+
+        if self.config.widgets != previous.widgets {
+            self.widgets = create_widgets(&self.config, self.contexts.glow.gl_context());
+        }
+
+        for widget in &mut self.widgets {
+            widget.apply_config(&self.config);
+        }
+
+        if self.config.window_title != previous.window_title {
+            self.contexts.window.set_title(&self.config.window_title);
+        }
+
+        let fonts_changed = self.config.font_path != previous.font_path
+            || self.config.icon_font_path != previous.icon_font_path
+            || self.config.fallback_fonts != previous.fallback_fonts;
+
+        if fonts_changed {
+            self.fonts = FontFiles::load(&self.config)?;
+        }
+
+        if fonts_changed || self.config.font_size != previous.font_size {
+            self.rebuild_fonts(self.contexts.window.scale_factor()).context("Failed to rebuild fonts")?;
+        }
+
+        // also when only the theme file changed
+        self.refresh_theme()?;
+
+        if self.config.wallpaper_path != previous.wallpaper_path || self.config.wallpaper_mode != previous.wallpaper_mode {
+            match &self.config.wallpaper_path {
+                Some(path) => self.renderer
+                    .load_wallpaper(resolve_path(path)?, self.config.wallpaper_mode)
+                    .context("Failed to load wallpaper")?,
+
+                None => self.renderer.clear_wallpaper(),
+            }
+        }
+
+        let slideshow_changed = self.config.slideshow_directory != previous.slideshow_directory
+            || self.config.slideshow_interval_seconds != previous.slideshow_interval_seconds;
+
+        // a new wallpaper replaced the slideshow's image
+        if slideshow_changed || self.config.wallpaper_path != previous.wallpaper_path || self.config.wallpaper_mode != previous.wallpaper_mode {
+            self.start_slideshow()?;
+        }
+
+        self.night_light.apply_config(&self.config)
+    }
+
+    /// Shows the first image of the configured slideshow directory over the
+    /// wallpaper, or stops the slideshow when there's no directory anymore.
+    fn start_slideshow(&mut self) -> Result<()> {
+        self.slideshow = None;
+
+        let Some(directory) = &self.config.slideshow_directory else {
+            return Ok(());
+        };
+
+        let interval = Duration::from_secs(self.config.slideshow_interval_seconds);
+        let slideshow = Slideshow::new(&resolve_path(directory)?, interval)?;
+
+        // the wallpaper or background shader stays
+        let Some(path) = slideshow.current() else {
+            bail!("Slideshow directory '{directory}' has no images");
+        };
+
+        self.renderer.load_wallpaper(path, self.config.wallpaper_mode).context("Failed to load slideshow image")?;
+        self.slideshow = Some(slideshow);
+
+        Ok(())
+    }
+
+    /// Moves and resizes the window when `monitor` changed resolution or position.
+    pub fn update_monitor(&mut self, monitor: MonitorHandle) -> Result<()> {
+        let position = monitor.position();
+        let moved = self.contexts.window.outer_position().is_ok_and(|current| current != position);
+
+        // a window is left where the user put it
+        if monitor.size() == self.contexts.video_mode.size() && (!moved || self.config.windowed) {
+            return Ok(());
+        }
+
+        let video_mode = best_video_mode(&monitor)?;
+        let size = video_mode.size();
+
+        log::info!("Monitor '{}' changed to {}x{}", monitor.name().unwrap_or_default(), size.width, size.height);
+
+        // the surface follows in the `Resized` event
+        if !self.config.windowed {
+            self.contexts.window.set_outer_position(position);
+            let _ = self.contexts.window.request_inner_size(
+                PhysicalSize::new(size.width + FULLSCREEN_OVERSCAN, size.height + FULLSCREEN_OVERSCAN)
+            );
+        }
+
+        self.about = About::new(self.contexts.glow.gl_context(), &video_mode);
+        self.contexts.monitor = monitor;
+        self.contexts.video_mode = video_mode;
+
+        Ok(())
+    }
+
+    /// Recreates the OpenGL context and everything living in it after `e`
+    /// failed a frame, giving up once `GL_RECOVERY_ATTEMPTS` in a row fail.
+    pub fn recover(&mut self, e: Error) -> Result<()> {
+        if !is_recoverable(&e) {
+            return Err(e);
+        }
+
+        self.failed_recoveries += 1;
+
+        if self.failed_recoveries > GL_RECOVERY_ATTEMPTS {
+            return Err(e.context(format!("Failed to recover after {GL_RECOVERY_ATTEMPTS} attempts")));
+        }
+
+        log::warn!(
+            "{e:?}\nRecreating OpenGL context (attempt {}/{GL_RECOVERY_ATTEMPTS})",
+            self.failed_recoveries
+        );
+
+        // a failed attempt leaves the old context in
+        // place, the next frame fails and tries again
+        if let Err(e) = self.recreate_context() {
+            log::error!("{:?}", e.context("Failed to recreate OpenGL context"));
+        }
+
+        Ok(())
+    }
+
+    fn recreate_context(&mut self) -> Result<()> {
+        // widgets delete their textures when dropped, which
+        // has to happen before another context is current
+        self.widgets.clear();
+
+        let (opengl, surface) = init_opengl(
+            &self.contexts.window,
+            &self.contexts.gl_config,
+            self.config.window_size,
+            self.config.gl_debug
+        )?;
+        let glow = init_glow(&opengl, &mut self.contexts.imgui, self.config.gl_debug)?;
+
+        init_swap_interval(&opengl, &surface)?;
+
+        let mut renderer = VeilDERenderer::new(glow.gl_context()).context("Failed to create VeilDE renderer")?;
+
+        if let Some(path) = &self.config.wallpaper_path {
+            renderer.load_wallpaper(resolve_path(path)?, self.config.wallpaper_mode).context("Failed to load wallpaper")?;
+        }
+
+        if let Some(path) = self.slideshow.as_ref().and_then(Slideshow::current) {
+            renderer.load_wallpaper(path, self.config.wallpaper_mode).context("Failed to load slideshow image")?;
+        }
+
+        // the old renderer would delete its objects in the
+        // new context on drop, and they died with the old one
+        std::mem::forget(std::mem::replace(&mut self.contexts.glow, glow));
+
+        self.contexts.opengl = opengl;
+        self.contexts.surface = surface;
+        self.renderer = renderer;
+        self.widgets = create_widgets(&self.config, self.contexts.glow.gl_context());
+
+        self.resize(self.contexts.window.inner_size())?;
+
+        Ok(())
+    }
+
+    /// Arranges the open windows on this monitor into the configured layout, around the taskbar.
+    #[cfg(windows)]
+    pub fn tile_windows(&self) -> Result<()> {
+        let (position, size) = self.work_area();
+        let origin = self.contexts.monitor.position();
+        let area = [origin.x + position[0] as i32, origin.y + position[1] as i32, size[0] as i32, size[1] as i32];
+
+        crate::win32::tiling::tile_windows(
+            &self.contexts.monitor,
+            self.config.tiling_layout,
+            area,
+            self.config.tiling_gap.max(0)
+        )
+    }
+
+    pub fn set_do_not_disturb(&mut self, do_not_disturb: bool) {
+        self.do_not_disturb = do_not_disturb;
+        self.notifications.set_do_not_disturb(do_not_disturb);
+    }
+
+    /// Raises the window above other applications while the lock screen
+    /// or screensaver covers it, and back to the bottom once neither does.
+    fn update_window_level(&self) -> Result<()> {
+        if self.config.windowed {
+            return Ok(());
+        }
+
+        if self.lock_screen.is_some() || self.screensaver.is_some() {
+            self.contexts.window.set_window_level(WindowLevel::AlwaysOnTop);
+            self.contexts.window.focus_window();
+        } else {
+            self.contexts.window.set_window_level(WindowLevel::AlwaysOnBottom);
+
+            #[cfg(windows)]
+            crate::win32::send_to_bottom(&self.contexts.window).context("Failed to keep window at the bottom")?;
+        }
+
+        self.contexts.window.request_redraw();
+
+        Ok(())
+    }
+
+    pub fn lock(&mut self) -> Result<()> {
+        self.lock_screen = Some(LockScreen::new());
+        self.update_window_level()
+    }
+
+    pub fn unlock(&mut self) -> Result<()> {
+        self.lock_screen = None;
+        self.update_window_level()
+    }
+
+    pub fn start_screensaver(&mut self) -> Result<()> {
+        self.screensaver = Some(Screensaver::new());
+        self.update_window_level()
+    }
+
+    pub fn stop_screensaver(&mut self) -> Result<()> {
+        self.screensaver = None;
+        self.update_window_level()
+    }
+
+    pub fn toggle_fps_overlay(&mut self) {
+        self.fps_overlay = match self.fps_overlay {
+            Some(_) => None,
+            None => Some(FpsOverlay::new()),
+        };
+    }
+
+    pub fn take_screenshot(&mut self) {
+        self.screenshot_region = Some(([0, 0], [self.resolution.width, self.resolution.height]));
+        self.contexts.window.request_redraw();
+    }
+
+    pub fn toggle_region_capture(&mut self) {
+        self.region_capture = match self.region_capture {
+            Some(_) => None,
+            None => Some(RegionCapture::new()),
+        };
+    }
+
+    pub fn toggle_color_picker(&mut self) {
+        self.color_picker = match self.color_picker {
+            Some(_) => None,
+            None => Some(ColorPicker::new()),
+        };
+    }
+
+    /// Starts or finishes the region capture drag, if selecting.
+    pub fn region_capture_input(&mut self, state: ElementState) {
+        let (Some(capture), Some(cursor)) = (&mut self.region_capture, self.cursor) else {
+            return;
+        };
+
+        if state == ElementState::Pressed {
+            capture.begin(cursor);
+            return;
+        }
+
+        let Some((position, size)) = capture.selection(cursor) else {
+            return;
+        };
+
+        self.region_capture = None;
+
+        // clicks without dragging select nothing
+        let (width, height) = (size[0].round() as u32, size[1].round() as u32);
+
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let x = (position[0].max(0f32) as u32).min(self.resolution.width - 1);
+        let y = (position[1].max(0f32) as u32).min(self.resolution.height - 1);
+
+        // the overlay is gone by the time the next frame is captured
+        self.screenshot_region = Some((
+            [x, y],
+            [width.min(self.resolution.width - x), height.min(self.resolution.height - y)]
+        ));
+        self.contexts.window.request_redraw();
+    }
+
+    /// Applies the configured theme, or the system's when following it.
+    pub fn refresh_theme(&mut self) -> Result<()> {
+        apply_theme(self.contexts.imgui.style_mut(), &self.default_style, &theme_name(&self.config))
+            .context("Failed to apply theme")?;
+
+        self.accent_color = accent_color(&self.config);
+
+        Ok(())
+    }
+
+    /// Asks for an image to use as this monitor's wallpaper until the config is reloaded.
+    fn change_wallpaper(&mut self) -> Result<()> {
+        let path = DialogBuilder::file()
+            .set_title("Change wallpaper")
+            .add_filter("Images", ["png", "jpg", "jpeg"])
+            .open_single_file()
+            .show()
+            .context("Failed to show file dialog")?;
+
+        let Some(path) = path else {
+            return Ok(());
+        };
+
+        self.renderer.load_wallpaper(&path, self.config.wallpaper_mode)?;
+        self.config.wallpaper_path = Some(path.to_string_lossy().into_owned());
+        self.slideshow = None; // or it'd be replaced at the next image
+
+        Ok(())
+    }
+
+    /// Reloads the shaders, wallpaper, slideshow images and theme from disk.
+    fn refresh(&mut self) -> Result<()> {
+        self.renderer.reload_shaders()?;
+
+        if let Some(path) = &self.config.wallpaper_path {
+            self.renderer.load_wallpaper(resolve_path(path)?, self.config.wallpaper_mode).context("Failed to load wallpaper")?;
+        }
+
+        self.start_slideshow()?;
+
+        self.refresh_theme()
+    }
+
+    /// Shows a recoverable error to the user, keeping the desktop running.
+    pub fn notify_error(&mut self, e: Error) {
+        self.notifications.push(e);
+    }
+
+    pub fn shutdown(&mut self) -> Result<()> {
+        self.renderer.shutdown();
+
+        Ok(())
+    }
+
+    fn gui(&mut self) -> Result<()> {
+        // keyboard and mouse events are forwarded to
+        // imgui by `WinitPlatform` in `pre_window_event`,
+        // this only applies requested cursor movement
+        self.contexts.winit
+            .prepare_frame(self.contexts.imgui.io_mut(), &self.contexts.window)
+            .context("Failed to prepare imgui frame")?;
+
+        // nothing else is drawn or takes input while locked
+        if let Some(lock_screen) = &mut self.lock_screen {
+            let size = [self.resolution.width as f32, self.resolution.height as f32];
+            let hash = self.config.lock_password_hash.as_deref().unwrap_or_default();
+            let ui = self.contexts.imgui.new_frame();
+
+            self.unlocked = lock_screen.render(ui, size, hash);
+            self.contexts.winit.prepare_render(ui, &self.contexts.window);
+
+            return Ok(());
+        }
+
+        let dt = Duration::from_secs_f32(self.contexts.imgui.io().delta_time);
+
+        for widget in &mut self.widgets {
+            widget.update(dt);
+        }
+
+        self.update_taskbar_reveal(dt);
+
+        // before the frame borrows the imgui context
+        let (position, size) = self.taskbar_rect();
+        let (launcher_anchor, launcher_pivot) = self.launcher_anchor();
+        let (work_position, work_size) = self.work_area();
+
+        let ui = self.contexts.imgui.new_frame();
+
+        // run once the frame is built, with
+        // where it was chosen (e.g. for notes)
+        let mut chosen: Option<(Action, [f32; 2])> = None;
+
+        // a starting point for custom panels
+        ui.window("VeilDE")
+            .always_auto_resize(true)
+            .collapsible(false)
+            .build(|| -> Result<()> {
+                let uptime = (Instant::now() - self.start).as_secs();
+
+                ui.text(format!("{}x{}", self.resolution.width, self.resolution.height));
+                ui.text(format!("{:.0} FPS", ui.io().framerate));
+                ui.text(format!("{}:{:02}:{:02}", uptime / 3600, uptime / 60 % 60, uptime % 60));
+
+                // for trying out error handling, release
+                // builds can't end the session this way
+                #[cfg(debug_assertions)]
+                if ui.button("Fail") {
+                    bail!("Failed on purpose");
+                }
+
+                Ok(())
+            }).unwrap_or(Ok(()))?;
+
+        // popups opened from the taskbar
+        // are built inside it, so they're
+        // accented as well
+        let accent = self.accent_color.map(|Color(accent)| {
+            let faded = [accent[0], accent[1], accent[2], accent[3] * 0.4];
+            let darkened = [accent[0] * 0.8, accent[1] * 0.8, accent[2] * 0.8, accent[3]];
+
+            [
+                ui.push_style_color(StyleColor::Button, faded),
+                ui.push_style_color(StyleColor::ButtonHovered, accent),
+                ui.push_style_color(StyleColor::ButtonActive, darkened),
+                ui.push_style_color(StyleColor::Header, faded),
+                ui.push_style_color(StyleColor::HeaderHovered, accent),
+                ui.push_style_color(StyleColor::HeaderActive, darkened),
+                ui.push_style_color(StyleColor::CheckMark, accent),
+                ui.push_style_color(StyleColor::SliderGrab, accent),
+                ui.push_style_color(StyleColor::SliderGrabActive, darkened),
+                ui.push_style_color(StyleColor::PlotHistogram, accent), // progress bars
+            ]
+        });
+
+        // over the blurred backdrop, if any
+        ui.window("_taskbar")
+            .size(size, Condition::Always)
+            .bg_alpha(self.config.taskbar_opacity.clamp(0f32, 1f32))
+            .title_bar(false)
+            .resizable(false)
+            .movable(false)
+            .position(position, Condition::Always)
+            .build(|| {
+                let vertical = self.config.taskbar_position.is_vertical();
+                let visible = self.widgets.iter().filter(|widget| widget.visible()).count();
+
+                // the start button, then a column per widget
+                if !vertical {
+                    ui.columns(visible as i32 + 1, "_taskbar_columns", true);
+                }
+
+                self.launcher.render_button(ui);
+
+                if ui.is_item_clicked_with_button(MouseButton::Right) {
+                    ui.open_popup(TASKBAR_MENU_ID);
+                }
+
+                ui.same_line();
+
+                let (icon, fallback) = if self.do_not_disturb { (ICON_BELL_SLASH, tr("DND")) } else { (ICON_BELL, tr("Alerts")) };
+                let label = if has_glyph(ui, icon) { icon.to_string() } else { fallback.to_string() };
+
+                // every monitor, like the other toggles
+                if ui.button(format!("{label}##_do_not_disturb")) {
+                    self.command = Some(IpcCommand::ToggleDoNotDisturb);
+                }
+
+                if ui.is_item_hovered() {
+                    match (self.do_not_disturb, self.notifications.count()) {
+                        (true, 0) => ui.tooltip_text(tr("Do not disturb is on")),
+                        (true, held) => ui.tooltip_text(tr("Do not disturb is on, {held} notifications held").replace("{held}", &held.to_string())),
+                        (false, _) => ui.tooltip_text(tr("Do not disturb is off")),
+                    }
+                }
+
+                ui.popup(TASKBAR_MENU_ID, || {
+                    for action in TASKBAR_MENU_ACTIONS {
+                        if ui.menu_item(action.name(&self.config)) {
+                            chosen = Some((*action, ui.mouse_pos_on_opening_current_popup()));
+                        }
+                    }
+                });
+
+                for (index, widget) in self.widgets.iter_mut().filter(|widget| widget.visible()).enumerate() {
+                    let _dimmed = (self.do_not_disturb && !widget.essential())
+                        .then(|| ui.push_style_var(StyleVar::Alpha(DO_NOT_DISTURB_DIM_ALPHA)));
+
+                    if !vertical {
+                        ui.next_column();
+                        widget.render(ui);
+                        continue;
+                    }
+
+                    // widgets lay themselves out against their
+                    // window, so each gets a horizontal-sized slot
+                    ui.separator();
+                    ui.child_window(format!("_taskbar_widget_{index}"))
+                        .size([0f32, self.config.taskbar_height])
+                        .build(|| widget.render(ui));
+                }
+            });
+
+        drop(accent);
+
+        {
+            let _opacity = push_opacity(ui, &self.config, "Launcher");
+
+            if let Some(action) = self.launcher.render_window(ui, &self.config, launcher_anchor, launcher_pivot) {
+                chosen = Some((action, launcher_anchor));
+            }
+        }
+
+        // removed panels are dropped here, so
+        // they don't move to another monitor
+        self.panels.retain_mut(|panel| panel(ui));
+
+        // right-clicking empty space, not one of the windows above
+        if ui.is_mouse_released(MouseButton::Right) && !ui.is_window_hovered_with_flags(WindowHoveredFlags::ANY_WINDOW) {
+            ui.open_popup(DESKTOP_MENU_ID);
+        }
+
+        if let Some(notes) = &mut self.notes {
+            let _opacity = push_opacity(ui, &self.config, "Notes");
+
+            if let Err(e) = notes.render(ui) {
+                self.notifications.push(e);
+            }
+        }
+
+        // applied right away, saved once a slider is released
+        let mut save_opacity = false;
+
+        let has_notes = self.notes.is_some();
+        let available = |action: &&Action| **action != Action::NewNote || has_notes;
+
+        ui.popup(DESKTOP_MENU_ID, || {
+            for action in DESKTOP_MENU_ACTIONS.iter().filter(available) {
+                if ui.menu_item(action.name(&self.config)) {
+                    chosen = Some((*action, ui.mouse_pos_on_opening_current_popup()));
+                }
+            }
+
+            ui.menu(tr("Window opacity"), || {
+                for name in MANAGED_WINDOWS {
+                    let mut value = self.config.window_opacity(name);
+
+                    if ui.slider(name, WINDOW_OPACITY_MIN, 1f32, &mut value) {
+                        self.config.window_opacity.insert(name.to_string(), value);
+                    }
+
+                    save_opacity |= ui.is_item_deactivated_after_edit();
+                }
+            });
+
+            ui.separator();
+
+            for widget in &mut self.widgets {
+                widget.desktop_menu(ui);
+            }
+        });
+
+        if save_opacity {
+            self.settings_change = Some(SettingsChange::WindowOpacity(self.config.window_opacity.clone()));
+        }
+
+        let actions: Vec<Action> = all_actions(&self.config).into_iter().filter(|action| available(&action)).collect();
+
+        {
+            let _opacity = push_opacity(ui, &self.config, "Command palette");
+
+            if let Some(action) = self.command_palette.render(ui, &actions, &self.config) {
+                chosen = Some((action, ui.io().display_size.map(|size| size / 2f32)));
+            }
+        }
+
+        {
+            let _opacity = push_opacity(ui, &self.config, "Log console");
+            self.log_console.render(ui);
+        }
+
+        {
+            let _opacity = push_opacity(ui, &self.config, "About");
+            self.about.render(ui);
+        }
+
+        {
+            let _opacity = push_opacity(ui, &self.config, "Settings");
+
+            if let Some(change) = self.settings.render(ui) {
+                self.settings_change = Some(change);
+            }
+        }
+
+        {
+            let _opacity = push_opacity(ui, &self.config, "Notifications");
+            self.notifications.render(ui, [work_position[0] + work_size[0], work_position[1]]);
+        }
+
+        if let Some(region_capture) = &self.region_capture {
+            region_capture.render(ui, self.cursor, [self.resolution.width as f32, self.resolution.height as f32]);
+        }
+
+        let picked = self.color_picker
+            .as_ref()
+            .is_some_and(|color_picker| color_picker.render(ui, [self.resolution.width as f32, self.resolution.height as f32]));
+
+        if picked {
+            self.color_picker = None;
+        }
+
+        // last, so it stays above every other window
+        if let Some(fps_overlay) = &self.fps_overlay {
+            fps_overlay.render(ui, work_position);
+        }
+
+        // applies the cursor icon imgui requested
+        self.contexts.winit.prepare_render(ui, &self.contexts.window);
+
+        if let Some((action, position)) = chosen {
+            self.run_action(action, position)?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs an action chosen at `position`, e.g. where a menu was opened.
+    fn run_action(&mut self, action: Action, position: [f32; 2]) -> Result<()> {
+        match action {
+            Action::Settings => self.settings.open(&self.config),
+            Action::About => self.about.open(),
+
+            Action::NewNote => {
+                if let Some(notes) = &mut self.notes {
+                    notes.create(position);
+                }
+            }
+
+            Action::ChangeWallpaper => self.change_wallpaper().context("Failed to change wallpaper")?,
+            Action::Refresh => self.refresh().context("Failed to refresh desktop")?,
+            Action::ReloadConfig => self.command = Some(IpcCommand::ReloadConfig),
+            Action::ToggleTaskbar => self.command = Some(IpcCommand::ToggleTaskbar),
+            Action::ToggleNightLight => self.command = Some(IpcCommand::ToggleNightLight),
+            Action::ToggleDoNotDisturb => self.command = Some(IpcCommand::ToggleDoNotDisturb),
+            Action::CycleLayout => self.command = Some(IpcCommand::CycleLayout),
+            Action::TileWindows => self.command = Some(IpcCommand::TileWindows),
+            Action::Screenshot => self.take_screenshot(),
+            Action::LockScreen => self.command = Some(IpcCommand::LockScreen),
+            Action::RegionCapture => self.toggle_region_capture(),
+            Action::ColorPicker => self.toggle_color_picker(),
+            Action::SignOut | Action::Restart | Action::ShutDown => end_session(action)?,
+
+            // a program that won't start
+            // shouldn't end the session
+            Action::Launch(index) => {
+                if let Some(Err(e)) = self.config.launcher.get(index).map(LauncherEntry::launch) {
+                    self.notifications.push(e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Slides an auto-hidden taskbar in while the cursor is at its edge, and out after.
+    fn update_taskbar_reveal(&mut self, dt: Duration) {
+        let (position, size) = self.docked_taskbar_rect();
+        let (width, height) = (self.resolution.width as f32, self.resolution.height as f32);
+
+        let near = self.cursor.is_some_and(|cursor| {
+            let distance = match self.config.taskbar_position {
+                TaskbarPosition::Top => cursor[1],
+                TaskbarPosition::Bottom => height - cursor[1],
+                TaskbarPosition::Left => cursor[0],
+                TaskbarPosition::Right => width - cursor[0],
+            };
+
+            let inside = cursor[0] >= position[0]
+                && cursor[1] >= position[1]
+                && cursor[0] <= position[0] + size[0]
+                && cursor[1] <= position[1] + size[1];
 
-#[allow(unused)] // contexts are all important, even if not currently used
-struct VeilDEContexts {
-    pub monitor: MonitorHandle,
-    pub video_mode: VideoModeHandle,
-    pub imgui: ImGuiContext,
-    pub winit: WinitPlatform,
-    pub window: Window,
-    pub opengl: OpenGlContext,
-    pub glow: AutoRenderer,
-    pub surface: Surface<WindowSurface>,
-}
+            // once sliding in, the whole taskbar
+            // keeps it open so clicks land on it
+            distance <= TASKBAR_REVEAL_DISTANCE || (self.taskbar_reveal > 0f32 && inside)
+        });
 
-struct VeilDEApplication {
-    contexts: VeilDEContexts,
-    renderer: VeilDERenderer,
-    last_frame: Option<Instant>,
-    resolution: PhysicalSize<u32>,
-}
+        // held open while it's being used
+        let in_use = self.launcher.is_open() || self.contexts.imgui.io().mouse_down.iter().any(|down| *down);
 
-struct VeilDEApplicationHandler {
-    application: Option<VeilDEApplication>,
-    error_tx: Sender<Error>,
-}
+        let target = if !self.config.taskbar_auto_hide || near || in_use { 1f32 } else { 0f32 };
+        let step = dt.as_secs_f32() * self.config.taskbar_slide_speed;
 
-impl VeilDEApplicationHandler {
-    pub fn new(error_tx: Sender<Error>) -> Self {
-        Self {
-            application: None,
-            error_tx,
-        }
+        self.taskbar_reveal = if target > self.taskbar_reveal {
+            (self.taskbar_reveal + step).min(target)
+        } else {
+            (self.taskbar_reveal - step).max(target)
+        };
     }
-}
 
-impl VeilDEApplication {
-    pub fn new(event_loop: &ActiveEventLoop) -> Result<Self> {
-        let monitor = event_loop
-            .primary_monitor()
-            .or_else(
-                || event_loop
-                    .available_monitors()
-                    .next()
-            ).context("Failed to get monitor")?;
-
-        let video_mode = monitor.video_modes().next().context("Failed to get video mode")?;
-        let resolution = video_mode.size();
-        let (window, config) = init_glutin(
-            event_loop,
-            LogicalSize::new(resolution.width + 1, resolution.height + 1),
-            monitor.position()
-        )?;
-        let (opengl, surface) = init_opengl(&window, &config)?;
-        let mut imgui = init_imgui()?;
-        let glow = init_glow(&opengl, &mut imgui)?;
-        let winit = init_winit(&mut imgui, &window)?;
+    /// The taskbar's position and size in pixels, while fully shown.
+    fn docked_taskbar_rect(&self) -> ([f32; 2], [f32; 2]) {
+        let (width, height) = (self.resolution.width as f32, self.resolution.height as f32);
+        let (taskbar_width, taskbar_height) = (self.config.taskbar_width, self.config.taskbar_height);
+
+        match self.config.taskbar_position {
+            TaskbarPosition::Top => ([0f32, 0f32], [width, taskbar_height]),
+            TaskbarPosition::Bottom => ([0f32, height - taskbar_height], [width, taskbar_height]),
+            TaskbarPosition::Left => ([0f32, 0f32], [taskbar_width, height]),
+            TaskbarPosition::Right => ([width - taskbar_width, 0f32], [taskbar_width, height]),
+        }
+    }
 
-        surface.set_swap_interval(
-            &opengl,
-            SwapInterval::Wait(
-                NonZeroU32::new(1)
-                    .context("Swap interval was zero or out-of-bounds")?
-            )
-        ).context("Failed to set swap interval")?;
+    /// The taskbar's position and size in pixels, slid off screen as far as it's hidden.
+    fn taskbar_rect(&self) -> ([f32; 2], [f32; 2]) {
+        let (position, size) = self.docked_taskbar_rect();
+        let thickness = if self.config.taskbar_position.is_vertical() { size[0] } else { size[1] };
+        let hidden = (thickness - TASKBAR_HIDDEN_SIZE).max(0f32) * (1f32 - self.taskbar_reveal);
 
-        let contexts = VeilDEContexts {
-            monitor,
-            video_mode,
-            glow,
-            imgui,
-            opengl,
-            winit,
-            window,
-            surface,
+        let position = match self.config.taskbar_position {
+            TaskbarPosition::Top => [position[0], position[1] - hidden],
+            TaskbarPosition::Bottom => [position[0], position[1] + hidden],
+            TaskbarPosition::Left => [position[0] - hidden, position[1]],
+            TaskbarPosition::Right => [position[0] + hidden, position[1]],
         };
 
-        Ok(
-            Self {
-                renderer: VeilDERenderer::new(contexts.glow.gl_context()).context("Failed to create VeilDE renderer")?,
-                contexts,
-                last_frame: None,
-                resolution
-            }
-        )
+        (position, size)
     }
 
-    pub fn pre_window_event(&mut self, event: &WindowEvent) {
-        self.contexts.winit.handle_event::<WindowEvent>(
-            self.contexts.imgui.io_mut(),
-            &self.contexts.window,
-            &Event::WindowEvent {
-                window_id: self.contexts.window.id(),
-                event: event.clone()
-            },
-        );
+    /// The rest of the screen, where windows anchored to a corner go.
+    fn work_area(&self) -> ([f32; 2], [f32; 2]) {
+        let (width, height) = (self.resolution.width as f32, self.resolution.height as f32);
+        let (_, size) = self.docked_taskbar_rect();
+
+        // an auto-hidden taskbar only slides over it
+        let size = if self.config.taskbar_auto_hide { [TASKBAR_HIDDEN_SIZE; 2] } else { size };
+
+        match self.config.taskbar_position {
+            TaskbarPosition::Top => ([0f32, size[1]], [width, height - size[1]]),
+            TaskbarPosition::Bottom => ([0f32, 0f32], [width, height - size[1]]),
+            TaskbarPosition::Left => ([size[0], 0f32], [width - size[0], height]),
+            TaskbarPosition::Right => ([0f32, 0f32], [width - size[0], height]),
+        }
     }
 
-    pub fn post_window_event(&mut self, _: &WindowEvent) {
-        self.contexts.window.request_redraw();
+    /// Where the launcher opens from, next to the start button, and its pivot.
+    fn launcher_anchor(&self) -> ([f32; 2], [f32; 2]) {
+        let (position, size) = self.taskbar_rect();
+
+        match self.config.taskbar_position {
+            TaskbarPosition::Top => ([0f32, size[1]], [0f32, 0f32]),
+            TaskbarPosition::Bottom => (position, [0f32, 1f32]),
+            TaskbarPosition::Left => ([size[0], 0f32], [0f32, 0f32]),
+            TaskbarPosition::Right => (position, [1f32, 0f32]),
+        }
     }
 
-    pub fn shutdown(&mut self) -> Result<()> {
-        self.renderer.shutdown();
+    /// Sleeps out the rest of the frame when a target frame rate is configured,
+    /// for drivers that don't honor the swap interval.
+    fn limit_frame_rate(&self) {
+        let (Some(target_fps), Some(last_frame)) = (self.config.target_fps, self.last_frame) else {
+            return;
+        };
 
-        Ok(())
+        let frame_time = Duration::from_secs(1) / target_fps.max(1);
+        let elapsed = last_frame.elapsed();
+
+        if elapsed < frame_time {
+            std::thread::sleep(frame_time - elapsed);
+        }
     }
 
-    fn gui(&mut self) -> Result<()> {
-        let ui = self.contexts.imgui.new_frame();
+    /// Draws the screensaver instead of the desktop.
+    fn render_screensaver(&mut self, frame_time: Duration) -> Result<()> {
+        let Some(screensaver) = &mut self.screensaver else {
+            return Ok(());
+        };
 
-        ui.window("VeilDE")
-            .size([72f32, 56f32], Condition::Always)
-            .resizable(false)
-            .collapsible(false)
-            .build(|| -> Result<()> {
-                if ui.button("blow up") {
-                    bail!("boom");
-                }
+        screensaver.update(frame_time);
 
-                Ok(())
-            }).unwrap_or(Ok(()))?;
+        // widgets keep counting (e.g. a running
+        // timer) without being drawn, so they're
+        // where they should be once it stops
+        for widget in &mut self.widgets {
+            widget.update(frame_time);
+        }
 
-        let size = [self.resolution.width as f32, TASKBAR_HEIGHT];
-        let position = [0f32, self.resolution.height as f32 - TASKBAR_HEIGHT];
+        let resolution = [self.resolution.width as f32, self.resolution.height as f32];
 
-        ui.window("_taskbar")
-            .size(size, Condition::Always)
-            .title_bar(false)
-            .resizable(false)
-            .movable(false)
-            .position(position, Condition::Always)
-            .build(|| -> Result<()> {
-                let now = Local::now();
-
-                let time = now.format(TIME_FORMAT).to_string();
-                let date = now.format(DATE_FORMAT).to_string();
-                let display = format!("{time}\n{date}");
-
-                ui.columns(3, "_taskbar_columns", true);
-                ui.text("column 1");
-                ui.next_column();
-                ui.text("column 2");
-                ui.next_column();
-                /*
-                let pos = ui.cursor_pos();
-                let size = ui.calc_text_size(display);
-                ui.set_cursor_pos([self.resolution.width as f32 - size[0] - 5f32, TASKBAR_HEIGHT - size[1] - 10f32]);
-                 */
-                ui.text(display);
+        self.renderer
+            .draw_screensaver(resolution, screensaver.time(), screensaver.dim())
+            .context("Failed to draw screensaver")?;
+
+        self.contexts.surface
+            .swap_buffers(&self.contexts.opengl)
+            .context("Failed to swap surface buffers")?;
+
+        // nothing changes once it's black
+        if screensaver.dim() < 1f32 {
+            self.contexts.window.request_redraw();
+        }
 
-                Ok(())
-            }).unwrap_or(Ok(()))?;
         Ok(())
     }
 
     pub fn render(&mut self) -> Result<()> {
+        // `last_frame` is kept, so the first frame after
+        // being uncovered updates widgets for the whole
+        // time they were hidden rather than a single frame
+        if self.occluded {
+            return Ok(());
+        }
+
+        self.limit_frame_rate();
+
         let now = Instant::now();
-        self.contexts.imgui.io_mut().update_delta_time(now - self.last_frame.unwrap_or(now));
+        let frame_time = now - self.last_frame.unwrap_or(now);
+        self.contexts.imgui.io_mut().update_delta_time(frame_time);
         self.last_frame = Some(now);
 
-        // no safe way to achieve this
-        unsafe { self.contexts.glow.gl_context().clear(glow::COLOR_BUFFER_BIT); }
+        if let Some(fps_overlay) = &mut self.fps_overlay {
+            fps_overlay.push(frame_time);
+        }
+
+        if self.screensaver.is_some() {
+            return self.render_screensaver(frame_time);
+        }
+
+        if let Some(image) = self.slideshow.as_mut().and_then(|slideshow| slideshow.update(frame_time)) {
+            if let Err(e) = self.renderer.crossfade_wallpaper(&image, self.config.wallpaper_mode) {
+                self.notify_error(e.context("Failed to change slideshow image"));
+            }
+        }
+
+        let crossfading = self.renderer.advance_crossfade(frame_time);
+
+        self.renderer.gl().clear_color_buffer([0f32; 4])?;
+
+        let resolution = [self.resolution.width as f32, self.resolution.height as f32];
+
+        // the background is drawn first
+        // so imgui composites over it
+        self.renderer
+            .draw(resolution, (now - self.start).as_secs_f32())
+            .context("Failed to render VeilDE")?;
+
+        if self.config.taskbar_blur_radius > 0f32 {
+            let (position, size) = self.taskbar_rect();
+
+            self.renderer.draw_blur(
+                resolution,
+                [position[0], position[1], size[0], size[1]],
+                self.config.taskbar_blur_radius,
+                self.config.taskbar_blur_tint.0
+            ).context("Failed to blur taskbar backdrop")?;
+        }
+
+        run_hooks(&mut self.hooks.borrow_mut().begin, "begin", frame_time);
 
-        //self.renderer.draw().context("Failed to render VeilDE")?;
-        self.gui().context("Failed to render VeilDE GUI")?;
+        // gui failures are recoverable, only
+        // context and rendering errors are fatal
+        if let Err(e) = self.gui() {
+            self.notify_error(e.context("Failed to render VeilDE GUI"));
+        }
 
         self.contexts.glow
             .render(self.contexts.imgui.render())
             .map_err(|_| anyhow!("Failed to render ImGui renderer data"))?;
 
+        run_hooks(&mut self.hooks.borrow_mut().end, "end", frame_time);
+
+        // after imgui, so it's what's on screen
+        if let Some((position, size)) = self.screenshot_region.take() {
+            let resolution = [self.resolution.width, self.resolution.height];
+            let screenshot = read_framebuffer(self.contexts.glow.gl_context(), resolution, position, size)
+                .and_then(|image| save_screenshot(&image));
+
+            match screenshot {
+                Ok(path) => log::info!("Saved screenshot '{}'", path.display()),
+                Err(e) => self.notify_error(e.context("Failed to take screenshot")),
+            }
+        }
+
+        // sampled for the next frame's tooltip
+        if let (Some(color_picker), Some(cursor)) = (&mut self.color_picker, self.cursor) {
+            let resolution = [self.resolution.width, self.resolution.height];
+
+            if let Err(e) = color_picker.sample(self.contexts.glow.gl_context(), resolution, cursor) {
+                self.color_picker = None;
+                self.notify_error(e.context("Failed to pick color"));
+            }
+        }
+
+        // after the screenshot and color picker,
+        // so they get the colors actually drawn
+        let night_light = self.night_light.update(frame_time) * self.config.night_light_intensity.clamp(0f32, 1f32);
+
+        if night_light > 0f32 {
+            self.renderer
+                .draw_night_light(self.config.night_light_temperature, night_light)
+                .context("Failed to draw night light")?;
+        }
+
         self.contexts.surface
             .swap_buffers(&self.contexts.opengl)
             .context("Failed to swap surface buffers")?;
 
+        self.failed_recoveries = 0;
+
+        // the atlas can't change mid-frame, so
+        // new glyphs show from the next frame
+        if self.fonts.load_missing() {
+            self.rebuild_fonts(self.contexts.window.scale_factor()).context("Failed to rebuild fonts")?;
+            self.contexts.window.request_redraw();
+        }
+
+        // the slide animates even without input
+        if self.taskbar_reveal > 0f32 && self.taskbar_reveal < 1f32 {
+            self.contexts.window.request_redraw();
+        }
+
+        // as does the crossfade
+        if crossfading {
+            self.contexts.window.request_redraw();
+        }
+
         Ok(())
     }
 }
 
-impl ApplicationHandler for VeilDEApplicationHandler {
+impl ApplicationHandler<IpcCommand> for VeilDEApplicationHandler {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        if self.application.is_none() {
-            match VeilDEApplication::new(event_loop) {
-                Ok(app) => self.application = Some(app),
-                Err(e) => {
-                    // unavoidable crash ahead
-                    self.error_tx.send(e).expect("Failed to send error");
-                    event_loop.exit();
-                }
+        if self.applications.is_empty() {
+            if let Err(e) = self.create_applications(event_loop) {
+                // unavoidable crash ahead
+                self.error_tx.send(e).expect("Failed to send error");
+                event_loop.exit();
             }
         }
     }
 
-    fn window_event(&mut self, event_loop: &ActiveEventLoop, _: WindowId, event: WindowEvent) {
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, window_id: WindowId, event: WindowEvent) {
         let mut perform = || -> Result<()> {
-            if let Some(app) = self.application.as_mut() {
+            if let WindowEvent::CloseRequested = event {
+                // e.g. Alt+F4 on the desktop, which would leave the
+                // user without a shell, a window is closed as usual
+                if self.config.confirm_exit && !self.config.windowed && !confirm(tr("Quit VeilDE"), tr("Quit VeilDE? The desktop and taskbar will close."))? {
+                    return Ok(());
+                }
+
+                // closing any monitor's
+                // window ends the session
+                self.config_watcher = None;
+
+                #[cfg(windows)]
+                {
+                    self.tray_host = None;
+                    self.clipboard_host = None;
+                    self.hotkey_host = None;
+                }
+
+                // not worth failing the shutdown over
+                if let Err(e) = self.save_window_geometry() {
+                    log::warn!("{:?}", e.context("Failed to save window geometry"));
+                }
+
+                for app in self.applications.values_mut() {
+                    app.make_current()?;
+                    app.shutdown().context("Failed to shutdown VeilDE application")?;
+                }
+
+                event_loop.exit();
+                return Ok(());
+            }
+
+            if let Some(app) = self.applications.get_mut(&window_id) {
+                app.make_current()?;
                 app.pre_window_event(&event);
 
                 match event {
-                    WindowEvent::CloseRequested => {
-                        app.shutdown().context("Failed to shutdown VeilDE application")?;
-                        event_loop.exit()
-                    },
-
                     WindowEvent::RedrawRequested => {
-                        app.render().context("Failed to draw VeilDE application")?;
+                        if let Err(e) = app.render() {
+                            app.recover(e).context("Failed to draw VeilDE application")?;
+                        }
+                    }
+
+                    // only the lock screen takes input while locked,
+                    // which imgui was given in `pre_window_event`
+                    WindowEvent::KeyboardInput { .. } | WindowEvent::MouseInput { .. } if app.lock_screen.is_some() => { }
+
+                    // the input that stops the screensaver isn't acted on
+                    WindowEvent::KeyboardInput { .. } | WindowEvent::MouseInput { .. } if app.screensaver.is_some() => { }
+
+                    WindowEvent::KeyboardInput {
+                        event: KeyEvent {
+                            logical_key: Key::Named(NamedKey::F5),
+                            state: ElementState::Pressed,
+                            repeat: false,
+                            ..
+                        },
+                        ..
+                    } => {
+                        // a broken shader keeps the
+                        // previous program, so this
+                        // doesn't need to be fatal
+                        if let Err(e) = app.renderer.reload_shaders() {
+                            app.notify_error(e);
+                        }
+                    }
+
+                    WindowEvent::KeyboardInput {
+                        event: KeyEvent {
+                            logical_key: Key::Named(NamedKey::F1),
+                            state: ElementState::Pressed,
+                            repeat: false,
+                            ..
+                        },
+                        ..
+                    } => {
+                        app.command_palette.toggle();
+                    }
+
+                    WindowEvent::KeyboardInput {
+                        event: KeyEvent {
+                            logical_key: Key::Named(NamedKey::F3),
+                            state: ElementState::Pressed,
+                            repeat: false,
+                            ..
+                        },
+                        ..
+                    } => {
+                        app.toggle_fps_overlay();
+                    }
+
+                    WindowEvent::KeyboardInput {
+                        event: KeyEvent {
+                            logical_key: Key::Named(NamedKey::F9),
+                            state: ElementState::Pressed,
+                            repeat: false,
+                            ..
+                        },
+                        ..
+                    } => {
+                        app.take_screenshot();
+                    }
+
+                    WindowEvent::KeyboardInput {
+                        event: KeyEvent {
+                            logical_key: Key::Named(NamedKey::F8),
+                            state: ElementState::Pressed,
+                            repeat: false,
+                            ..
+                        },
+                        ..
+                    } => {
+                        app.toggle_region_capture();
+                    }
+
+                    WindowEvent::KeyboardInput {
+                        event: KeyEvent {
+                            logical_key: Key::Named(NamedKey::F6),
+                            state: ElementState::Pressed,
+                            repeat: false,
+                            ..
+                        },
+                        ..
+                    } => {
+                        // every monitor, not just this one
+                        app.command = Some(IpcCommand::ToggleNightLight);
+                    }
+
+                    WindowEvent::KeyboardInput {
+                        event: KeyEvent {
+                            logical_key: Key::Named(NamedKey::F7),
+                            state: ElementState::Pressed,
+                            repeat: false,
+                            ..
+                        },
+                        ..
+                    } => {
+                        app.toggle_color_picker();
+                    }
+
+                    WindowEvent::KeyboardInput {
+                        event: KeyEvent {
+                            logical_key: Key::Named(NamedKey::Escape),
+                            state: ElementState::Pressed,
+                            ..
+                        },
+                        ..
+                    } if app.region_capture.is_some() || app.color_picker.is_some() => {
+                        app.region_capture = None;
+                        app.color_picker = None;
+                    }
+
+                    WindowEvent::MouseInput { state, button: winit::event::MouseButton::Left, .. } => {
+                        app.region_capture_input(state);
+                    }
+
+                    WindowEvent::KeyboardInput {
+                        event: KeyEvent {
+                            logical_key: Key::Named(NamedKey::F12),
+                            state: ElementState::Pressed,
+                            repeat: false,
+                            ..
+                        },
+                        ..
+                    } => {
+                        app.log_console.toggle();
+                    }
+
+                    // activating the window (e.g. clicking the taskbar)
+                    // raises it above other applications on Windows
+                    #[cfg(windows)]
+                    WindowEvent::Focused(true) if !app.config.windowed && app.lock_screen.is_none() && app.screensaver.is_none() => {
+                        crate::win32::send_to_bottom(&app.contexts.window).context("Failed to keep window at the bottom")?;
+                    }
+
+                    WindowEvent::Resized(size) => {
+                        if let Err(e) = app.resize(size) {
+                            app.notify_error(e.context("Failed to resize"));
+                        }
+                    }
+
+                    WindowEvent::Moved(position) => {
+                        app.window_position = Some([position.x, position.y]);
+                    }
+
+                    // e.g. a fullscreen application covering the desktop
+                    WindowEvent::Occluded(occluded) => {
+                        app.occluded = occluded;
+
+                        if !occluded {
+                            app.contexts.window.request_redraw();
+                        }
+                    }
+
+                    // e.g. switching Windows to dark mode
+                    WindowEvent::ThemeChanged(_) => {
+                        if let Err(e) = app.refresh_theme() {
+                            app.notify_error(e);
+                        }
+                    }
+
+                    WindowEvent::CursorMoved { position, .. } => {
+                        app.cursor = Some([position.x as f32, position.y as f32]);
+                    }
+
+                    WindowEvent::CursorLeft { .. } => {
+                        app.cursor = None;
+                    }
+
+                    WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                        app.rebuild_fonts(scale_factor).context("Failed to rebuild fonts for new scale factor")?;
                     }
 
                     _ => { }
@@ -251,6 +2064,44 @@ impl ApplicationHandler for VeilDEApplicationHandler {
                 app.post_window_event(&event);
             }
 
+            #[cfg(not(windows))]
+            if matches!(
+                event,
+                WindowEvent::KeyboardInput { .. } | WindowEvent::MouseInput { .. } | WindowEvent::MouseWheel { .. } | WindowEvent::CursorMoved { .. }
+            ) {
+                self.last_input = Instant::now();
+            }
+
+            let unlocked = self.applications
+                .get_mut(&window_id)
+                .is_some_and(|app| std::mem::take(&mut app.unlocked));
+
+            if unlocked {
+                self.unlock()?;
+            }
+
+            let command = self.applications
+                .get_mut(&window_id)
+                .and_then(|app| app.command.take());
+
+            if let Some(command) = command {
+                if let Err(e) = self.run_command(command) {
+                    self.notify_error(e);
+                }
+            }
+
+            let change = self.applications
+                .get_mut(&window_id)
+                .and_then(|app| app.settings_change.take());
+
+            // a failed save leaves the
+            // settings window open to retry
+            if let Some(change) = change {
+                if let Err(e) = self.apply_settings(change) {
+                    self.notify_error(e.context("Failed to apply settings"));
+                }
+            }
+
             Ok(())
         };
 
@@ -260,156 +2111,272 @@ impl ApplicationHandler for VeilDEApplicationHandler {
             event_loop.exit();
         }
     }
-}
 
-fn get_font_data(scale: f64) -> Vec<FontSource<'static>> {
-    vec![
-        FontSource::TtfData {
-            data: include_bytes!("../resources/segoeui.ttf"), // TODO: load dynamically
-            size_pixels: (FONT_SIZE * scale) as f32,
-            config: Some(FontConfig {
-                rasterizer_multiply: 1f32,
-                font_builder_flags: ImGuiFreeTypeBuilderFlags_Bitmap,
-
-                oversample_h: 1i32,
-                oversample_v: 1i32,
-                glyph_offset: [0f32, (-5f64 * scale) as f32], // TODO: calculate dynamically by checking for blank pixels at the edge of the font atlas
-
-                ..FontConfig::default()
-            })
-        },
-    ]
-}
+    fn user_event(&mut self, _: &ActiveEventLoop, command: IpcCommand) {
+        if let Err(e) = self.run_command(command) {
+            self.notify_error(e.context("Failed to run IPC command"));
+        }
+    }
 
-fn init_imgui() -> Result<ImGuiContext> {
-    let mut context = ImGuiContext::create();
+    fn new_events(&mut self, _: &ActiveEventLoop, cause: StartCause) {
+        // the clock tick in reactive mode
+        if let StartCause::ResumeTimeReached { .. } = cause {
+            for app in self.applications.values() {
+                app.contexts.window.request_redraw();
+            }
+        }
+    }
 
-    context.set_ini_filename(None);
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        let occluded = self.applications.values().all(|app| app.occluded);
 
-    // freetype doesn't enable itself
-    // due to a bug in the 'imgui-sys'
-    // crate, that has yet to be patched
-    //
-    // https://github.com/imgui-rs/imgui-rs/issues/773
-    unsafe { context.fonts().raw_mut().FontBuilderIO = ImGuiFreeType_GetBuilderForFreeType(); }
-    context.io_mut().font_global_scale = 1f32; // scale through font data for high quality
-    context.fonts().add_font(get_font_data(1f64).as_slice());
+        match self.config.redraw_mode {
+            // nothing to draw until a window is uncovered
+            RedrawMode::Continuous if occluded => event_loop.set_control_flow(ControlFlow::Wait),
 
-    Ok(context)
-}
+            // winit advises using Poll for vertically synced apps
+            RedrawMode::Continuous => event_loop.set_control_flow(ControlFlow::Poll),
 
-fn init_winit(imgui: &mut ImGuiContext, window: &Window) -> Result<WinitPlatform> {
-    let mut context = WinitPlatform::new(imgui);
-    context.attach_window(imgui.io_mut(), window, HiDpiMode::Default);
+            RedrawMode::Reactive => {
+                let until_next_second = 1000 - Local::now().timestamp_subsec_millis().min(999);
+                event_loop.set_control_flow(ControlFlow::WaitUntil(
+                    Instant::now() + Duration::from_millis(until_next_second as u64)
+                ));
+            }
+        }
 
-    Ok(context)
-}
+        match self.screensaver_idle {
+            // any input since it started
+            Some(idle) if self.idle_time() < idle => {
+                if let Err(e) = self.stop_screensaver() {
+                    self.notify_error(e.context("Failed to stop screensaver"));
+                }
+            }
 
-fn init_glutin<T: Into<Size<>>, S: Into<Position<>>>(event_loop: &ActiveEventLoop, size: T, position: S) -> Result<(Window, Config)> {
-    let (window, config) = glutin_winit::DisplayBuilder::new()
-        .with_window_attributes(Some(
-            WindowAttributes::default()
-                .with_title(WINDOW_TITLE)
-                .with_inner_size(size)
-                .with_fullscreen(None) // TODO: fullscreen and transparent don't work together
-                .with_decorations(false)
-                .with_transparent(true)
-                .with_position(position)
-                //.with_window_level(WindowLevel::AlwaysOnBottom)
-        )
-        ).build(
-        event_loop,
-        ConfigTemplateBuilder::new(),
-        |mut cfg| {
-            cfg.next().context("Failed to get next configuration value").unwrap()
+            Some(_) => { }
+
+            None => {
+                let idle = self.config.screensaver_idle_minutes
+                    .is_some_and(|minutes| self.idle_time() >= Duration::from_secs(minutes as u64 * 60));
+
+                if idle {
+                    if let Err(e) = self.start_screensaver() {
+                        self.notify_error(e.context("Failed to start screensaver"));
+                    }
+                }
+            }
         }
-    ).map_err(|_| anyhow!("Failed to initialize glutin"))?;
 
-    Ok(
-        (window.context("Failed to create window")?, config)
-    )
+        let idle_lock = self.config.lock_idle_minutes
+            .filter(|_| !self.locked && self.config.lock_password_hash.as_deref().is_some_and(is_valid_hash))
+            .is_some_and(|minutes| self.idle_time() >= Duration::from_secs(minutes as u64 * 60));
+
+        if idle_lock {
+            if let Err(e) = self.lock() {
+                self.notify_error(e.context("Failed to lock screen"));
+            }
+        }
+
+        if self.last_monitor_sync.elapsed() >= MONITOR_SYNC_INTERVAL {
+            self.last_monitor_sync = Instant::now();
+
+            // monitors that are still
+            // connected keep working
+            if let Err(e) = self.sync_monitors(event_loop) {
+                self.notify_error(e.context("Failed to update monitors"));
+            }
+        }
+
+        let Some(config) = self.config_watcher.as_ref().and_then(ConfigWatcher::poll) else {
+            return;
+        };
+
+        // a broken config keeps the
+        // previous one, so this
+        // doesn't need to be fatal
+        if let Err(e) = config.and_then(|config| {
+            let config = self.with_script(config);
+            self.apply_config(config)
+        }) {
+            self.notify_error(e.context("Failed to reload config"));
+        }
+    }
 }
 
-fn init_opengl(window: &Window, config: &Config) -> Result<(OpenGlContext, Surface<WindowSurface>)> {
-    // glutin does not provide a
-    // safe alternative to creating
-    // display contexts with winit
-    let context = unsafe {
-        config.display().create_context(
-            config,
-            &ContextAttributesBuilder::new()
-                .build(
-                    Some(
-                        window
-                            .window_handle()
-                            .context("Failed to get window handle for context")?
-                            .as_raw()
-                    )
-                )
-        ).context("Failed to create OpenGL context")?
+/// Applies `name`'s configured opacity to the windows drawn until the token drops.
+fn push_opacity<'ui>(ui: &'ui Ui, config: &Config, name: &str) -> StyleStackToken<'ui> {
+    ui.push_style_var(StyleVar::Alpha(config.window_opacity(name)))
+}
+
+/// Signs out, restarts or shuts down once confirmed.
+#[cfg(windows)]
+fn end_session(action: Action) -> Result<()> {
+    use crate::win32::power::EndSession;
+
+    let (end, question) = match action {
+        Action::SignOut => (EndSession::SignOut, tr("Sign out? Programs with unsaved work may lose it.")),
+        Action::Restart => (EndSession::Restart, tr("Restart the computer? Programs with unsaved work may lose it.")),
+        Action::ShutDown => (EndSession::ShutDown, tr("Shut down the computer? Programs with unsaved work may lose it.")),
+        _ => return Ok(()),
     };
 
-    // glutin does not provide a safe
-    // alternative to creating window
-    // surfaces with winit
-    let surface = unsafe {
-        config
-            .display()
-            .create_window_surface(
-                config,
-                &SurfaceAttributesBuilder::<WindowSurface>::new()
-                    .with_srgb(Some(true))
-                    .build(
-                        window
-                            .window_handle()
-                            .context("Failed to get window handle for surface")?
-                            .as_raw(),
-                        NonZeroU32::new(WINDOW_SIZE[0]).context("Window surface width was zero or out-of-bounds")?,
-                        NonZeroU32::new(WINDOW_SIZE[1]).context("Window surface height was zero or out-of-bounds")?,
-                    )
-            )
-            .context("Failed to create window surface")?
+    if confirm("VeilDE-rs", question)? {
+        crate::win32::power::end_session(end)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn end_session(_action: Action) -> Result<()> {
+    bail!("Ending the session is only supported on Windows")
+}
+
+/// The taskbar widgets, from left to right after the start button.
+fn create_widgets(config: &Config, gl: &Rc<glow::Context>) -> Vec<Box<dyn Widget>> {
+    let names = match config.widgets.is_empty() {
+        true => default_widget_names(),
+        false => config.widgets.iter().map(String::as_str).collect(),
     };
 
-    Ok((
-        context.make_current(&surface)
-            .context("Failed to make OpenGL context current")?,
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let widget = create_widget(name, config, gl);
+
+            if widget.is_none() {
+                log::warn!("Unknown widget '{name}', or it isn't available on this platform");
+            }
+
+            widget
+        })
+        .collect()
+}
+
+/// Every widget, in the order used when none are configured.
+fn default_widget_names() -> Vec<&'static str> {
+    let mut names = Vec::new();
 
-        surface
-    ))
+    #[cfg(windows)]
+    names.extend(["workspaces", "windows"]);
+
+    names.extend(["system_info", "system", "disks", "network"]);
+
+    #[cfg(windows)]
+    names.extend(["tray", "clipboard", "media", "keyboard", "quick_settings", "volume", "battery"]);
+
+    names.extend(["weather", "timer"]);
+    names.extend(plugins().iter().map(|plugin| plugin.name.as_str()));
+    names.push("clock");
+    names
 }
 
-fn init_glow(opengl: &OpenGlContext, imgui: &mut ImGuiContext) -> Result<AutoRenderer> {
-    // glow requires using `get_proc_address`,
-    // which is an inherently unsafe concept
-    AutoRenderer::new(
-        unsafe {
-            glow::Context::from_loader_function_cstr(
-                |s| {
-                    opengl
-                        .display()
-                        .get_proc_address(s)
-                        .cast()
-                })
-        }, imgui
-    ).context("Failed to create GLOW context")
+/// Built-in widgets take precedence over plugins with the same name.
+#[cfg_attr(not(windows), allow(unused_variables))]
+fn create_widget(name: &str, config: &Config, gl: &Rc<glow::Context>) -> Option<Box<dyn Widget>> {
+    let widget: Box<dyn Widget> = match name {
+        #[cfg(windows)]
+        "workspaces" => Box::new(crate::win32::workspace_switcher::WorkspaceSwitcher::new(config)),
+        #[cfg(windows)]
+        "windows" => Box::new(crate::win32::open_windows::OpenWindows::new(gl.clone())),
+        "system_info" => Box::new(SystemInfo::new()),
+        "system" => Box::new(SystemUsage::new()),
+        "disks" => Box::new(Disks::new(config)),
+        "network" => Box::new(Network::new()),
+        #[cfg(windows)]
+        "tray" => Box::new(crate::win32::tray::TrayIcons::new(gl.clone())),
+        #[cfg(windows)]
+        "clipboard" => Box::new(crate::win32::clipboard::ClipboardHistory::new(gl.clone())),
+        #[cfg(windows)]
+        "media" => Box::new(crate::win32::media::Media::new(gl.clone())),
+        #[cfg(windows)]
+        "keyboard" => Box::new(crate::win32::keyboard::Keyboard::new()),
+        #[cfg(windows)]
+        "brightness" => Box::new(crate::win32::brightness::BrightnessSlider::new()),
+        #[cfg(windows)]
+        "quick_settings" => Box::new(crate::win32::quick_settings::QuickSettings::new()),
+        #[cfg(windows)]
+        "visualizer" => Box::new(crate::win32::visualizer::Visualizer::new()),
+        #[cfg(windows)]
+        "volume" => Box::new(crate::win32::volume::Volume::new()),
+        #[cfg(windows)]
+        "battery" => Box::new(crate::win32::battery::Battery::new()),
+        "weather" => Box::new(Weather::new(config)),
+        "timer" => Box::new(Timer::new(config)),
+        "clock" => Box::new(Clock::new(config, Rc::new(SystemClock))),
+        _ => Box::new(PluginWidget::new(plugins().iter().find(|plugin| plugin.name == name)?, config)),
+    };
+
+    Some(widget)
 }
 
-pub fn init() -> Result<()> {
-    let event_loop = EventLoop::new().context("Failed to create event loop")?;
+pub fn run(config: Config, overrides: ConfigOverrides, config_path: Option<PathBuf>, panels: Vec<Panel>, hooks: FrameHooks) -> Result<()> {
+    log_console::init();
+    panics::install_hook();
+    load_plugins();
+
+    // user events are commands from other processes
+    let event_loop = EventLoop::<IpcCommand>::with_user_event()
+        .build()
+        .context("Failed to create event loop")?;
 
-    // winit advises using Poll for vertically synced apps
+    // winit advises using Poll for vertically synced apps,
+    // reactive redrawing switches this in `about_to_wait`
     event_loop.set_control_flow(ControlFlow::Poll);
 
     let (tx, rx) = channel::<Error>();
 
-    event_loop.run_app(
-        &mut VeilDEApplicationHandler::new(tx)
-    ).context("Failed to run app loop")?;
+    #[cfg(windows)]
+    crate::win32::ipc::spawn_server(event_loop.create_proxy());
+
+    let result = event_loop.run_app(
+        &mut VeilDEApplicationHandler::new(config, overrides, config_path, panels, hooks, event_loop.create_proxy(), tx)
+    );
+
+    // however the loop ended, e.g. a fatal error
+    // rather than closing the window, windows on
+    // other workspaces shouldn't stay hidden
+    #[cfg(windows)]
+    crate::win32::workspaces::restore();
 
-    if let Ok(error) = rx.try_recv() {
-        bail!(error)
+    result.context("Failed to run app loop")?;
+
+    // e.g. several monitors failing during teardown
+    combine_errors(rx.try_iter().collect())
+}
+
+/// Reports every error sent before the event loop ended, not only the first.
+fn combine_errors(mut errors: Vec<Error>) -> Result<()> {
+    if errors.len() <= 1 {
+        return errors.pop().map_or(Ok(()), Err);
     }
 
-    Ok(())
-}
\ No newline at end of file
+    let report = errors
+        .iter()
+        .enumerate()
+        .map(|(index, e)| format!("Error {}:\n{e:?}", index + 1))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    bail!("{} errors occurred\n\n{report}", errors.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+    use anyhow::anyhow;
+    use super::combine_errors;
+
+    #[test]
+    fn combines_every_queued_error() {
+        let (tx, rx) = channel();
+        tx.send(anyhow!("first monitor failed")).unwrap();
+        tx.send(anyhow!("second monitor failed")).unwrap();
+
+        let report = format!("{:?}", combine_errors(rx.try_iter().collect()).unwrap_err());
+
+        assert!(report.contains("2 errors occurred"));
+        assert!(report.contains("first monitor failed"));
+        assert!(report.contains("second monitor failed"));
+    }
+}
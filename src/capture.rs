@@ -0,0 +1,127 @@
+use std::path::PathBuf;
+use anyhow::{Context, Result};
+use chrono::Local;
+use image::RgbaImage;
+use imgui::{Condition, Ui};
+use imgui_glow_renderer::glow;
+use imgui_glow_renderer::glow::HasContext;
+use crate::consts::*;
+use crate::utils::resolve_path;
+
+/// Reads `size` pixels of the framebuffer from `position`, both in pixels from the top-left corner.
+pub fn read_framebuffer(gl: &glow::Context, resolution: [u32; 2], position: [u32; 2], size: [u32; 2]) -> Result<RgbaImage> {
+    let mut pixels = vec![0u8; size[0] as usize * size[1] as usize * 4];
+
+    // the framebuffer's origin is the bottom-left corner
+    let y = resolution[1].saturating_sub(position[1] + size[1]);
+
+    unsafe {
+        // the shaders and imgui write sRGB encoded colors themselves,
+        // so the stored bytes are read as is, without the driver
+        // converting them from an sRGB capable surface
+        let srgb = !gl.version().is_embedded && gl.is_enabled(glow::FRAMEBUFFER_SRGB);
+
+        if srgb {
+            gl.disable(glow::FRAMEBUFFER_SRGB);
+        }
+
+        gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+        gl.read_pixels(
+            position[0] as i32,
+            y as i32,
+            size[0] as i32,
+            size[1] as i32,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            glow::PixelPackData::Slice(Some(&mut pixels))
+        );
+
+        if srgb {
+            gl.enable(glow::FRAMEBUFFER_SRGB);
+        }
+    }
+
+    let mut image = RgbaImage::from_raw(size[0], size[1], pixels).context("Framebuffer size mismatch")?;
+
+    // rows are read bottom to top
+    image::imageops::flip_vertical_in_place(&mut image);
+
+    Ok(image)
+}
+
+/// Saves `image` as a PNG named after the current time, returning its path.
+pub fn save_screenshot(image: &RgbaImage) -> Result<PathBuf> {
+    let directory = resolve_path(SCREENSHOT_DIRECTORY)?;
+    std::fs::create_dir_all(&directory).context("Failed to create screenshot directory")?;
+
+    let path = directory.join(format!("{}.png", Local::now().format("%Y%m%d%H%M%S%3f")));
+
+    image
+        .save_with_format(&path, image::ImageFormat::Png)
+        .with_context(|| format!("Failed to save screenshot '{}'", path.display()))?;
+
+    Ok(path)
+}
+
+/// Drag-to-select overlay choosing the region of a screenshot.
+pub struct RegionCapture {
+    start: Option<[f32; 2]>, // where the drag began, `None` before it does
+}
+
+impl RegionCapture {
+    pub fn new() -> Self {
+        Self { start: None }
+    }
+
+    pub fn begin(&mut self, cursor: [f32; 2]) {
+        self.start = Some(cursor);
+    }
+
+    /// The selected position and size in pixels, once dragging.
+    pub fn selection(&self, cursor: [f32; 2]) -> Option<([f32; 2], [f32; 2])> {
+        let start = self.start?;
+        let position = [start[0].min(cursor[0]), start[1].min(cursor[1])];
+        let size = [(start[0] - cursor[0]).abs(), (start[1] - cursor[1]).abs()];
+
+        Some((position, size))
+    }
+
+    /// Dims the screen around the selection and outlines it.
+    pub fn render(&self, ui: &Ui, cursor: Option<[f32; 2]>, resolution: [f32; 2]) {
+        ui.window("_region_capture")
+            .title_bar(false)
+            .resizable(false)
+            .movable(false)
+            .scroll_bar(false)
+            .draw_background(false)
+            .position([0f32, 0f32], Condition::Always)
+            .size(resolution, Condition::Always)
+            .focused(true)
+            .build(|| {
+                let draw_list = ui.get_window_draw_list();
+
+                let Some((position, size)) = cursor.and_then(|cursor| self.selection(cursor)) else {
+                    draw_list
+                        .add_rect([0f32, 0f32], resolution, REGION_CAPTURE_DIM_COLOR)
+                        .filled(true)
+                        .build();
+
+                    return;
+                };
+
+                let end = [position[0] + size[0], position[1] + size[1]];
+
+                // above, below, left and right of the selection
+                for (min, max) in [
+                    ([0f32, 0f32], [resolution[0], position[1]]),
+                    ([0f32, end[1]], resolution),
+                    ([0f32, position[1]], [position[0], end[1]]),
+                    ([end[0], position[1]], [resolution[0], end[1]]),
+                ] {
+                    draw_list.add_rect(min, max, REGION_CAPTURE_DIM_COLOR).filled(true).build();
+                }
+
+                draw_list.add_rect(position, end, REGION_CAPTURE_BORDER_COLOR).build();
+            });
+    }
+}
@@ -0,0 +1,66 @@
+use anyhow::Result;
+use imgui::{Condition, MouseButton, Ui};
+use imgui_glow_renderer::glow;
+use crate::capture::read_framebuffer;
+
+/// Samples the pixel under the cursor, copying its hex value on click.
+pub struct ColorPicker {
+    color: Option<[u8; 4]>, // from the last frame, `None` before one was sampled
+}
+
+impl ColorPicker {
+    pub fn new() -> Self {
+        Self { color: None }
+    }
+
+    /// Reads the pixel at `cursor` from the finished frame.
+    pub fn sample(&mut self, gl: &glow::Context, resolution: [u32; 2], cursor: [f32; 2]) -> Result<()> {
+        let x = (cursor[0].max(0f32) as u32).min(resolution[0] - 1);
+        let y = (cursor[1].max(0f32) as u32).min(resolution[1] - 1);
+
+        let pixel = read_framebuffer(gl, resolution, [x, y], [1, 1])?;
+        self.color = Some(pixel.get_pixel(0, 0).0);
+
+        Ok(())
+    }
+
+    fn hex(color: [u8; 4]) -> String {
+        format!("#{:02X}{:02X}{:02X}", color[0], color[1], color[2])
+    }
+
+    /// Shows the sampled color next to the cursor, returning
+    /// whether it was picked (and copied) this frame.
+    pub fn render(&self, ui: &Ui, resolution: [f32; 2]) -> bool {
+        let Some(color) = self.color else {
+            return false;
+        };
+
+        let hex = Self::hex(color);
+        let mut picked = false;
+
+        // keeps clicks from reaching the windows below
+        ui.window("_color_picker")
+            .title_bar(false)
+            .resizable(false)
+            .movable(false)
+            .scroll_bar(false)
+            .draw_background(false)
+            .position([0f32, 0f32], Condition::Always)
+            .size(resolution, Condition::Always)
+            .focused(true)
+            .build(|| {
+                ui.tooltip(|| {
+                    ui.color_button("##_color_picker_swatch", color.map(|channel| channel as f32 / 255f32));
+                    ui.same_line();
+                    ui.text(format!("{hex}\nR {} G {} B {}", color[0], color[1], color[2]));
+                });
+
+                if ui.is_mouse_clicked(MouseButton::Left) {
+                    ui.set_clipboard_text(&hex);
+                    picked = true;
+                }
+            });
+
+        picked
+    }
+}
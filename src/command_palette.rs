@@ -0,0 +1,158 @@
+use imgui::{Condition, Key, Ui};
+use crate::actions::Action;
+use crate::config::Config;
+use crate::consts::*;
+use crate::fonts::request_glyphs;
+use crate::i18n::tr;
+
+/// Scores how well `query` fuzzy matches `name`, higher being better.
+///
+/// Every character of `query` has to appear in `name` in order, matches
+/// at the start of words or right after the previous match scoring more.
+/// `None` when it doesn't match at all.
+fn fuzzy_score(query: &str, name: &str) -> Option<i32> {
+    let mut score = 0;
+    let mut previous: Option<usize> = None; // index of the last matched character
+    let mut chars = name.chars().flat_map(char::to_lowercase).enumerate().peekable();
+    let mut word_start = true;
+
+    'query: for wanted in query.chars().flat_map(char::to_lowercase).filter(|c| !c.is_whitespace()) {
+        for (index, c) in chars.by_ref() {
+            let at_word_start = word_start;
+            word_start = !c.is_alphanumeric();
+
+            if c != wanted {
+                continue;
+            }
+
+            score += 1;
+
+            if at_word_start {
+                score += 8;
+            }
+
+            match previous {
+                Some(previous) if previous + 1 == index => score += 5,
+                Some(previous) => score -= (index - previous - 1).min(5) as i32,
+                None => score -= index.min(5) as i32, // prefer matches near the start
+            }
+
+            previous = Some(index);
+            continue 'query;
+        }
+
+        return None;
+    }
+
+    Some(score)
+}
+
+/// Searchable list of every action, run with enter.
+pub struct CommandPalette {
+    open: bool,
+    query: String,
+    selected: usize, // index into the filtered matches
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.query.clear();
+        self.selected = 0;
+    }
+
+    /// Draws the palette, returning the action chosen this frame.
+    pub fn render(&mut self, ui: &Ui, actions: &[Action], config: &Config) -> Option<Action> {
+        if !self.open {
+            return None;
+        }
+
+        let mut matches: Vec<(i32, Action)> = actions
+            .iter()
+            .filter_map(|action| Some((fuzzy_score(&self.query, action.name(config))?, *action)))
+            .collect();
+
+        // stable, so ties keep the registry's order
+        matches.sort_by_key(|(score, _)| -score);
+
+        let mut chosen = None;
+        let mut close = false;
+
+        ui.window("_command_palette")
+            .title_bar(false)
+            .resizable(false)
+            .movable(false)
+            .size(COMMAND_PALETTE_SIZE, Condition::Always)
+            .position_pivot([0.5f32, 0f32])
+            .position([ui.io().display_size[0] / 2f32, ui.io().display_size[1] * 0.2], Condition::Always)
+            .build(|| {
+                if ui.is_window_appearing() {
+                    ui.set_keyboard_focus_here();
+                }
+
+                let entered = ui.input_text("##_command_palette_query", &mut self.query)
+                    .hint(tr("Type a command"))
+                    .enter_returns_true(true)
+                    .build();
+
+                // the best match is back on top
+                if ui.is_item_edited() {
+                    self.selected = 0;
+                }
+
+                let mut moved = false;
+
+                if ui.is_key_pressed(Key::DownArrow) && self.selected + 1 < matches.len() {
+                    self.selected += 1;
+                    moved = true;
+                }
+
+                if ui.is_key_pressed(Key::UpArrow) && self.selected > 0 {
+                    self.selected -= 1;
+                    moved = true;
+                }
+
+                self.selected = self.selected.min(matches.len().saturating_sub(1));
+                close = ui.is_key_pressed(Key::Escape);
+
+                if entered {
+                    chosen = matches.get(self.selected).map(|(_, action)| *action);
+                }
+
+                ui.separator();
+
+                ui.child_window("_command_palette_matches").build(|| {
+                    if matches.is_empty() {
+                        ui.text_disabled(tr(COMMAND_PALETTE_EMPTY_TEXT));
+                    }
+
+                    for (index, (_, action)) in matches.iter().enumerate() {
+                        let name = action.name(config);
+                        request_glyphs(ui, name);
+
+                        if ui.selectable_config(format!("{name}##_command_{index}")).selected(index == self.selected).build() {
+                            chosen = Some(*action);
+                        }
+
+                        if moved && index == self.selected {
+                            ui.set_scroll_here_y();
+                        }
+                    }
+                });
+            });
+
+        if chosen.is_some() || close {
+            self.open = false;
+        }
+
+        chosen
+    }
+}
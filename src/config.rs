@@ -0,0 +1,358 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use chrono::Locale;
+use serde::{Deserialize, Serialize};
+use anyhow::{bail, Context, Error, Result};
+use crate::consts::*;
+use crate::fonts::FallbackFont;
+use crate::ipc::IpcCommand;
+use crate::layout::Layout;
+use crate::renderer::WallpaperMode;
+use crate::widgets::launcher::LauncherEntry;
+use crate::utils::resolve_path;
+
+/// When the desktop is redrawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedrawMode {
+    /// Every frame, needed for animated background shaders.
+    Continuous,
+    /// Only on input and once per second for the clock.
+    Reactive,
+}
+
+/// An RGBA color, written as "#RRGGBB" or "#RRGGBBAA" in the config file.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Color(pub [f32; 4]);
+
+impl TryFrom<String> for Color {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        let hex = value.strip_prefix('#').unwrap_or(&value);
+
+        if !hex.is_ascii() || (hex.len() != 6 && hex.len() != 8) {
+            bail!("Invalid color '{value}', expected '#RRGGBB' or '#RRGGBBAA'");
+        }
+
+        let mut color = [1f32; 4]; // opaque without an alpha channel
+
+        for (channel, digits) in color.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            let digits = std::str::from_utf8(digits).context("Color is not ASCII")?;
+            let value = u8::from_str_radix(digits, 16)
+                .with_context(|| format!("Invalid color '{value}', '{digits}' is not hexadecimal"))?;
+
+            *channel = value as f32 / 255f32;
+        }
+
+        Ok(Self(color))
+    }
+}
+
+impl From<Color> for String {
+    fn from(Color(color): Color) -> Self {
+        let [r, g, b, a] = color.map(|channel| (channel.clamp(0f32, 1f32) * 255f32).round() as u8);
+        format!("#{r:02X}{g:02X}{b:02X}{a:02X}")
+    }
+}
+
+/// The screen edge the taskbar is docked to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskbarPosition {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl TaskbarPosition {
+    /// Whether widgets are stacked instead of laid out in columns.
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Self::Left | Self::Right)
+    }
+}
+
+/// A system-wide shortcut, e.g. `{ keys = "Ctrl+Alt+S", command = "screenshot" }`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Hotkey {
+    pub keys: String, // modifiers and a key joined by '+'
+    #[serde(flatten)]
+    pub command: IpcCommand,
+}
+
+/// User configuration, loaded from `config.toml` next to the executable.
+///
+/// Missing fields fall back to the defaults in `consts`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    pub window_size: [u32; 2],
+    pub window_position: Option<[i32; 2]>, // of the window in windowed mode, saved on exit
+    pub window_title: String,
+    pub font_size: f64,
+    pub font_path: Option<String>,
+    pub icon_font_path: Option<String>, // e.g. Font Awesome, merged into the font for widget icons
+    pub fallback_fonts: Vec<FallbackFont>, // loaded once text needs them
+    pub time_format: String,
+    pub use_24h: bool, // overrides `time_format`
+    pub date_format: String,
+    pub locale: Option<String>, // e.g. "en_US", for dates and `I18N_DIRECTORY`'s strings, system locale when `None`
+    pub taskbar_position: TaskbarPosition,
+    pub taskbar_height: f32, // also the height of each widget when vertical
+    pub taskbar_width: f32, // only used when vertical
+    pub taskbar_auto_hide: bool,
+    pub taskbar_slide_speed: f32, // full slides per second
+    pub taskbar_blur_radius: f32, // in pixels, disabled at 0
+    pub taskbar_blur_tint: Color,
+    pub taskbar_opacity: f32, // of the background, 0 to 1
+    pub accent_color: Option<Color>, // imgui's default blue when `None`
+    pub window_opacity: BTreeMap<String, f32>, // by name in `MANAGED_WINDOWS`, opaque when missing
+    pub workspace_count: usize, // Windows only
+    pub tiling_layout: Layout, // Windows only, applied when changed or on the tile-windows command
+    pub tiling_gap: i32, // in pixels, between tiles and around them
+    pub widgets: Vec<String>, // taskbar widget names in order (e.g. "clock" or a plugin's), all of them when empty
+    pub disks: Vec<String>, // mount points (e.g. "C:\\"), all drives when empty
+    pub timer_work_minutes: u32,
+    pub timer_break_minutes: u32,
+    pub weather_location: Option<[f64; 2]>, // latitude and longitude, the widget is hidden when `None`
+    pub weather_interval_minutes: u32,
+    pub clipboard_history_limit: usize, // Windows only, entries kept
+    pub lock_password_hash: Option<String>, // from the hash-password command, locking is disabled when `None`
+    pub lock_idle_minutes: Option<u32>, // locks after this long without input, never when `None`
+    pub screensaver_idle_minutes: Option<u32>, // starts after this long without input, never when `None`
+    pub theme: String, // "dark", "light", "classic" or the path of a theme file
+    pub system_theme: bool, // follows Windows light/dark mode over `theme` when it can be read
+    pub wallpaper_path: Option<String>,
+    pub wallpaper_mode: WallpaperMode,
+    pub slideshow_directory: Option<String>, // cycles through its images over `wallpaper_path`
+    pub slideshow_interval_seconds: u64,
+    pub night_light: bool, // follows the schedule below, F6 toggles it either way
+    pub night_light_start: String, // "HH:MM" in local time
+    pub night_light_end: String,
+    pub night_light_temperature: f32, // in kelvin
+    pub night_light_intensity: f32, // 0 to 1
+    pub launcher: Vec<LauncherEntry>,
+    pub hotkeys: Vec<Hotkey>, // Windows only
+    pub target_fps: Option<u32>, // uncapped (vsync only) when `None`
+    pub redraw_mode: RedrawMode,
+    pub confirm_exit: bool, // asks before closing the desktop, e.g. on Alt+F4
+    pub crash_log_limit: usize,
+    pub gl_debug: bool, // requires a restart
+    // command line overrides aren't saved
+    #[serde(skip_serializing)]
+    pub windowed: bool, // a normal window for development, requires a restart
+    #[serde(skip_serializing)]
+    pub monitor: Option<usize>, // index into the available monitors, all of them when `None`, requires a restart
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            window_size: WINDOW_SIZE,
+            window_position: None,
+            window_title: WINDOW_TITLE.to_string(),
+            font_size: FONT_SIZE,
+            font_path: FONT_PATH.map(str::to_string),
+            icon_font_path: ICON_FONT_PATH.map(str::to_string),
+            fallback_fonts: Vec::new(),
+            time_format: TIME_FORMAT.to_string(),
+            use_24h: false,
+            date_format: DATE_FORMAT.to_string(),
+            locale: None,
+            taskbar_position: TASKBAR_POSITION,
+            taskbar_height: TASKBAR_HEIGHT,
+            taskbar_width: TASKBAR_WIDTH,
+            taskbar_auto_hide: false,
+            taskbar_slide_speed: TASKBAR_SLIDE_SPEED,
+            taskbar_blur_radius: TASKBAR_BLUR_RADIUS,
+            taskbar_blur_tint: TASKBAR_BLUR_TINT,
+            taskbar_opacity: TASKBAR_OPACITY,
+            accent_color: None,
+            window_opacity: BTreeMap::new(),
+            workspace_count: WORKSPACE_COUNT,
+            tiling_layout: Layout::Floating,
+            tiling_gap: TILING_GAP,
+            widgets: Vec::new(),
+            disks: Vec::new(),
+            timer_work_minutes: TIMER_WORK_MINUTES,
+            timer_break_minutes: TIMER_BREAK_MINUTES,
+            weather_location: None,
+            weather_interval_minutes: WEATHER_INTERVAL_MINUTES,
+            clipboard_history_limit: CLIPBOARD_HISTORY_LIMIT,
+            lock_password_hash: None,
+            lock_idle_minutes: None,
+            screensaver_idle_minutes: None,
+            theme: THEME.to_string(),
+            system_theme: true,
+            wallpaper_path: WALLPAPER_PATH.map(str::to_string),
+            wallpaper_mode: WALLPAPER_MODE,
+            slideshow_directory: None,
+            slideshow_interval_seconds: SLIDESHOW_INTERVAL_SECONDS,
+            night_light: false,
+            night_light_start: NIGHT_LIGHT_START.to_string(),
+            night_light_end: NIGHT_LIGHT_END.to_string(),
+            night_light_temperature: NIGHT_LIGHT_TEMPERATURE,
+            night_light_intensity: NIGHT_LIGHT_INTENSITY,
+            launcher: Vec::new(),
+            hotkeys: Vec::new(),
+            target_fps: None,
+            redraw_mode: RedrawMode::Continuous,
+            confirm_exit: CONFIRM_EXIT,
+            crash_log_limit: CRASH_LOG_LIMIT,
+            gl_debug: GL_DEBUG,
+            windowed: false,
+            monitor: None,
+        }
+    }
+}
+
+impl Config {
+    /// Opacity of the managed window `name`, never so low it can't be found again.
+    pub fn window_opacity(&self, name: &str) -> f32 {
+        self.window_opacity
+            .get(name)
+            .map_or(1f32, |opacity| opacity.clamp(WINDOW_OPACITY_MIN, 1f32))
+    }
+
+    /// Loads the configuration file next to the executable,
+    /// using the defaults when it doesn't exist.
+    pub fn load() -> Result<Self> {
+        Self::load_from(resolve_path(CONFIG_PATH)?)
+    }
+
+    /// Loads the configuration file at `path`, using the defaults when it doesn't exist.
+    pub fn load_from<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file '{}'", path.display()))?;
+
+        // the toml error already reports the line and column
+        toml::from_str(&source).with_context(|| format!("Failed to parse config file '{}'", path.display()))
+    }
+
+    /// Writes the configuration to `path`, replacing the file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let source = toml::to_string_pretty(self).context("Failed to serialize config")?;
+
+        std::fs::write(path, source).with_context(|| format!("Failed to write config file '{}'", path.display()))
+    }
+
+    pub fn time_format(&self) -> &str {
+        if self.use_24h {
+            TIME_FORMAT_24H
+        } else {
+            &self.time_format
+        }
+    }
+
+    /// The configured locale's name (e.g. "en_US"), or the system locale's.
+    pub fn locale_name(&self) -> Option<String> {
+        // BCP 47 tags (e.g. "en-US") use
+        // dashes instead of underscores
+        self.locale
+            .clone()
+            .or_else(sys_locale::get_locale)
+            .map(|name| name.replace('-', "_"))
+    }
+
+    /// Resolves the configured locale, falling back to the system locale and then to POSIX.
+    pub fn locale(&self) -> Locale {
+        self.locale_name()
+            .and_then(|name| Locale::try_from(name.as_str()).ok())
+            .unwrap_or(Locale::POSIX)
+    }
+}
+
+/// Settings from the `VeilDE` builder (e.g. `--windowed`), kept apart
+/// from the config so they apply again after every reload.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigOverrides {
+    pub window_title: Option<String>,
+    pub window_size: Option<[u32; 2]>,
+    pub font_path: Option<String>,
+    pub wallpaper: Option<(String, WallpaperMode)>,
+    pub windowed: Option<bool>,
+    pub monitor: Option<usize>,
+}
+
+impl ConfigOverrides {
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(title) = &self.window_title {
+            config.window_title = title.clone();
+        }
+
+        if let Some(size) = self.window_size {
+            config.window_size = size;
+        }
+
+        if let Some(path) = &self.font_path {
+            config.font_path = Some(path.clone());
+        }
+
+        if let Some((path, mode)) = &self.wallpaper {
+            config.wallpaper_path = Some(path.clone());
+            config.wallpaper_mode = *mode;
+        }
+
+        if let Some(windowed) = self.windowed {
+            config.windowed = windowed;
+        }
+
+        if let Some(index) = self.monitor {
+            config.monitor = Some(index);
+        }
+    }
+}
+
+/// Watches a configuration file and its script for changes, stops watching when dropped.
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<notify::Event>>,
+    path: PathBuf,
+    script_path: PathBuf,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: PathBuf) -> Result<Self> {
+        let directory = path.parent().context("Config path has no parent directory")?;
+        let (tx, rx) = channel();
+
+        // editors often replace the file instead of
+        // writing to it, so the directory is watched
+        let mut watcher = notify::recommended_watcher(tx).context("Failed to create config watcher")?;
+        watcher
+            .watch(directory, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch '{}'", directory.display()))?;
+
+        Ok(
+            Self {
+                _watcher: watcher,
+                events: rx,
+                script_path: path.with_file_name(CONFIG_SCRIPT_PATH),
+                path,
+            }
+        )
+    }
+
+    /// Reloads the configuration if the file or its script changed since the last poll,
+    /// the script is left for the caller to run.
+    pub fn poll(&self) -> Option<Result<Config>> {
+        let changed = self.events
+            .try_iter()
+            .filter_map(Result::ok)
+            .filter(|event| matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)))
+            .any(|event| event.paths.iter().any(|path| *path == self.path || *path == self.script_path));
+
+        changed.then(|| Config::load_from(&self.path))
+    }
+}
@@ -1,9 +1,9 @@
+use std::time::Duration;
+use crate::config::{Color, TaskbarPosition};
+use crate::renderer::WallpaperMode;
+
 pub const VERTEX_SHADER_SOURCE: &str = r#"
-const vec2 verts[3] = vec2[3](
-    vec2(0.5f, 1.0f),
-    vec2(0.0f, 0.0f),
-    vec2(1.0f, 0.0f)
-);
+in vec2 a_position; // from `BACKGROUND_VERTICES`
 
 out vec2 vert;
 out vec4 color;
@@ -21,7 +21,7 @@ vec4 srgb_to_linear(vec4 srgb_color) {
 }
 
 void main() {
-    vert = verts[gl_VertexID];
+    vert = a_position;
     color = srgb_to_linear(vec4(vert, 0.5, 1.0));
     gl_Position = vec4(vert - 0.5, 0.0, 1.0);
 }
@@ -30,6 +30,9 @@ pub const FRAGMENT_SHADER_SOURCE: &str = r#"
 in vec2 vert;
 in vec4 color;
 
+uniform vec2 u_resolution; // framebuffer size in pixels
+uniform float u_time; // seconds since startup
+
 out vec4 frag_color;
 
 vec4 linear_to_srgb(vec4 linear_color) {
@@ -47,10 +50,360 @@ void main() {
     frag_color = linear_to_srgb(color);
 }
 "#;
+pub const WALLPAPER_VERTEX_SHADER_SOURCE: &str = r#"
+const vec2 verts[4] = vec2[4](
+    vec2(0.0f, 0.0f),
+    vec2(1.0f, 0.0f),
+    vec2(0.0f, 1.0f),
+    vec2(1.0f, 1.0f)
+);
+
+uniform vec2 u_uv_scale; // fraction of the image visible on each axis
+uniform vec2 u_incoming_uv_scale; // the same for `u_incoming`
+
+out vec2 uv;
+out vec2 incoming_uv;
+
+void main() {
+    vec2 vert = verts[gl_VertexID];
+    uv = (vert - 0.5) * u_uv_scale + 0.5;
+    uv.y = 1.0 - uv.y; // images are stored top row first
+    incoming_uv = (vert - 0.5) * u_incoming_uv_scale + 0.5;
+    incoming_uv.y = 1.0 - incoming_uv.y;
+    gl_Position = vec4(vert * 2.0 - 1.0, 0.0, 1.0);
+}
+"#;
+pub const WALLPAPER_FRAGMENT_SHADER_SOURCE: &str = r#"
+in vec2 uv;
+in vec2 incoming_uv;
+
+uniform sampler2D u_texture;
+uniform sampler2D u_incoming; // the next slideshow image
+uniform float u_mix; // crossfade from `u_texture` to `u_incoming`, 0 to 1
+
+out vec4 frag_color;
+
+void main() {
+    frag_color = mix(texture(u_texture, uv), texture(u_incoming, incoming_uv), u_mix);
+}
+"#;
+
+pub const BLUR_VERTEX_SHADER_SOURCE: &str = r#"
+const vec2 verts[4] = vec2[4](
+    vec2(0.0f, 0.0f),
+    vec2(1.0f, 0.0f),
+    vec2(0.0f, 1.0f),
+    vec2(1.0f, 1.0f)
+);
+
+out vec2 uv;
+
+void main() {
+    uv = verts[gl_VertexID];
+    gl_Position = vec4(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+"#;
+pub const BLUR_FRAGMENT_SHADER_SOURCE: &str = r#"
+in vec2 uv;
+
+uniform sampler2D u_texture;
+uniform vec2 u_resolution; // size of `u_texture` in pixels
+uniform vec2 u_direction; // the axis blurred by this pass
+uniform float u_radius; // in pixels, 0 only applies the tint
+uniform vec4 u_tint; // mixed in by its alpha
+
+out vec4 frag_color;
+
+void main() {
+    vec2 texel = u_direction / u_resolution;
+    float sigma = max(u_radius / 2.0, 0.0001);
+
+    vec4 sum = texture(u_texture, uv);
+    float total = 1.0;
+
+    for (float i = 1.0; i <= u_radius; i += 1.0) {
+        float weight = exp(-(i * i) / (2.0 * sigma * sigma));
+        sum += (texture(u_texture, uv + texel * i) + texture(u_texture, uv - texel * i)) * weight;
+        total += 2.0 * weight;
+    }
+
+    vec4 blurred = sum / total;
+    frag_color = vec4(mix(blurred.rgb, u_tint.rgb, u_tint.a), max(blurred.a, u_tint.a));
+}
+"#;
+
+/// Drawn over the finished frame, using `BLUR_VERTEX_SHADER_SOURCE`'s full-screen quad.
+pub const NIGHT_LIGHT_FRAGMENT_SHADER_SOURCE: &str = r#"
+in vec2 uv;
+
+uniform float u_temperature; // in kelvin
+uniform float u_strength; // 0 leaves colors untouched
+
+out vec4 frag_color;
+
+vec4 srgb_to_linear(vec4 srgb_color) {
+    vec3 srgb = srgb_color.rgb;
+    vec3 selector = ceil(srgb - 0.04045);
+    vec3 less_than_branch = srgb / 12.92;
+    vec3 greater_than_branch = pow((srgb + 0.055) / 1.055, vec3(2.4));
+    return vec4(
+        mix(less_than_branch, greater_than_branch, selector),
+        srgb_color.a
+    );
+}
+
+// Tanner Helland's fit of blackbody colors, in sRGB
+vec3 kelvin_to_srgb(float kelvin) {
+    float t = kelvin / 100.0;
+    vec3 color = vec3(1.0);
+
+    if (t > 66.0) {
+        color.r = 1.29293618606 * pow(t - 60.0, -0.1332047592);
+        color.g = 1.12989086089 * pow(t - 60.0, -0.0755148492);
+    } else {
+        color.g = 0.39008157876 * log(t) - 0.63184144378;
+        color.b = t <= 19.0 ? 0.0 : 0.54320678911 * log(t - 10.0) - 1.19625408914;
+    }
+
+    return clamp(color, 0.0, 1.0);
+}
+
+void main() {
+    // multiplied with the frame by blending, which
+    // happens in linear space like the rest of VeilDE
+    vec3 tint = srgb_to_linear(vec4(kelvin_to_srgb(u_temperature), 1.0)).rgb;
+    frag_color = vec4(mix(vec3(1.0), tint, u_strength), 1.0);
+}
+"#;
+
+/// Drawn instead of the desktop while idle, using `BLUR_VERTEX_SHADER_SOURCE`'s full-screen quad.
+pub const SCREENSAVER_FRAGMENT_SHADER_SOURCE: &str = r#"
+in vec2 uv;
+
+uniform vec2 u_resolution; // framebuffer size in pixels
+uniform float u_time; // seconds since the screensaver started
+uniform float u_dim; // 0 leaves colors untouched, 1 is black
+
+out vec4 frag_color;
+
+void main() {
+    // keeps the waves round on wide screens
+    vec2 p = (uv - 0.5) * vec2(u_resolution.x / u_resolution.y, 1.0) * 4.0;
+    float t = u_time * 0.3;
+
+    float v = sin(p.x + t)
+        + sin((p.y + t) * 0.5)
+        + sin((p.x + p.y + t) * 0.5)
+        + sin(length(p + vec2(sin(t * 0.7), cos(t * 0.5)) * 2.0) + t);
+
+    vec3 color = 0.5 + 0.5 * cos(v * 1.5 + vec3(0.0, 2.1, 4.2));
+    frag_color = vec4(color * 0.6 * (1.0 - u_dim), 1.0);
+}
+"#;
+
+/// Shown while a monitor initializes, using `BLUR_VERTEX_SHADER_SOURCE`'s full-screen quad.
+pub const SPLASH_FRAGMENT_SHADER_SOURCE: &str = r#"
+in vec2 uv;
+
+uniform vec2 u_resolution; // framebuffer size in pixels
+uniform float u_progress; // 0 to 1
+
+out vec4 frag_color;
+
+void main() {
+    // in pixels from the center
+    vec2 p = (uv - 0.5) * u_resolution;
+    vec3 color = vec3(0.08, 0.08, 0.1);
+
+    float ring = abs(length(p - vec2(0.0, 24.0)) - 36.0);
+    color = mix(color, vec3(0.85), 1.0 - smoothstep(4.0, 5.5, ring));
+
+    vec2 bar = abs(p - vec2(0.0, -48.0)) - vec2(80.0, 2.0);
+
+    if (max(bar.x, bar.y) < 0.0) {
+        color = p.x < 160.0 * u_progress - 80.0 ? vec3(0.85) : vec3(0.25);
+    }
+
+    frag_color = vec4(color, 1.0);
+}
+"#;
+
+/// Background geometry in `a_position`, in 0-1 screen space.
+pub const BACKGROUND_VERTICES: [[f32; 2]; 3] = [
+    [0.5, 1.0],
+    [0.0, 0.0],
+    [1.0, 0.0],
+];
+
+// Font Awesome codepoints, drawn when an icon font is configured
+pub const ICON_GLYPH_RANGES: &[u32] = &[0xE000, 0xF8FF, 0]; // the private use area
+pub const EMOJI_GLYPH_RANGES: &[u32] = &[0x2600, 0x27BF, 0x1F300, 0x1FAFF, 0];
+pub const ICON_BATTERY_FULL: char = '\u{f240}';
+pub const ICON_BATTERY_THREE_QUARTERS: char = '\u{f241}';
+pub const ICON_BATTERY_HALF: char = '\u{f242}';
+pub const ICON_BATTERY_QUARTER: char = '\u{f243}';
+pub const ICON_BATTERY_EMPTY: char = '\u{f244}';
+pub const ICON_BOLT: char = '\u{f0e7}';
+pub const ICON_VOLUME_HIGH: char = '\u{f028}';
+pub const ICON_VOLUME_LOW: char = '\u{f027}';
+pub const ICON_VOLUME_MUTE: char = '\u{f6a9}';
+pub const ICON_WIFI: char = '\u{f1eb}';
+pub const ICON_KEYBOARD: char = '\u{f11c}';
+pub const ICON_BELL: char = '\u{f0f3}';
+pub const ICON_BELL_SLASH: char = '\u{f1f6}';
+pub const ICON_CLIPBOARD: char = '\u{f328}';
+pub const ICON_PLAY: char = '\u{f04b}';
+pub const ICON_PAUSE: char = '\u{f04c}';
+pub const ICON_BACKWARD: char = '\u{f048}';
+pub const ICON_FORWARD: char = '\u{f051}';
+pub const ICON_BRIGHTNESS: char = '\u{f185}';
+pub const ICON_SLIDERS: char = '\u{f1de}';
+pub const ICON_WEATHER_CLEAR: char = '\u{f185}';
+pub const ICON_WEATHER_PARTLY_CLOUDY: char = '\u{f6c4}';
+pub const ICON_WEATHER_CLOUDY: char = '\u{f0c2}';
+pub const ICON_WEATHER_FOG: char = '\u{f75f}';
+pub const ICON_WEATHER_RAIN: char = '\u{f73d}';
+pub const ICON_WEATHER_SNOW: char = '\u{f2dc}';
+
+pub const VERTEX_SHADER_PATH: &str = "shaders/vertex.glsl";
+pub const FRAGMENT_SHADER_PATH: &str = "shaders/fragment.glsl";
+pub const GLSL_VERSION: Option<&str> = None; // detected from the driver when `None`
+
+pub const CONFIG_PATH: &str = "config.toml";
+pub const CONFIG_SCRIPT_PATH: &str = "config.rhai"; // next to the config file, run over it when loaded
+pub const CONFIG_SCRIPT_OPERATION_LIMIT: u64 = 1_000_000; // so an endless loop can't hang the desktop
+pub const CRASH_LOG_DIRECTORY: &str = "crash";
+pub const SCREENSHOT_DIRECTORY: &str = "screenshots";
+pub const INSTANCE_LOCK_PATH: &str = "VeilDE-rs.lock";
+pub const NOTES_PATH: &str = "notes.json";
+pub const I18N_DIRECTORY: &str = "i18n"; // string tables named after locales, e.g. "de.toml"
+pub const PLUGIN_DIRECTORY: &str = "plugins"; // shared libraries loaded as widgets at startup
+pub const PLUGIN_ABI_VERSION: u32 = 1; // bumped whenever `Widget` or `Config` change
+pub const PLUGIN_ABI_SYMBOL: &[u8] = b"VEILDE_PLUGIN_ABI";
+pub const PLUGIN_ENTRY_SYMBOL: &[u8] = b"veilde_plugin";
+pub const IPC_PIPE_NAME: &str = r"\\.\pipe\VeilDE-rs";
+pub const IPC_MESSAGE_LIMIT: usize = 64 * 1024; // in bytes
 
+// defaults for anything missing from the config file
 pub const WINDOW_SIZE: [u32; 2] = [1600, 900];
 pub const WINDOW_TITLE: &str = "VeilDE-rs";
 pub const FONT_SIZE: f64 = 14.0;
+pub const FONT_PATH: Option<&str> = None; // relative to the executable, embedded font when `None`
+pub const ICON_FONT_PATH: Option<&str> = None; // relative to the executable, text labels when `None`
 pub const TIME_FORMAT: &str = "%I:%M %p";
-pub const DATE_FORMAT: &str = "%m/%d/%G";
+pub const TIME_FORMAT_24H: &str = "%H:%M";
+pub const DATE_FORMAT: &str = "%x"; // the locale's date representation
+pub const TASKBAR_POSITION: TaskbarPosition = TaskbarPosition::Bottom;
 pub const TASKBAR_HEIGHT: f32 = 50.0;
+pub const TASKBAR_WIDTH: f32 = 200.0;
+pub const TASKBAR_SLIDE_SPEED: f32 = 6.0;
+pub const TASKBAR_HIDDEN_SIZE: f32 = 2.0; // the strip left on screen when auto-hidden
+pub const TASKBAR_REVEAL_DISTANCE: f32 = 4.0; // from the screen edge
+pub const TASKBAR_BLUR_RADIUS: f32 = 16.0; // in pixels, disabled at 0
+pub const TASKBAR_BLUR_TINT: Color = Color([0.0, 0.0, 0.0, 0.35]);
+pub const TASKBAR_OPACITY: f32 = 0.5;
+pub const THEME: &str = "dark";
+pub const WORKSPACE_COUNT: usize = 4;
+pub const SYSTEM_USAGE_INTERVAL: Duration = Duration::from_secs(1);
+pub const SYSTEM_USAGE_BAR_WIDTH: f32 = 60.0;
+pub const SYSTEM_INFO_INTERVAL: Duration = Duration::from_secs(1);
+pub const SYSTEM_INFO_POPUP_ID: &str = "_system_info";
+pub const CALENDAR_POPUP_ID: &str = "_calendar";
+pub const CALENDAR_TODAY_COLOR: [f32; 4] = [0.4, 0.7, 1.0, 1.0];
+pub const LAUNCHER_BUTTON_LABEL: &str = "Start";
+pub const TASKBAR_MENU_ID: &str = "_taskbar_menu";
+pub const TILING_GAP: i32 = 8; // in pixels
+pub const TILING_MASTER_RATIO: f32 = 0.5; // of the width, for the master window
+pub const WINDOW_OPACITY_MIN: f32 = 0.2;
+pub const MANAGED_WINDOWS: &[&str] = &["Launcher", "Command palette", "Settings", "About", "Log console", "Notes", "Notifications"];
+pub const DO_NOT_DISTURB_DIM_ALPHA: f32 = 0.4; // of non-essential widgets
+pub const DESKTOP_MENU_ID: &str = "_desktop_menu";
+pub const POWER_MENU_ID: &str = "_power_menu";
+pub const LAUNCHER_SIZE: [f32; 2] = [300.0, 400.0];
+pub const COMMAND_PALETTE_SIZE: [f32; 2] = [480.0, 320.0];
+pub const COMMAND_PALETTE_EMPTY_TEXT: &str = "No matching commands";
+pub const NOTIFICATION_LIMIT: usize = 5;
+pub const NOTIFICATION_WIDTH: f32 = 320.0;
+pub const CONFIRM_EXIT: bool = true;
+pub const CRASH_LOG_LIMIT: usize = 20;
+pub const REACTIVE_EXTRA_FRAMES: u32 = 2; // imgui needs a few frames to settle after input
+pub const DISKS_INTERVAL: Duration = Duration::from_secs(30);
+pub const DISKS_BAR_WIDTH: f32 = 120.0;
+pub const DISKS_POPUP_ID: &str = "_disks";
+pub const NETWORK_INTERVAL: Duration = Duration::from_secs(1);
+pub const NETWORK_POPUP_ID: &str = "_network";
+pub const OPEN_WINDOWS_INTERVAL: Duration = Duration::from_secs(1);
+pub const OPEN_WINDOW_TITLE_LENGTH: usize = 24;
+pub const THUMBNAIL_INTERVAL: Duration = Duration::from_millis(500); // while hovered
+pub const THUMBNAIL_SIZE: [u32; 2] = [240, 160]; // the most either side is scaled to
+pub const BATTERY_INTERVAL: Duration = Duration::from_secs(5);
+pub const KEYBOARD_INTERVAL: Duration = Duration::from_millis(500);
+pub const VOLUME_INTERVAL: Duration = Duration::from_secs(2);
+pub const VOLUME_POPUP_ID: &str = "_volume";
+pub const QUICK_SETTINGS_INTERVAL: Duration = Duration::from_secs(5);
+pub const QUICK_SETTINGS_TILE_SIZE: [f32; 2] = [96f32, 48f32];
+pub const QUICK_SETTINGS_POPUP_ID: &str = "_quick_settings";
+pub const BRIGHTNESS_INTERVAL: Duration = Duration::from_secs(5);
+pub const BRIGHTNESS_POPUP_ID: &str = "_brightness";
+pub const VISUALIZER_INTERVAL: Duration = Duration::from_millis(30);
+pub const VISUALIZER_RETRY_INTERVAL: Duration = Duration::from_secs(10); // after the capture ended
+pub const VISUALIZER_BUFFER_DURATION: Duration = Duration::from_millis(200);
+pub const VISUALIZER_WINDOW: usize = 1024; // samples each spectrum is taken over
+pub const VISUALIZER_BARS: usize = 16;
+pub const VISUALIZER_BAR_WIDTH: f32 = 3f32;
+pub const VISUALIZER_BAR_GAP: f32 = 1f32;
+pub const VISUALIZER_MIN_FREQUENCY: f32 = 60f32; // in hertz
+pub const VISUALIZER_MAX_FREQUENCY: f32 = 16000f32;
+pub const VISUALIZER_FLOOR_DECIBELS: f32 = -60f32; // quieter bands show empty
+pub const VISUALIZER_FALL_RATE: f32 = 2f32; // bar heights per second
+pub const MEDIA_INTERVAL: Duration = Duration::from_secs(1);
+pub const MEDIA_TITLE_LENGTH: usize = 32;
+pub const MEDIA_ART_SIZE: [u32; 2] = [200, 200]; // the most either side is scaled to
+pub const MEDIA_POPUP_ID: &str = "_media";
+pub const TIMER_WORK_MINUTES: u32 = 25;
+pub const TIMER_BREAK_MINUTES: u32 = 5;
+pub const WEATHER_INTERVAL_MINUTES: u32 = 15;
+pub const TIMER_FLASH_DURATION: Duration = Duration::from_secs(10);
+pub const TIMER_FLASH_COLOR: [f32; 4] = [1.0, 0.8, 0.3, 1.0];
+pub const TIMER_POPUP_ID: &str = "_timer";
+pub const WEATHER_API_URL: &str = "https://api.open-meteo.com/v1/forecast";
+pub const WEATHER_REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+pub const TRAY_EMPTY_TEXT: &str = "No tray icons";
+pub const CLIPBOARD_HISTORY_LIMIT: usize = 25;
+pub const CLIPBOARD_IMAGE_LIMIT: usize = 64 * 1024 * 1024; // in bytes, larger images aren't kept
+pub const CLIPBOARD_PREVIEW_SIZE: [u32; 2] = [160, 90]; // the most either side is scaled to
+pub const CLIPBOARD_ENTRY_LENGTH: usize = 48; // characters shown of text entries
+pub const CLIPBOARD_POPUP_ID: &str = "_clipboard";
+pub const CLIPBOARD_EMPTY_TEXT: &str = "Nothing copied yet";
+pub const NOTE_SIZE: [f32; 2] = [220.0, 180.0];
+pub const SETTINGS_SIZE: [f32; 2] = [420.0, 520.0];
+pub const LOG_LINE_LIMIT: usize = 500;
+pub const LOG_CONSOLE_SIZE: [f32; 2] = [640.0, 320.0];
+pub const LOG_ERROR_COLOR: [f32; 4] = [1.0, 0.4, 0.4, 1.0];
+pub const LOG_WARN_COLOR: [f32; 4] = [1.0, 0.8, 0.3, 1.0];
+pub const LOCK_SCREEN_WIDTH: f32 = 280.0;
+pub const LOCK_SCREEN_COLOR: [f32; 4] = [0.05, 0.05, 0.08, 1.0];
+pub const LOCK_SCREEN_ERROR_COLOR: [f32; 4] = [1.0, 0.4, 0.4, 1.0];
+pub const LOCK_PASSWORD_SCHEME: &str = "pbkdf2-sha256";
+pub const LOCK_PASSWORD_ROUNDS: u32 = 600_000; // OWASP's recommendation for PBKDF2-HMAC-SHA256
+pub const LOCK_PASSWORD_SALT_LENGTH: usize = 16;
+pub const LOCK_RETRY_DELAY: Duration = Duration::from_secs(2); // after a wrong password
+pub const SCREENSAVER_DIM_DELAY: Duration = Duration::from_secs(5 * 60); // after it starts
+pub const SCREENSAVER_DIM_DURATION: Duration = Duration::from_secs(30);
+pub const REGION_CAPTURE_DIM_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.5];
+pub const REGION_CAPTURE_BORDER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+pub const FPS_SAMPLES: usize = 120;
+pub const MONITOR_SYNC_INTERVAL: Duration = Duration::from_secs(2);
+pub const GL_DEBUG: bool = cfg!(debug_assertions);
+pub const GL_RECOVERY_ATTEMPTS: u32 = 3;
+pub const GL_ERROR_LIMIT: usize = 8; // read per check, a lost context may report them endlessly
+pub const FULLSCREEN_OVERSCAN: u32 = 1; // extra pixels keeping the window composited, see `init_glutin`
+pub const WALLPAPER_PATH: Option<&str> = None; // relative to the executable, background shader when `None`
+pub const WALLPAPER_MODE: WallpaperMode = WallpaperMode::Cover;
+pub const SLIDESHOW_INTERVAL_SECONDS: u64 = 300;
+pub const WALLPAPER_CROSSFADE_DURATION: Duration = Duration::from_secs(2);
+pub const NIGHT_LIGHT_START: &str = "20:00";
+pub const NIGHT_LIGHT_END: &str = "07:00";
+pub const NIGHT_LIGHT_TEMPERATURE: f32 = 3400.0; // in kelvin, 6500 being neutral
+pub const NIGHT_LIGHT_INTENSITY: f32 = 1.0;
+pub const NIGHT_LIGHT_RAMP_DURATION: Duration = Duration::from_secs(60);
@@ -0,0 +1,293 @@
+use imgui::{
+    internal::RawCast,
+    Context as ImGuiContext,
+    FontConfig,
+    FontGlyphRanges,
+    FontSource,
+    Ui,
+};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use imgui_sys::ImGuiFreeTypeBuilderFlags_Bitmap;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use crate::config::Config;
+use crate::consts::*;
+use crate::utils::resolve_path;
+
+/// A script or symbol set that a fallback font provides.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GlyphRange {
+    ChineseFull,
+    ChineseSimplifiedCommon,
+    Japanese,
+    Korean,
+    Cyrillic,
+    Thai,
+    Vietnamese,
+    Emoji,
+}
+
+impl GlyphRange {
+    fn glyph_ranges(self) -> FontGlyphRanges {
+        match self {
+            Self::ChineseFull => FontGlyphRanges::chinese_full(),
+            Self::ChineseSimplifiedCommon => FontGlyphRanges::chinese_simplified_common(),
+            Self::Japanese => FontGlyphRanges::japanese(),
+            Self::Korean => FontGlyphRanges::korean(),
+            Self::Cyrillic => FontGlyphRanges::cyrillic(),
+            Self::Thai => FontGlyphRanges::thai(),
+            Self::Vietnamese => FontGlyphRanges::vietnamese(),
+            Self::Emoji => FontGlyphRanges::from_slice(EMOJI_GLYPH_RANGES),
+        }
+    }
+
+    /// Whether `character` belongs to the script, roughly,
+    /// to decide if a fallback font is worth loading.
+    fn covers(self, character: char) -> bool {
+        let ranges: &[(u32, u32)] = match self {
+            Self::ChineseFull | Self::ChineseSimplifiedCommon | Self::Japanese => {
+                &[(0x3000, 0x30FF), (0x31F0, 0x31FF), (0x4E00, 0x9FAF), (0xFF00, 0xFFEF)]
+            }
+            Self::Korean => &[(0x3131, 0x3163), (0xAC00, 0xD7A3)],
+            Self::Cyrillic => &[(0x0400, 0x052F), (0x2DE0, 0x2DFF), (0xA640, 0xA69F)],
+            Self::Thai => &[(0x0E00, 0x0E7F)],
+            Self::Vietnamese => &[(0x0102, 0x01B0), (0x1EA0, 0x1EF9)],
+            Self::Emoji => &[(0x2600, 0x27BF), (0x1F300, 0x1FAFF)],
+        };
+
+        ranges.iter().any(|(start, end)| (*start..=*end).contains(&(character as u32)))
+    }
+}
+
+/// A font merged into the atlas for glyphs the main font lacks.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct FallbackFont {
+    pub path: String,
+    pub glyphs: GlyphRange,
+}
+
+thread_local! {
+    // glyphs that were drawn without being in the atlas,
+    // collected during a frame by `request_glyphs`
+    static MISSING_GLYPHS: RefCell<BTreeSet<char>> = const { RefCell::new(BTreeSet::new()) };
+}
+
+/// The font files merged into the atlas, read once so rebuilding
+/// it (e.g. on a scale factor change) doesn't touch the disk.
+pub struct FontFiles {
+    pub base: Vec<u8>,
+    pub icons: Option<Vec<u8>>,
+
+    // only read once text needs them, as
+    // CJK fonts take a lot of atlas space
+    fallbacks: Vec<(FallbackFont, Option<Vec<u8>>)>,
+    failed: Vec<String>, // paths not to retry
+}
+
+impl FontFiles {
+    pub fn load(config: &Config) -> Result<Self> {
+        let base = load_font_data(config.font_path.as_deref()).context("Failed to load font")?;
+
+        // widgets fall back to text labels without icons
+        let icons = config.icon_font_path
+            .as_deref()
+            .and_then(|path| {
+                read_font_file(path)
+                    .inspect_err(|e| log::warn!("{:?}", e.context("Failed to load icon font")))
+                    .ok()
+            });
+
+        Ok(
+            Self {
+                base,
+                icons,
+                fallbacks: config.fallback_fonts.iter().map(|font| (font.clone(), None)).collect(),
+                failed: Vec::new(),
+            }
+        )
+    }
+
+    /// Loads the fallback fonts covering glyphs requested since the last
+    /// call, returning whether the atlas has to be rebuilt to include them.
+    pub fn load_missing(&mut self) -> bool {
+        let missing = MISSING_GLYPHS.with_borrow_mut(std::mem::take);
+        let mut loaded = false;
+
+        for (font, data) in &mut self.fallbacks {
+            if data.is_some() || self.failed.contains(&font.path) {
+                continue;
+            }
+
+            if !missing.iter().any(|character| font.glyphs.covers(*character)) {
+                continue;
+            }
+
+            // boxes are better than no desktop
+            match read_font_file(&font.path) {
+                Ok(font_data) => {
+                    *data = Some(font_data);
+                    loaded = true;
+                }
+
+                Err(e) => {
+                    log::warn!("{:?}", e.context("Failed to load fallback font"));
+                    self.failed.push(font.path.clone());
+                }
+            }
+        }
+
+        loaded
+    }
+}
+
+/// Notes the characters of `text` missing from the atlas, so fallback fonts
+/// covering them are loaded for the next frames (see `FontFiles::load_missing`).
+pub fn request_glyphs(ui: &Ui, text: &str) {
+    let missing = text
+        .chars()
+        .filter(|character| !character.is_ascii() && !has_glyph(ui, *character))
+        .collect::<Vec<_>>();
+
+    if !missing.is_empty() {
+        MISSING_GLYPHS.with_borrow_mut(|glyphs| glyphs.extend(missing));
+    }
+}
+
+/// Reads the font at `path`, falling back to the embedded font when no path is given.
+pub fn load_font_data(path: Option<&str>) -> Result<Vec<u8>> {
+    let Some(path) = path else {
+        return Ok(include_bytes!("../resources/segoeui.ttf").to_vec());
+    };
+
+    read_font_file(path)
+}
+
+fn read_font_file(path: &str) -> Result<Vec<u8>> {
+    let path = resolve_path(path)?;
+    let data = std::fs::read(&path).with_context(|| format!("Failed to read font file '{}'", path.display()))?;
+
+    // TrueType, OpenType (CFF), legacy Apple TrueType and TrueType collections
+    match data.get(..4) {
+        Some([0x00, 0x01, 0x00, 0x00] | b"OTTO" | b"true" | b"ttcf") => Ok(data),
+        _ => bail!("Font file '{}' is not a valid TrueType/OpenType font", path.display()),
+    }
+}
+
+fn get_font_data(files: &FontFiles, size: f64, scale: f64, glyph_offset: f32) -> Vec<FontSource<'_>> {
+    // merged fonts are rasterized the same way
+    // and share the offset, so they line up
+    let config = |glyph_ranges| FontConfig {
+        rasterizer_multiply: 1f32,
+        font_builder_flags: ImGuiFreeTypeBuilderFlags_Bitmap,
+
+        oversample_h: 1i32,
+        oversample_v: 1i32,
+        glyph_offset: [0f32, glyph_offset],
+        glyph_ranges,
+
+        ..FontConfig::default()
+    };
+
+    let mut sources = vec![
+        FontSource::TtfData {
+            data: &files.base,
+            size_pixels: (size * scale) as f32,
+            config: Some(config(FontGlyphRanges::default())),
+        },
+    ];
+
+    // every source after the first is merged into it
+    if let Some(icons) = &files.icons {
+        sources.push(FontSource::TtfData {
+            data: icons,
+            size_pixels: (size * scale) as f32,
+            config: Some(config(FontGlyphRanges::from_slice(ICON_GLYPH_RANGES))),
+        });
+    }
+
+    for (font, data) in &files.fallbacks {
+        if let Some(data) = data {
+            sources.push(FontSource::TtfData {
+                data,
+                size_pixels: (size * scale) as f32,
+                config: Some(config(font.glyphs.glyph_ranges())),
+            });
+        }
+    }
+
+    sources
+}
+
+/// Whether the current font, including merged fonts, has a glyph for `character`.
+pub fn has_glyph(_ui: &Ui, character: char) -> bool {
+    // only valid during a frame,
+    // which the `Ui` guarantees
+    unsafe { !imgui_sys::ImFont_FindGlyphNoFallback(imgui_sys::igGetFont(), character as _).is_null() }
+}
+
+/// Finds the vertical offset that moves the highest inked pixel
+/// of the printable ASCII glyphs onto the top of the line.
+///
+/// The atlas must already be built as an alpha8 texture.
+fn calculate_glyph_offset(context: &mut ImGuiContext) -> Result<f32> {
+    let texture = context.fonts().build_alpha8_texture();
+    let (width, height) = (texture.width as usize, texture.height as usize);
+    let pixels = texture.data.to_vec();
+
+    let atlas = context.fonts().raw_mut();
+    if atlas.Fonts.Size == 0 {
+        bail!("Font atlas contains no fonts");
+    }
+
+    // only the first font is
+    // used for the taskbar text
+    let font = unsafe { &**atlas.Fonts.Data };
+    let glyphs = unsafe { std::slice::from_raw_parts(font.Glyphs.Data, font.Glyphs.Size as usize) };
+
+    let mut top = f32::MAX;
+
+    for glyph in glyphs {
+        // accented and other extended glyphs reach
+        // above the cap height and would skew the offset
+        if glyph.Visible() == 0 || !(0x21..=0x7E).contains(&glyph.Codepoint()) {
+            continue;
+        }
+
+        let x0 = (glyph.U0 * width as f32).round() as usize;
+        let x1 = (glyph.U1 * width as f32).round() as usize;
+        let y0 = (glyph.V0 * height as f32).round() as usize;
+        let y1 = (glyph.V1 * height as f32).round() as usize;
+
+        let blank_rows = (y0..y1)
+            .take_while(|&y| pixels[y * width + x0..y * width + x1].iter().all(|&alpha| alpha == 0))
+            .count();
+
+        if blank_rows < y1 - y0 {
+            top = top.min(glyph.Y0 + blank_rows as f32);
+        }
+    }
+
+    if top == f32::MAX {
+        bail!("Font has no visible printable glyphs");
+    }
+
+    Ok(-top.round())
+}
+
+/// Rebuilds the font atlas at `size` times `scale`, returning the glyph offset calculated for it.
+pub fn build_fonts(context: &mut ImGuiContext, files: &FontFiles, size: f64, scale: f64) -> Result<f32> {
+    // the atlas copies the font data
+    // while adding it, so it only has
+    // to outlive the `add_font` call
+    context.fonts().clear();
+    context.fonts().add_font(get_font_data(files, size, scale, 0f32).as_slice());
+
+    let glyph_offset = calculate_glyph_offset(context).context("Failed to calculate glyph offset")?;
+
+    context.fonts().clear();
+    context.fonts().add_font(get_font_data(files, size, scale, glyph_offset).as_slice());
+
+    Ok(glyph_offset)
+}
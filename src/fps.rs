@@ -0,0 +1,53 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+use imgui::{Condition, Ui};
+use crate::consts::*;
+
+/// Rolling frame time average, drawn as a small overlay for performance debugging.
+pub struct FpsOverlay {
+    frame_times: VecDeque<Duration>,
+    total: Duration,
+}
+
+impl FpsOverlay {
+    pub fn new() -> Self {
+        Self {
+            frame_times: VecDeque::with_capacity(FPS_SAMPLES),
+            total: Duration::ZERO,
+        }
+    }
+
+    pub fn push(&mut self, frame_time: Duration) {
+        if self.frame_times.len() == FPS_SAMPLES {
+            if let Some(oldest) = self.frame_times.pop_front() {
+                self.total -= oldest;
+            }
+        }
+
+        self.frame_times.push_back(frame_time);
+        self.total += frame_time;
+    }
+
+    /// Draws the overlay with its top-left corner at `anchor`.
+    pub fn render(&self, ui: &Ui, anchor: [f32; 2]) {
+        if self.frame_times.is_empty() {
+            return;
+        }
+
+        let average = self.total.as_secs_f32() / self.frame_times.len() as f32;
+
+        ui.window("_fps")
+            .title_bar(false)
+            .resizable(false)
+            .movable(false)
+            .always_auto_resize(true)
+            .no_inputs()
+            .focus_on_appearing(false)
+            .position(anchor, Condition::Always)
+            .bg_alpha(0.5)
+            .build(|| {
+                ui.text(format!("{:.0} FPS", 1f32 / average.max(f32::EPSILON)));
+                ui.text(format!("{:.2} ms", average * 1000f32));
+            });
+    }
+}
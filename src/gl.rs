@@ -0,0 +1,184 @@
+use std::fmt;
+use std::rc::Rc;
+use imgui_glow_renderer::glow;
+use imgui_glow_renderer::glow::{
+    HasContext, NativeBuffer, NativeFramebuffer, NativeProgram, NativeTexture, NativeUniformLocation, NativeVertexArray
+};
+use crate::consts::*;
+
+/// Errors OpenGL reported after `operation`, in the order it queued them.
+#[derive(Debug)]
+pub struct GlError {
+    pub operation: &'static str,
+    pub codes: Vec<u32>,
+}
+
+impl GlError {
+    /// The context is gone (e.g. after a driver reset), so recreating it may help.
+    pub fn is_context_lost(&self) -> bool {
+        self.codes.contains(&glow::CONTEXT_LOST)
+    }
+}
+
+impl fmt::Display for GlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = self.codes
+            .iter()
+            .map(|code| match *code {
+                glow::INVALID_ENUM => "GL_INVALID_ENUM",
+                glow::INVALID_VALUE => "GL_INVALID_VALUE",
+                glow::INVALID_OPERATION => "GL_INVALID_OPERATION",
+                glow::INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION",
+                glow::OUT_OF_MEMORY => "GL_OUT_OF_MEMORY",
+                glow::STACK_UNDERFLOW => "GL_STACK_UNDERFLOW",
+                glow::STACK_OVERFLOW => "GL_STACK_OVERFLOW",
+                glow::CONTEXT_LOST => "GL_CONTEXT_LOST",
+                _ => "unknown error",
+            })
+            .collect();
+
+        write!(f, "OpenGL reported {} while {}", names.join(", "), self.operation)
+    }
+}
+
+impl std::error::Error for GlError { }
+
+/// The OpenGL context behind checked methods, so callers don't
+/// repeat `unsafe` and the errors OpenGL queues aren't lost.
+///
+/// Calls only queue errors, `check` (or a method returning
+/// `Result`) reports them, usually once per draw.
+#[derive(Clone)]
+pub struct Gl {
+    context: Rc<glow::Context>,
+}
+
+impl Gl {
+    pub fn new(context: &Rc<glow::Context>) -> Self {
+        Self { context: context.clone() }
+    }
+
+    /// The raw context, for what creates its own objects (e.g. widgets' textures).
+    pub fn context(&self) -> &Rc<glow::Context> {
+        &self.context
+    }
+
+    /// Fails with the errors queued since the last check, naming them after `operation`.
+    pub fn check(&self, operation: &'static str) -> Result<(), GlError> {
+        let mut codes = Vec::new();
+
+        // a lost context can keep reporting errors forever
+        while codes.len() < GL_ERROR_LIMIT {
+            match unsafe { self.context.get_error() } {
+                glow::NO_ERROR => break,
+                code => codes.push(code),
+            }
+        }
+
+        match codes.is_empty() {
+            true => Ok(()),
+            false => Err(GlError { operation, codes }),
+        }
+    }
+
+    pub fn clear_color_buffer(&self, color: [f32; 4]) -> Result<(), GlError> {
+        unsafe {
+            self.context.clear_color(color[0], color[1], color[2], color[3]);
+            self.context.clear(glow::COLOR_BUFFER_BIT);
+        }
+
+        self.check("clearing the frame")
+    }
+
+    pub fn viewport(&self, x: i32, y: i32, width: i32, height: i32) -> Result<(), GlError> {
+        unsafe { self.context.viewport(x, y, width, height); }
+        self.check("setting the viewport")
+    }
+
+    /// Blends with `source` and `destination` factors, or draws over the frame when `None`.
+    pub fn set_blend(&self, factors: Option<(u32, u32)>) {
+        unsafe {
+            match factors {
+                Some((source, destination)) => {
+                    self.context.enable(glow::BLEND);
+                    self.context.blend_func(source, destination);
+                }
+
+                None => self.context.disable(glow::BLEND),
+            }
+        }
+    }
+
+    pub fn use_program(&self, program: Option<NativeProgram>) {
+        unsafe { self.context.use_program(program); }
+    }
+
+    pub fn bind_vertex_array(&self, vertex_array: Option<NativeVertexArray>) {
+        unsafe { self.context.bind_vertex_array(vertex_array); }
+    }
+
+    pub fn bind_framebuffer(&self, framebuffer: Option<NativeFramebuffer>) {
+        unsafe { self.context.bind_framebuffer(glow::FRAMEBUFFER, framebuffer); }
+    }
+
+    /// Binds `texture` to texture unit `unit`, leaving unit 0 active.
+    pub fn bind_texture(&self, unit: u32, texture: Option<NativeTexture>) {
+        unsafe {
+            self.context.active_texture(glow::TEXTURE0 + unit);
+            self.context.bind_texture(glow::TEXTURE_2D, texture);
+            self.context.active_texture(glow::TEXTURE0);
+        }
+    }
+
+    /// Copies the framebuffer's pixels from `x` and `y` (from its bottom-left corner) into `texture`.
+    pub fn copy_to_texture(&self, texture: NativeTexture, x: i32, y: i32, width: i32, height: i32) {
+        unsafe {
+            self.context.bind_texture(glow::TEXTURE_2D, Some(texture));
+            self.context.copy_tex_sub_image_2d(glow::TEXTURE_2D, 0, 0, 0, x, y, width, height);
+        }
+    }
+
+    pub fn uniform_location(&self, program: NativeProgram, name: &str) -> Option<NativeUniformLocation> {
+        unsafe { self.context.get_uniform_location(program, name) }
+    }
+
+    pub fn uniform_1_i32(&self, location: Option<&NativeUniformLocation>, value: i32) {
+        unsafe { self.context.uniform_1_i32(location, value); }
+    }
+
+    pub fn uniform_1_f32(&self, location: Option<&NativeUniformLocation>, value: f32) {
+        unsafe { self.context.uniform_1_f32(location, value); }
+    }
+
+    pub fn uniform_2_f32(&self, location: Option<&NativeUniformLocation>, value: [f32; 2]) {
+        unsafe { self.context.uniform_2_f32(location, value[0], value[1]); }
+    }
+
+    pub fn uniform_4_f32(&self, location: Option<&NativeUniformLocation>, value: [f32; 4]) {
+        unsafe { self.context.uniform_4_f32(location, value[0], value[1], value[2], value[3]); }
+    }
+
+    pub fn draw_arrays(&self, mode: u32, count: i32) {
+        unsafe { self.context.draw_arrays(mode, 0, count); }
+    }
+
+    pub fn delete_program(&self, program: NativeProgram) {
+        unsafe { self.context.delete_program(program); }
+    }
+
+    pub fn delete_texture(&self, texture: NativeTexture) {
+        unsafe { self.context.delete_texture(texture); }
+    }
+
+    pub fn delete_framebuffer(&self, framebuffer: NativeFramebuffer) {
+        unsafe { self.context.delete_framebuffer(framebuffer); }
+    }
+
+    pub fn delete_vertex_array(&self, vertex_array: NativeVertexArray) {
+        unsafe { self.context.delete_vertex_array(vertex_array); }
+    }
+
+    pub fn delete_buffer(&self, buffer: NativeBuffer) {
+        unsafe { self.context.delete_buffer(buffer); }
+    }
+}
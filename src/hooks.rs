@@ -0,0 +1,28 @@
+use std::time::Duration;
+use crate::FrameHook;
+use crate::panics::catch_unwind;
+
+/// Callbacks from embedders around each frame's imgui pass.
+#[derive(Default)]
+pub struct FrameHooks {
+    pub begin: Vec<FrameHook>,
+    pub end: Vec<FrameHook>,
+}
+
+/// Calls every hook with `dt`. Errors are logged, and a hook that
+/// panics is logged and dropped, so neither ends the session.
+pub fn run_hooks(hooks: &mut Vec<FrameHook>, stage: &str, dt: Duration) {
+    hooks.retain_mut(|hook| match catch_unwind(|| hook(dt)) {
+        Ok(Ok(())) => true,
+
+        Ok(Err(e)) => {
+            log::error!("{:?}", e.context(format!("Frame {stage} hook failed")));
+            true
+        }
+
+        Err(_) => {
+            log::error!("Frame {stage} hook panicked and was removed");
+            false
+        }
+    });
+}
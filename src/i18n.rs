@@ -0,0 +1,53 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use anyhow::{Context, Result};
+use crate::config::Config;
+use crate::consts::*;
+use crate::utils::resolve_path;
+
+thread_local! {
+    // translations by their English text, leaked so `tr`
+    // can hand them out like the literals it's given
+    static STRINGS: RefCell<BTreeMap<String, &'static str>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// `text` in the loaded locale, or unchanged when it has no translation.
+pub fn tr(text: &'static str) -> &'static str {
+    STRINGS.with_borrow(|strings| strings.get(text).copied()).unwrap_or(text)
+}
+
+/// Loads the string table for `config`'s locale from `I18N_DIRECTORY`, e.g.
+/// "de_AT.toml" or else "de.toml". English is built in, so it needs no file,
+/// and strings a table is missing stay English.
+pub fn load_strings(config: &Config) -> Result<()> {
+    let mut strings = BTreeMap::new();
+
+    if let Some(name) = config.locale_name() {
+        let language = name.split('_').next().unwrap_or(&name).to_string();
+        let directory = resolve_path(I18N_DIRECTORY)?;
+
+        // the most specific table found
+        let path = [name, language]
+            .into_iter()
+            .map(|name| directory.join(format!("{name}.toml")))
+            .find(|path| path.exists());
+
+        if let Some(path) = path {
+            let source = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read string table '{}'", path.display()))?;
+
+            // the toml error already reports the line and column
+            let table: BTreeMap<String, String> = toml::from_str(&source)
+                .with_context(|| format!("Failed to parse string table '{}'", path.display()))?;
+
+            strings = table
+                .into_iter()
+                .map(|(text, translation)| (text, &*translation.leak()))
+                .collect();
+        }
+    }
+
+    STRINGS.set(strings);
+
+    Ok(())
+}
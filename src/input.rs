@@ -0,0 +1,140 @@
+use imgui::Io;
+use imgui_winit_support::WinitPlatform;
+use winit::event::{Event, WindowEvent};
+use winit::window::Window;
+
+/// Forwards a window event to imgui, shared by every window VeilDE creates.
+/// `WinitPlatform` pushes modifier state (e.g. for Ctrl+C) from
+/// `ModifiersChanged` too, even for modifiers pressed while another
+/// window had focus.
+pub fn forward_window_event(platform: &mut WinitPlatform, io: &mut Io, window: &Window, event: &WindowEvent) {
+    platform.handle_event::<WindowEvent>(
+        io,
+        window,
+        &Event::WindowEvent {
+            window_id: window.id(),
+            event: event.clone()
+        },
+    );
+}
+
+// these need a window, which only Windows' test
+// environments are guaranteed to be able to create
+#[cfg(all(test, windows))]
+mod tests {
+    use std::sync::OnceLock;
+    use imgui::{Condition, Context};
+    use imgui_winit_support::{HiDpiMode, WinitPlatform};
+    use winit::dpi::PhysicalPosition;
+    use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+    use winit::event_loop::EventLoop;
+    use winit::keyboard::ModifiersState;
+    use winit::platform::windows::EventLoopBuilderExtWindows;
+    use winit::window::Window;
+    use super::forward_window_event;
+
+    /// A hidden window shared by every test, as winit
+    /// allows a single event loop per process.
+    fn window() -> &'static Window {
+        static WINDOW: OnceLock<Window> = OnceLock::new();
+
+        WINDOW.get_or_init(|| {
+            // tests don't run on the main thread
+            let event_loop = EventLoop::builder().with_any_thread(true).build().unwrap();
+
+            #[allow(deprecated)]
+            let window = event_loop.create_window(Window::default_attributes().with_visible(false)).unwrap();
+
+            // kept alive with the window
+            std::mem::forget(event_loop);
+            window
+        })
+    }
+
+    /// An imgui context attached to `window()` like `init_imgui`
+    /// does, so positions are in physical pixels.
+    fn imgui() -> (Context, WinitPlatform) {
+        let mut imgui = Context::create();
+        imgui.set_ini_filename(None);
+
+        let mut platform = WinitPlatform::new(&mut imgui);
+        platform.attach_window(imgui.io_mut(), window(), HiDpiMode::Locked(1f64));
+
+        imgui.io_mut().display_size = [400f32, 300f32];
+        imgui.io_mut().delta_time = 1f32 / 60f32;
+        imgui.fonts().build_rgba32_texture();
+
+        (imgui, platform)
+    }
+
+    fn forward(imgui: &mut Context, platform: &mut WinitPlatform, event: WindowEvent) {
+        forward_window_event(platform, imgui.io_mut(), window(), &event);
+    }
+
+    fn mouse_input(state: ElementState) -> WindowEvent {
+        WindowEvent::MouseInput {
+            device_id: unsafe { DeviceId::dummy() },
+            state,
+            button: MouseButton::Left,
+        }
+    }
+
+    /// Draws a frame with a single button, returning whether it was clicked.
+    fn frame(imgui: &mut Context) -> bool {
+        let ui = imgui.new_frame();
+        let mut clicked = false;
+
+        ui.window("_input_test")
+            .position([0f32, 0f32], Condition::Always)
+            .size([200f32, 100f32], Condition::Always)
+            .build(|| {
+                clicked = ui.button_with_size("Button", [100f32, 40f32]);
+            });
+
+        imgui.render();
+        clicked
+    }
+
+    #[test]
+    fn mouse_press_and_release_clicks_a_button() {
+        let (mut imgui, mut platform) = imgui();
+
+        // below the title bar, within the button
+        forward(&mut imgui, &mut platform, WindowEvent::CursorMoved {
+            device_id: unsafe { DeviceId::dummy() },
+            position: PhysicalPosition::new(50f64, 50f64),
+        });
+
+        assert!(!frame(&mut imgui));
+        assert_eq!(imgui.io().mouse_pos, [50f32, 50f32]);
+
+        forward(&mut imgui, &mut platform, mouse_input(ElementState::Pressed));
+
+        assert!(!frame(&mut imgui));
+        assert!(imgui.io().mouse_down[0]);
+
+        forward(&mut imgui, &mut platform, mouse_input(ElementState::Released));
+
+        assert!(frame(&mut imgui));
+        assert!(!imgui.io().mouse_down[0]);
+
+        // released buttons stay unclicked
+        assert!(!frame(&mut imgui));
+    }
+
+    #[test]
+    fn modifiers_reach_imgui() {
+        let (mut imgui, mut platform) = imgui();
+
+        forward(&mut imgui, &mut platform, WindowEvent::ModifiersChanged(ModifiersState::CONTROL.into()));
+        frame(&mut imgui);
+
+        assert!(imgui.io().key_ctrl);
+        assert!(!imgui.io().key_shift);
+
+        forward(&mut imgui, &mut platform, WindowEvent::ModifiersChanged(ModifiersState::empty().into()));
+        frame(&mut imgui);
+
+        assert!(!imgui.io().key_ctrl);
+    }
+}
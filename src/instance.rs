@@ -0,0 +1,33 @@
+use std::fs::{File, OpenOptions, TryLockError};
+use anyhow::{Context, Result};
+use crate::consts::*;
+use crate::utils::resolve_path;
+
+/// Held while this process owns the desktop, so a second
+/// instance doesn't draw over the first one.
+///
+/// The lock belongs to the open file, so the OS releases it
+/// when the process exits for any reason, including a crash.
+pub struct InstanceLock {
+    _file: File,
+}
+
+impl InstanceLock {
+    /// Takes the lock, or returns `None` when another instance holds it.
+    pub fn acquire() -> Result<Option<Self>> {
+        let path = resolve_path(INSTANCE_LOCK_PATH)?;
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open instance lock '{}'", path.display()))?;
+
+        match file.try_lock() {
+            Ok(()) => Ok(Some(Self { _file: file })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => Err(e).context("Failed to lock instance lock"),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::io::{Read, Write};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use crate::consts::*;
+
+/// A command sent to the running instance, e.g. from a script.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+pub enum IpcCommand {
+    SetWallpaper { path: String },
+    ReloadConfig,
+    ToggleTaskbar,
+    ToggleLauncher,
+    ToggleCommandPalette,
+    ToggleNightLight,
+    ToggleDoNotDisturb,
+    CycleLayout,
+    TileWindows,
+    Screenshot,
+    LockScreen,
+}
+
+/// The reply to an `IpcCommand`, sent once it's queued on the event loop.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct IpcResponse {
+    pub error: Option<String>,
+}
+
+/// Writes `message` as JSON, prefixed by its length as a little-endian `u32`.
+pub fn write_message<W: Write, T: Serialize>(writer: &mut W, message: &T) -> Result<()> {
+    let body = serde_json::to_vec(message).context("Failed to serialize IPC message")?;
+    let length = u32::try_from(body.len()).context("IPC message is too long")?;
+
+    writer.write_all(&length.to_le_bytes()).context("Failed to write IPC message length")?;
+    writer.write_all(&body).context("Failed to write IPC message")?;
+    writer.flush().context("Failed to flush IPC message")
+}
+
+/// Reads a message written by `write_message`.
+pub fn read_message<R: Read, T: DeserializeOwned>(reader: &mut R) -> Result<T> {
+    let mut length = [0u8; 4];
+    reader.read_exact(&mut length).context("Failed to read IPC message length")?;
+
+    let length = u32::from_le_bytes(length) as usize;
+
+    if length > IPC_MESSAGE_LIMIT {
+        bail!("IPC message of {length} bytes is over the {IPC_MESSAGE_LIMIT} byte limit");
+    }
+
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body).context("Failed to read IPC message")?;
+
+    serde_json::from_slice(&body).context("Failed to parse IPC message")
+}
+
+/// Sends `command` to the running instance and waits for it to be queued.
+#[cfg(windows)]
+pub fn send(command: &IpcCommand) -> Result<()> {
+    let mut pipe = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(IPC_PIPE_NAME)
+        .context("Failed to connect to VeilDE, is it running?")?;
+
+    write_message(&mut pipe, command)?;
+
+    let response: IpcResponse = read_message(&mut pipe)?;
+
+    match response.error {
+        Some(error) => bail!(error),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn send(_command: &IpcCommand) -> Result<()> {
+    bail!("Controlling VeilDE is only supported on Windows")
+}
@@ -0,0 +1,167 @@
+use std::iter::once;
+use serde::{Deserialize, Serialize};
+use crate::consts::*;
+
+/// How open windows are tiled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Layout {
+    /// Windows are left where they are.
+    #[default]
+    Floating,
+    /// Side by side, full height.
+    Columns,
+    /// Rows of equal tiles, the last row spread across the width.
+    Grid,
+    /// The first window on the left, the rest stacked on the right.
+    MasterStack,
+}
+
+impl Layout {
+    /// The layout after this one when cycling.
+    pub fn next(self) -> Self {
+        match self {
+            Layout::Floating => Layout::Columns,
+            Layout::Columns => Layout::Grid,
+            Layout::Grid => Layout::MasterStack,
+            Layout::MasterStack => Layout::Floating,
+        }
+    }
+}
+
+/// Divides `length` pixels from `start` into `parts` equal spans
+/// `gap` pixels apart, returning their starts and lengths.
+fn split(start: i32, length: i32, parts: usize, gap: i32) -> Vec<(i32, i32)> {
+    let parts = parts as i32;
+    let available = (length - gap * (parts - 1)).max(0);
+    let mut position = start;
+
+    (0..parts)
+        .map(|index| {
+            // leftover pixels go to the first spans
+            let span = available / parts + i32::from(index < available % parts);
+            let result = (position, span);
+
+            position += span + gap;
+            result
+        })
+        .collect()
+}
+
+/// Positions and sizes (x, y, width and height) of `count` tiles in `area`,
+/// `gap` pixels apart and from its edges. Empty when floating.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn tiles(layout: Layout, count: usize, area: [i32; 4], gap: i32) -> Vec<[i32; 4]> {
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let [x, y, width, height] = [area[0] + gap, area[1] + gap, area[2] - gap * 2, area[3] - gap * 2];
+
+    match layout {
+        Layout::Floating => Vec::new(),
+
+        Layout::Columns => split(x, width, count, gap)
+            .into_iter()
+            .map(|(x, width)| [x, y, width, height])
+            .collect(),
+
+        Layout::Grid => {
+            let columns = (count as f32).sqrt().ceil() as usize;
+            let rows = count.div_ceil(columns);
+
+            split(y, height, rows, gap)
+                .into_iter()
+                .enumerate()
+                .flat_map(|(row, (y, height))| {
+                    let in_row = columns.min(count - row * columns);
+
+                    split(x, width, in_row, gap)
+                        .into_iter()
+                        .map(move |(x, width)| [x, y, width, height])
+                })
+                .collect()
+        }
+
+        Layout::MasterStack if count == 1 => vec![[x, y, width, height]],
+
+        Layout::MasterStack => {
+            let master_width = ((width - gap) as f32 * TILING_MASTER_RATIO) as i32;
+            let stack_x = x + master_width + gap;
+            let stack_width = width - master_width - gap;
+
+            once([x, y, master_width, height])
+                .chain(split(y, height, count - 1, gap).into_iter().map(|(y, height)| [stack_x, y, stack_width, height]))
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tiles, Layout};
+
+    const AREA: [i32; 4] = [0, 0, 1000, 600];
+
+    #[test]
+    fn no_windows_have_no_tiles() {
+        for layout in [Layout::Floating, Layout::Columns, Layout::Grid, Layout::MasterStack] {
+            assert!(tiles(layout, 0, AREA, 0).is_empty());
+            assert!(tiles(layout, 0, AREA, 10).is_empty());
+        }
+    }
+
+    #[test]
+    fn floating_leaves_windows_alone() {
+        assert!(tiles(Layout::Floating, 3, AREA, 10).is_empty());
+    }
+
+    #[test]
+    fn a_single_window_fills_the_area() {
+        for layout in [Layout::Columns, Layout::Grid, Layout::MasterStack] {
+            assert_eq!(tiles(layout, 1, AREA, 0), [[0, 0, 1000, 600]]);
+            assert_eq!(tiles(layout, 1, AREA, 10), [[10, 10, 980, 580]]);
+        }
+    }
+
+    #[test]
+    fn columns() {
+        // the leftover pixel goes to the first column
+        assert_eq!(
+            tiles(Layout::Columns, 3, AREA, 0),
+            [[0, 0, 334, 600], [334, 0, 333, 600], [667, 0, 333, 600]]
+        );
+
+        assert_eq!(
+            tiles(Layout::Columns, 2, AREA, 10),
+            [[10, 10, 485, 580], [505, 10, 485, 580]]
+        );
+    }
+
+    #[test]
+    fn grid() {
+        // the last row spreads across the width
+        assert_eq!(
+            tiles(Layout::Grid, 5, AREA, 0),
+            [[0, 0, 334, 300], [334, 0, 333, 300], [667, 0, 333, 300], [0, 300, 500, 300], [500, 300, 500, 300]]
+        );
+
+        assert_eq!(
+            tiles(Layout::Grid, 4, AREA, 10),
+            [[10, 10, 485, 285], [505, 10, 485, 285], [10, 305, 485, 285], [505, 305, 485, 285]]
+        );
+    }
+
+    #[test]
+    fn master_stack() {
+        assert_eq!(
+            tiles(Layout::MasterStack, 3, AREA, 0),
+            [[0, 0, 500, 600], [500, 0, 500, 300], [500, 300, 500, 300]]
+        );
+
+        assert_eq!(
+            tiles(Layout::MasterStack, 3, AREA, 10),
+            [[10, 10, 485, 580], [505, 10, 485, 285], [505, 305, 485, 285]]
+        );
+    }
+}
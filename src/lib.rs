@@ -0,0 +1,169 @@
+mod about;
+mod actions;
+mod app;
+mod capture;
+mod color_picker;
+mod command_palette;
+pub mod config;
+pub mod consts;
+mod fonts;
+mod fps;
+mod gl;
+mod hooks;
+mod i18n;
+mod input;
+mod layout;
+mod lock_screen;
+pub mod instance;
+pub mod ipc;
+mod log_console;
+mod night_light;
+mod notes;
+mod notifications;
+mod panics;
+mod platform;
+mod plugins;
+mod renderer;
+mod screensaver;
+mod script;
+mod settings;
+mod slideshow;
+mod splash;
+mod theme;
+pub mod time;
+mod utils;
+mod widgets;
+#[cfg(windows)]
+mod win32;
+
+use std::path::PathBuf;
+use std::time::Duration;
+use anyhow::{Context, Result};
+use crate::config::{Config, ConfigOverrides};
+use crate::hooks::FrameHooks;
+
+pub use imgui;
+pub use crate::fonts::{FallbackFont, GlyphRange};
+pub use crate::lock_screen::hash_password;
+pub use crate::renderer::WallpaperMode;
+pub use crate::widgets::Widget;
+pub use crate::widgets::launcher::LauncherEntry;
+
+/// An extra imgui window drawn every frame on the primary monitor, removed once it returns `false`.
+///
+/// Panels are drawn in the order they were added, after the taskbar and
+/// launcher and before the notes, menus, settings and notifications.
+/// Until a window is focused, later ones show over earlier ones.
+pub type Panel = Box<dyn FnMut(&imgui::Ui) -> bool>;
+
+/// Called on every monitor's frame with the time since its last frame, while its OpenGL context is current.
+pub type FrameHook = Box<dyn FnMut(Duration) -> Result<()>>;
+
+/// Builder for running the VeilDE desktop.
+///
+/// Settings start from `config.toml`, and anything set here overrides
+/// the file's, including after it changes while the desktop is running.
+pub struct VeilDE {
+    config: Config,
+    overrides: ConfigOverrides,
+    config_path: Option<PathBuf>, // watched for changes, `CONFIG_PATH` when `None`
+    panels: Vec<Panel>,
+    hooks: FrameHooks,
+}
+
+impl VeilDE {
+    /// Starts from the configuration file next to the executable.
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_config(Config::load().context("Failed to load config")?))
+    }
+
+    /// Starts from the configuration file at `path`, which is watched instead.
+    pub fn from_config_file<P: Into<PathBuf>>(path: P) -> Result<Self> {
+        let path = path.into();
+        let config = Config::load_from(&path).context("Failed to load config")?;
+
+        Ok(
+            Self {
+                config_path: Some(path),
+                ..Self::with_config(config)
+            }
+        )
+    }
+
+    pub fn with_config(config: Config) -> Self {
+        Self {
+            config,
+            overrides: ConfigOverrides::default(),
+            config_path: None,
+            panels: Vec::new(),
+            hooks: FrameHooks::default(),
+        }
+    }
+
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.overrides.window_title = Some(title.into());
+        self
+    }
+
+    pub fn size(mut self, size: [u32; 2]) -> Self {
+        self.overrides.window_size = Some(size);
+        self
+    }
+
+    pub fn font_path<S: Into<String>>(mut self, path: S) -> Self {
+        self.overrides.font_path = Some(path.into());
+        self
+    }
+
+    pub fn wallpaper<S: Into<String>>(mut self, path: S, mode: WallpaperMode) -> Self {
+        self.overrides.wallpaper = Some((path.into(), mode));
+        self
+    }
+
+    /// Runs in a normal resizable window instead of covering the monitor.
+    pub fn windowed(mut self, windowed: bool) -> Self {
+        self.overrides.windowed = Some(windowed);
+        self
+    }
+
+    /// Only runs on the monitor at `index`, in the order the platform lists them.
+    pub fn monitor(mut self, index: usize) -> Self {
+        self.overrides.monitor = Some(index);
+        self
+    }
+
+    /// Adds a panel drawn for as long as the desktop runs.
+    pub fn panel<F: FnMut(&imgui::Ui) + 'static>(self, mut panel: F) -> Self {
+        self.removable_panel(move |ui| {
+            panel(ui);
+            true
+        })
+    }
+
+    /// Adds a panel that's removed once it returns `false`, e.g. when its window is closed.
+    pub fn removable_panel<F: FnMut(&imgui::Ui) -> bool + 'static>(mut self, panel: F) -> Self {
+        self.panels.push(Box::new(panel));
+        self
+    }
+
+    /// Runs `hook` before each frame's imgui pass, after the background is drawn.
+    ///
+    /// Errors are logged and the frame goes on, a hook that panics is removed.
+    pub fn on_frame_begin<F: FnMut(Duration) -> Result<()> + 'static>(mut self, hook: F) -> Self {
+        self.hooks.begin.push(Box::new(hook));
+        self
+    }
+
+    /// Runs `hook` after each frame's imgui pass, before the frame is shown.
+    ///
+    /// Errors are logged and the frame goes on, a hook that panics is removed.
+    pub fn on_frame_end<F: FnMut(Duration) -> Result<()> + 'static>(mut self, hook: F) -> Self {
+        self.hooks.end.push(Box::new(hook));
+        self
+    }
+
+    /// Runs the desktop until it's closed.
+    pub fn run(self) -> Result<()> {
+        app::run(self.config, self.overrides, self.config_path, self.panels, self.hooks)
+    }
+}
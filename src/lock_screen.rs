@@ -0,0 +1,198 @@
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+use chrono::Local;
+use imgui::{Condition, StyleColor, Ui};
+use anyhow::{anyhow, Result};
+use pbkdf2::pbkdf2_hmac_array;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+use crate::consts::*;
+use crate::i18n::tr;
+
+/// A `lock_password_hash` for `password`: PBKDF2-HMAC-SHA256 over a random salt, as
+/// "pbkdf2-sha256$<rounds>$<salt>$<hash>" in hex. The salt makes precomputed tables
+/// useless and the rounds make every guess slow.
+pub fn hash_password(password: &str) -> Result<String> {
+    let mut salt = [0u8; LOCK_PASSWORD_SALT_LENGTH];
+    getrandom::fill(&mut salt).map_err(|e| anyhow!("Failed to generate salt: {e}"))?;
+
+    let hash = derive(password, &salt, LOCK_PASSWORD_ROUNDS);
+
+    Ok(format!("{LOCK_PASSWORD_SCHEME}${LOCK_PASSWORD_ROUNDS}${}${}", to_hex(&salt), to_hex(&hash)))
+}
+
+/// Whether `hash` could have come from `hash_password`, so locking can't shut the user out.
+pub fn is_valid_hash(hash: &str) -> bool {
+    parse_hash(hash).is_some()
+}
+
+fn derive(password: &str, salt: &[u8], rounds: u32) -> [u8; 32] {
+    pbkdf2_hmac_array::<Sha256, 32>(password.as_bytes(), salt, rounds)
+}
+
+/// A `hash_password` string's parts, rounds read back
+/// so older hashes still verify when the default changes.
+struct PasswordHash {
+    rounds: u32,
+    salt: Vec<u8>,
+    hash: Vec<u8>,
+}
+
+fn parse_hash(text: &str) -> Option<PasswordHash> {
+    let mut parts = text.split('$');
+
+    if parts.next()? != LOCK_PASSWORD_SCHEME {
+        return None;
+    }
+
+    let rounds = parts.next()?.parse().ok().filter(|rounds| *rounds > 0)?;
+    let salt = from_hex(parts.next()?).filter(|salt| !salt.is_empty())?;
+    let hash = from_hex(parts.next()?).filter(|hash| hash.len() == 32)?;
+
+    parts.next().is_none().then_some(PasswordHash { rounds, salt, hash })
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 || !text.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&text[index..index + 2], 16).ok())
+        .collect()
+}
+
+/// Compares every byte wherever the first difference is,
+/// so the time taken says nothing about how close a guess was.
+fn verify(password: &str, hash: &str) -> bool {
+    let Some(expected) = parse_hash(hash) else {
+        return false;
+    };
+
+    derive(password, &expected.salt, expected.rounds)
+        .iter()
+        .zip(&expected.hash)
+        .fold(0u8, |difference, (a, b)| difference | (a ^ b)) == 0
+}
+
+/// Verifies on a background thread, as deriving takes a moment by
+/// design and would freeze every monitor's frame in the meantime.
+fn spawn_verify(password: Zeroizing<String>, hash: String) -> Result<JoinHandle<bool>> {
+    thread::Builder::new()
+        .name("lock screen".to_string())
+        .spawn(move || verify(&password, &hash))
+        .map_err(|e| anyhow!("Failed to spawn lock screen thread: {e}"))
+}
+
+/// An opaque overlay over everything VeilDE draws, until the password is entered.
+///
+/// Other applications can still be switched to from the keyboard,
+/// so this doesn't replace the system's own lock screen.
+pub struct LockScreen {
+    password: Zeroizing<String>, // wiped from memory once entered
+    checking: Option<JoinHandle<bool>>, // the entered password's verification
+    wrong: bool, // the last attempt failed
+    retry_at: Option<Instant>, // attempts are ignored until then
+    focus: bool, // the password field takes keyboard focus once shown
+}
+
+impl LockScreen {
+    pub fn new() -> Self {
+        Self {
+            password: Zeroizing::new(String::new()),
+            checking: None,
+            wrong: false,
+            retry_at: None,
+            focus: true,
+        }
+    }
+
+    /// Draws the overlay covering `size`, returning `true` once `hash`'s password is entered.
+    pub fn render(&mut self, ui: &Ui, size: [f32; 2], hash: &str) -> bool {
+        let mut unlocked = false;
+
+        if self.checking.as_ref().is_some_and(JoinHandle::is_finished) {
+            let correct = self.checking.take().is_some_and(|checking| checking.join().unwrap_or(false));
+
+            if correct {
+                unlocked = true;
+            } else {
+                self.wrong = true;
+                self.retry_at = Some(Instant::now() + LOCK_RETRY_DELAY);
+                self.focus = true;
+                log::warn!("Wrong lock screen password");
+            }
+        }
+
+        let _background = ui.push_style_color(StyleColor::WindowBg, LOCK_SCREEN_COLOR);
+
+        ui.window("_lock_screen")
+            .title_bar(false)
+            .resizable(false)
+            .movable(false)
+            .scroll_bar(false)
+            .bg_alpha(1f32)
+            .position([0f32, 0f32], Condition::Always)
+            .size(size, Condition::Always)
+            .focused(true)
+            .build(|| {
+                let left = ((size[0] - LOCK_SCREEN_WIDTH) / 2f32).max(0f32);
+                let line = |offset: f32| ui.set_cursor_pos([left, size[1] / 2f32 + offset * ui.frame_height_with_spacing()]);
+
+                line(-3f32);
+                ui.text(Local::now().format("%H:%M").to_string());
+                line(-2f32);
+                ui.text_disabled(Local::now().format("%A, %e %B").to_string());
+
+                // a wrong password waits the same time
+                // however close it was, which also
+                // slows down guessing
+                let checking = self.checking.is_some();
+                let waiting = checking || self.retry_at.is_some_and(|retry_at| Instant::now() < retry_at);
+                let _disabled = ui.begin_disabled(waiting);
+
+                line(0f32);
+
+                if self.focus && !waiting {
+                    ui.set_keyboard_focus_here();
+                    self.focus = false;
+                }
+
+                ui.set_next_item_width(LOCK_SCREEN_WIDTH);
+
+                let entered = ui.input_text("##_lock_screen_password", &mut *self.password)
+                    .password(true)
+                    .enter_returns_true(true)
+                    .hint(tr("Password"))
+                    .build();
+
+                line(1f32);
+
+                let label = if checking { tr("Checking...") } else { tr("Unlock") };
+                let clicked = ui.button_with_size(format!("{label}##_lock_screen_unlock"), [LOCK_SCREEN_WIDTH, 0f32]);
+
+                if (entered || clicked) && !waiting {
+                    // moved out, so the field starts empty and the
+                    // entered text is wiped once it's been checked
+                    let password = Zeroizing::new(std::mem::take(&mut *self.password));
+
+                    match spawn_verify(password, hash.to_string()) {
+                        Ok(checking) => self.checking = Some(checking),
+                        Err(e) => log::error!("{e:?}"),
+                    }
+                }
+
+                if self.wrong && !checking {
+                    line(2f32);
+                    ui.text_colored(LOCK_SCREEN_ERROR_COLOR, tr("Wrong password"));
+                }
+            });
+
+        unlocked
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::VecDeque;
+use std::sync::{Mutex, PoisonError};
+use chrono::{DateTime, Local};
+use imgui::{Condition, Ui};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use crate::consts::*;
+use crate::i18n::tr;
+
+struct LogLine {
+    level: Level,
+    time: DateTime<Local>,
+    message: String,
+}
+
+// shared by every monitor's console, bounded by `LOG_LINE_LIMIT`
+static LINES: Mutex<VecDeque<LogLine>> = Mutex::new(VecDeque::new());
+
+static LOGGER: ConsoleLogger = ConsoleLogger;
+
+/// Writes records to stderr and keeps the latest ones for the console.
+struct ConsoleLogger;
+
+impl Log for ConsoleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        // dependencies (winit especially) are
+        // chatty below info, VeilDE itself isn't
+        metadata.level() <= Level::Info || metadata.target().starts_with("veilde")
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        eprintln!("[{}] {}", record.level(), record.args());
+
+        let mut lines = LINES.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if lines.len() == LOG_LINE_LIMIT {
+            lines.pop_front();
+        }
+
+        lines.push_back(LogLine {
+            level: record.level(),
+            time: Local::now(),
+            message: record.args().to_string(),
+        });
+    }
+
+    fn flush(&self) { }
+}
+
+/// Installs the console logger, unless the
+/// embedding application already set its own.
+pub fn init() {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(if cfg!(debug_assertions) { LevelFilter::Debug } else { LevelFilter::Info });
+    }
+}
+
+/// Window showing the latest log lines, so failures can be diagnosed without the crash logs.
+pub struct LogConsole {
+    open: bool,
+    auto_scroll: bool,
+}
+
+impl LogConsole {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            auto_scroll: true,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    pub fn render(&mut self, ui: &Ui) {
+        if !self.open {
+            return;
+        }
+
+        ui.window("Log")
+            .size(LOG_CONSOLE_SIZE, Condition::FirstUseEver)
+            .opened(&mut self.open)
+            .build(|| {
+                let mut lines = LINES.lock().unwrap_or_else(PoisonError::into_inner);
+
+                ui.checkbox(tr("Auto-scroll"), &mut self.auto_scroll);
+                ui.same_line();
+
+                if ui.button(tr("Clear")) {
+                    lines.clear();
+                }
+
+                ui.separator();
+
+                ui.child_window("_log_lines").build(|| {
+                    for line in lines.iter() {
+                        let text = format!("{} [{}] {}", line.time.format("%X"), line.level, line.message);
+
+                        match line.level {
+                            Level::Error => ui.text_colored(LOG_ERROR_COLOR, text),
+                            Level::Warn => ui.text_colored(LOG_WARN_COLOR, text),
+                            Level::Info => ui.text(text),
+                            Level::Debug | Level::Trace => ui.text_disabled(text),
+                        }
+                    }
+
+                    // only follow new lines while already at the
+                    // bottom, so scrolling up to read isn't undone
+                    if self.auto_scroll && ui.scroll_y() >= ui.scroll_max_y() {
+                        ui.set_scroll_here_y_with_ratio(1f32);
+                    }
+                });
+            });
+    }
+}
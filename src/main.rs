@@ -1,10 +1,111 @@
-mod app;
-mod consts;
-mod renderer;
-
-use chrono::{Datelike, Local, Timelike};
+use std::backtrace::Backtrace;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use chrono::{Datelike, Local, NaiveDateTime, Timelike};
+use clap::{Parser, Subcommand};
 use native_dialog::{DialogBuilder, MessageLevel};
 use anyhow::{Context, Result};
+use veilde::config::Config;
+use veilde::consts::*;
+use veilde::instance::InstanceLock;
+use veilde::ipc::IpcCommand;
+use veilde::VeilDE;
+
+/// A desktop shell drawn with imgui.
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// Run in a normal resizable window instead of covering the monitor
+    #[arg(long)]
+    windowed: bool,
+
+    /// Only run on the monitor at this index
+    #[arg(long, value_name = "INDEX")]
+    monitor: Option<usize>,
+
+    /// Load and watch this configuration file instead of the one next to the executable
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Control the running instance instead of starting one
+    Send {
+        #[command(subcommand)]
+        command: SendCommand,
+    },
+    /// Print the `lock_password_hash` of a password read from standard input
+    HashPassword,
+}
+
+#[derive(Subcommand)]
+enum SendCommand {
+    /// Change the wallpaper until the config is reloaded
+    SetWallpaper { path: PathBuf },
+    /// Reload the configuration file
+    ReloadConfig,
+    /// Hide or show the taskbar
+    ToggleTaskbar,
+    /// Open or close the launcher
+    ToggleLauncher,
+    /// Open or close the command palette
+    ToggleCommandPalette,
+    /// Turn the night light on or off until its schedule next changes
+    ToggleNightLight,
+    /// Hold notifications and dim widgets, or show them again
+    ToggleDoNotDisturb,
+    /// Switch to the next window tiling layout
+    CycleLayout,
+    /// Tile the open windows into the current layout again
+    TileWindows,
+    /// Save a screenshot of every monitor
+    Screenshot,
+    /// Cover every monitor until the lock screen password is entered
+    LockScreen,
+}
+
+fn send(command: SendCommand) -> Result<()> {
+    let command = match command {
+        // the instance resolves relative
+        // paths against its own directory
+        SendCommand::SetWallpaper { path } => IpcCommand::SetWallpaper {
+            path: std::path::absolute(&path)
+                .context("Failed to resolve wallpaper path")?
+                .to_string_lossy()
+                .into_owned(),
+        },
+
+        SendCommand::ReloadConfig => IpcCommand::ReloadConfig,
+        SendCommand::ToggleTaskbar => IpcCommand::ToggleTaskbar,
+        SendCommand::ToggleLauncher => IpcCommand::ToggleLauncher,
+        SendCommand::ToggleCommandPalette => IpcCommand::ToggleCommandPalette,
+        SendCommand::ToggleNightLight => IpcCommand::ToggleNightLight,
+        SendCommand::ToggleDoNotDisturb => IpcCommand::ToggleDoNotDisturb,
+        SendCommand::CycleLayout => IpcCommand::CycleLayout,
+        SendCommand::TileWindows => IpcCommand::TileWindows,
+        SendCommand::Screenshot => IpcCommand::Screenshot,
+        SendCommand::LockScreen => IpcCommand::LockScreen,
+    };
+
+    veilde::ipc::send(&command)
+}
+
+/// Read from standard input rather than an argument, which would end up in the shell's history.
+fn hash_password() -> Result<()> {
+    let mut password = String::new();
+    std::io::stdin().read_line(&mut password).context("Failed to read password")?;
+
+    println!("{}", veilde::hash_password(password.trim_end_matches(['\r', '\n']))?);
+
+    Ok(())
+}
+
+// set once `--config` is parsed, for the crash log limit
+static CONFIG_FILE: OnceLock<PathBuf> = OnceLock::new();
 
 fn save_log(log: &str) -> Result<()> {
     let now = Local::now();
@@ -20,37 +121,209 @@ fn save_log(log: &str) -> Result<()> {
         now.timestamp_subsec_millis()
     );
 
-    std::fs::create_dir("crash")?;
-    std::fs::write(format!("crash/{name}"), log.as_bytes()).context("Failed to write log file")?;
+    std::fs::create_dir_all(CRASH_LOG_DIRECTORY).context("Failed to create crash log directory")?;
+    std::fs::write(format!("{CRASH_LOG_DIRECTORY}/{name}"), log.as_bytes()).context("Failed to write log file")?;
+
+    // the config may be what's broken
+    let keep = CONFIG_FILE
+        .get()
+        .map_or_else(Config::load, Config::load_from)
+        .map(|config| config.crash_log_limit)
+        .unwrap_or(CRASH_LOG_LIMIT);
+
+    prune_logs(Path::new(CRASH_LOG_DIRECTORY), keep).context("Failed to prune crash logs")?;
+
+    Ok(())
+}
+
+/// Deletes all but the `keep` most recent logs in `dir`, ordered by
+/// the timestamp in their names. Other files are left untouched.
+fn prune_logs(dir: &Path, keep: usize) -> Result<()> {
+    let mut logs = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read '{}'", dir.display()))?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+
+            if path.extension()? != "log" {
+                return None;
+            }
+
+            let time = NaiveDateTime::parse_from_str(path.file_stem()?.to_str()?, "%Y%m%d%H%M%S%3f").ok()?;
+            Some((time, path))
+        })
+        .collect::<Vec<_>>();
+
+    logs.sort_unstable_by_key(|(time, _)| *time);
+
+    let excess = logs.len().saturating_sub(keep);
+
+    for (_, path) in logs.drain(..excess) {
+        // a locked or already removed log
+        // shouldn't stop the others
+        if let Err(e) = std::fs::remove_file(&path) {
+            eprintln!("Failed to delete old crash log '{}': {e}", path.display());
+        }
+    }
 
     Ok(())
 }
 
+/// Shows the failure dialog and writes `display` to a crash log.
+///
+/// Used from the panic hook, so this reports its
+/// own failures instead of panicking.
+fn report_failure(display: &str) {
+    eprintln!("{display}");
+
+    let dialog = DialogBuilder::message()
+        .set_title("VeilDE-rs - Failure")
+        .set_text(display)
+        .set_level(MessageLevel::Error)
+        .alert()
+        .show();
+
+    if let Err(e) = dialog {
+        eprintln!("Failed to show dialog: {e}");
+    }
+
+    if let Err(e) = save_log(display) {
+        eprintln!("Failed to save log file: {e:?}");
+    }
+}
+
+fn run(args: Args) -> Result<()> {
+    let mut veilde = match &args.config {
+        Some(path) => {
+            // compared against watcher events, which are absolute
+            let path = std::path::absolute(path).context("Failed to resolve config path")?;
+            let _ = CONFIG_FILE.set(path.clone());
+
+            VeilDE::from_config_file(path)?
+        }
+
+        None => VeilDE::new()?,
+    };
+
+    // the config file's settings
+    // stand when no flag is given
+    if args.windowed {
+        veilde = veilde.windowed(true);
+    }
+
+    if let Some(index) = args.monitor {
+        veilde = veilde.monitor(index);
+    }
+
+    veilde.run()
+}
+
 fn main() {
-    match app::init() {
-        Ok(_) => {
+    // exits with usage on invalid arguments
+    let mut args = Args::parse();
+
+    // a client, so no dialogs or instance lock
+    if let Some(command) = args.command.take() {
+        let result = match command {
+            Command::Send { command } => send(command),
+            Command::HashPassword => hash_password(),
+        };
+
+        if let Err(e) = result {
+            eprintln!("{e:?}");
+            std::process::exit(1);
+        }
+
+        return;
+    }
+
+    // panics (e.g. from `expect`) never reach the
+    // error handling below, so they're logged here,
+    // except those a hook or plugin recovered from
+    std::panic::set_hook(Box::new(|info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+
+        let location = info
+            .location()
+            .map(|location| location.to_string())
+            .unwrap_or_else(|| "unknown location".to_string());
+
+        report_failure(&format!(
+            "panicked at {location}:\n{message}\n\nStack backtrace:\n{}",
+            Backtrace::force_capture()
+        ));
+    }));
+
+    // released when main returns or the process dies
+    let _lock = match InstanceLock::acquire() {
+        Ok(Some(lock)) => lock,
+
+        Ok(None) => {
             DialogBuilder::message()
-                .set_title("VeilDE-rs - Success")
-                .set_text("Nothing failed!")
-                .set_level(MessageLevel::Info)
+                .set_title("VeilDE-rs")
+                .set_text("VeilDE is already running.")
+                .set_level(MessageLevel::Warning)
                 .alert()
                 .show()
                 .expect("Failed to show dialog");
+
+            return;
         },
 
         Err(e) => {
-            let display = format!("{:?}", e);
-            eprintln!("{display}");
+            report_failure(&format!("{:?}", e));
+            return;
+        },
+    };
 
+    match run(args) {
+        Ok(_) => {
             DialogBuilder::message()
-                .set_title("VeilDE-rs - Failure")
-                .set_text(display.clone())
-                .set_level(MessageLevel::Error)
+                .set_title("VeilDE-rs - Success")
+                .set_text("Nothing failed!")
+                .set_level(MessageLevel::Info)
                 .alert()
                 .show()
                 .expect("Failed to show dialog");
-
-            save_log(display.as_str()).expect("Failed to save log file");
         },
+
+        Err(e) => report_failure(&format!("{:?}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use super::prune_logs;
+
+    #[test]
+    fn prunes_oldest_logs_only() {
+        let dir = std::env::temp_dir().join(format!("veilde-prune-logs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        // written out of order, as file system listings aren't sorted
+        let logs = ["20260102080000000", "20260101120000000", "20260103090000500", "20260103090000250"];
+
+        for name in logs {
+            fs::write(dir.join(format!("{name}.log")), "crash").unwrap();
+        }
+
+        fs::write(dir.join("notes.txt"), "not a log").unwrap();
+
+        prune_logs(&dir, 2).unwrap();
+
+        let mut remaining: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        remaining.sort();
+
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(remaining, ["20260103090000250.log", "20260103090000500.log", "notes.txt"]);
     }
 }
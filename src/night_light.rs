@@ -0,0 +1,91 @@
+use std::time::Duration;
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveTime};
+use crate::config::Config;
+use crate::consts::*;
+
+fn parse_time(time: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%H:%M").with_context(|| format!("Invalid night light time '{time}', expected e.g. \"20:30\""))
+}
+
+/// Warms the screen's colors between the configured start and end times.
+pub struct NightLight {
+    schedule: Option<(NaiveTime, NaiveTime)>, // start and end, never on when `None`
+    scheduled: bool, // whether the schedule had it on last update
+    forced: Option<bool>, // toggled by hand, until the schedule next turns it on or off
+    strength: Option<f32>, // 0 to 1, eased towards on or off, `None` until the first update
+}
+
+impl NightLight {
+    pub fn new() -> Self {
+        Self {
+            schedule: None,
+            scheduled: false,
+            forced: None,
+            strength: None,
+        }
+    }
+
+    pub fn apply_config(&mut self, config: &Config) -> Result<()> {
+        self.schedule = None;
+
+        if config.night_light {
+            self.schedule = Some((parse_time(&config.night_light_start)?, parse_time(&config.night_light_end)?));
+        }
+
+        self.scheduled = self.is_scheduled();
+
+        Ok(())
+    }
+
+    fn is_scheduled(&self) -> bool {
+        let Some((start, end)) = self.schedule else {
+            return false;
+        };
+
+        let now = Local::now().time();
+
+        // e.g. 20:00 to 07:00 spans midnight
+        if start <= end {
+            start <= now && now < end
+        } else {
+            now >= start || now < end
+        }
+    }
+
+    fn target(&self) -> bool {
+        self.forced.unwrap_or(self.scheduled)
+    }
+
+    /// Turns it on or off right away, overriding the schedule until it next changes.
+    pub fn toggle(&mut self) {
+        let on = !self.target();
+
+        self.forced = Some(on);
+        self.strength = Some(if on { 1f32 } else { 0f32 });
+    }
+
+    /// Eases towards the scheduled state, returning the strength to draw with.
+    pub fn update(&mut self, dt: Duration) -> f32 {
+        let scheduled = self.is_scheduled();
+
+        if scheduled != self.scheduled {
+            self.scheduled = scheduled;
+            self.forced = None;
+        }
+
+        let target = if self.target() { 1f32 } else { 0f32 };
+
+        // no ramp when starting up at night
+        let Some(strength) = self.strength else {
+            self.strength = Some(target);
+            return target;
+        };
+
+        let step = dt.as_secs_f32() / NIGHT_LIGHT_RAMP_DURATION.as_secs_f32();
+        let strength = if target > strength { (strength + step).min(target) } else { (strength - step).max(target) };
+
+        self.strength = Some(strength);
+        strength
+    }
+}
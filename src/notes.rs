@@ -0,0 +1,108 @@
+use anyhow::{Context, Result};
+use imgui::{Condition, MouseButton, Ui};
+use serde::{Deserialize, Serialize};
+use crate::consts::*;
+use crate::utils::resolve_path;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Note {
+    id: u64,
+    text: String,
+    position: [f32; 2],
+    size: [f32; 2],
+}
+
+/// Sticky notes on the desktop, saved to `NOTES_PATH` as they change.
+pub struct Notes {
+    notes: Vec<Note>,
+    dirty: bool, // changed since the last save
+}
+
+impl Notes {
+    /// Loads the saved notes, none when there's no file yet.
+    pub fn load() -> Result<Self> {
+        let path = resolve_path(NOTES_PATH)?;
+
+        let notes = if path.exists() {
+            let source = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read notes file '{}'", path.display()))?;
+
+            serde_json::from_str(&source).with_context(|| format!("Failed to parse notes file '{}'", path.display()))?
+        } else {
+            Vec::new()
+        };
+
+        Ok(
+            Self {
+                notes,
+                dirty: false,
+            }
+        )
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = resolve_path(NOTES_PATH)?;
+        let source = serde_json::to_string_pretty(&self.notes).context("Failed to serialize notes")?;
+
+        std::fs::write(&path, source).with_context(|| format!("Failed to write notes file '{}'", path.display()))
+    }
+
+    /// Adds an empty note at `position`.
+    pub fn create(&mut self, position: [f32; 2]) {
+        let id = self.notes.iter().map(|note| note.id + 1).max().unwrap_or_default();
+
+        self.notes.push(Note {
+            id,
+            text: String::new(),
+            position,
+            size: NOTE_SIZE,
+        });
+
+        self.dirty = true;
+    }
+
+    pub fn render(&mut self, ui: &Ui) -> Result<()> {
+        let mut deleted = None;
+
+        for note in &mut self.notes {
+            let mut open = true;
+
+            ui.window(format!("Note##_note_{}", note.id))
+                .position(note.position, Condition::Appearing)
+                .size(note.size, Condition::Appearing)
+                .collapsible(false)
+                .opened(&mut open)
+                .build(|| {
+                    ui.input_text_multiline(format!("##_note_text_{}", note.id), &mut note.text, [-1f32, -1f32]).build();
+                    self.dirty |= ui.is_item_deactivated_after_edit();
+
+                    // dragged or resized
+                    let (position, size) = (ui.window_pos(), ui.window_size());
+
+                    if position != note.position || size != note.size {
+                        note.position = position;
+                        note.size = size;
+                        self.dirty = true;
+                    }
+                });
+
+            if !open {
+                deleted = Some(note.id);
+            }
+        }
+
+        if let Some(id) = deleted {
+            self.notes.retain(|note| note.id != id);
+            self.dirty = true;
+        }
+
+        // not while dragging, or every frame would write the file,
+        // and a failed save waits for the next change to retry
+        if self.dirty && !ui.is_any_item_active() && !ui.is_mouse_down(MouseButton::Left) {
+            self.dirty = false;
+            self.save()?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+use anyhow::Error;
+use chrono::{DateTime, Local};
+use imgui::{Condition, Ui};
+use crate::consts::*;
+use crate::i18n::tr;
+
+pub struct Notification {
+    pub message: String,
+    pub time: DateTime<Local>,
+}
+
+/// Recoverable errors shown as dismissable notifications instead of ending the session.
+pub struct Notifications {
+    queue: VecDeque<Notification>,
+    do_not_disturb: bool, // held in the queue until turned off
+}
+
+impl Notifications {
+    pub fn new() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            do_not_disturb: false,
+        }
+    }
+
+    pub fn set_do_not_disturb(&mut self, do_not_disturb: bool) {
+        self.do_not_disturb = do_not_disturb;
+    }
+
+    pub fn count(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn push(&mut self, e: Error) {
+        log::error!("{e:?}");
+
+        if self.queue.len() == NOTIFICATION_LIMIT {
+            self.queue.pop_front();
+        }
+
+        self.queue.push_back(Notification {
+            message: format!("{e:#}"), // the whole context chain on one line
+            time: Local::now(),
+        });
+    }
+
+    /// Draws the notifications with their top-right corner at `anchor`.
+    pub fn render(&mut self, ui: &Ui, anchor: [f32; 2]) {
+        if self.queue.is_empty() || self.do_not_disturb {
+            return;
+        }
+
+        let mut dismissed = None;
+
+        ui.window("_notifications")
+            .title_bar(false)
+            .resizable(false)
+            .movable(false)
+            .always_auto_resize(true)
+            .position(anchor, Condition::Always)
+            .position_pivot([1f32, 0f32])
+            .build(|| {
+                let _wrap = ui.push_text_wrap_pos_with_pos(NOTIFICATION_WIDTH);
+
+                for (index, notification) in self.queue.iter().enumerate() {
+                    if index > 0 {
+                        ui.separator();
+                    }
+
+                    ui.text_disabled(notification.time.format("%X").to_string());
+                    ui.text(&notification.message);
+
+                    if ui.button(format!("{}##_notification_{index}", tr("Dismiss"))) {
+                        dismissed = Some(index);
+                    }
+                }
+            });
+
+        if let Some(index) = dismissed {
+            self.queue.remove(index);
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use std::cell::Cell;
+use std::panic::AssertUnwindSafe;
+
+thread_local! {
+    // how many `catch_unwind` calls are running, nested ones included
+    static CATCHING: Cell<usize> = const { Cell::new(0) };
+}
+
+/// `std::panic::catch_unwind`, but the panic hook only logs panics
+/// caught here instead of reporting them as a crash.
+pub fn catch_unwind<R>(f: impl FnOnce() -> R) -> std::thread::Result<R> {
+    CATCHING.set(CATCHING.get() + 1);
+    let result = std::panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(CATCHING.get() - 1);
+
+    result
+}
+
+/// Wraps the current panic hook (e.g. the executable's crash
+/// report) so it only runs for panics nothing recovers from,
+/// after showing windows hidden on other workspaces.
+pub fn install_hook() {
+    let previous = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info| {
+        // unavailable while the thread shuts down
+        if CATCHING.try_with(Cell::get).unwrap_or(0) > 0 {
+            log::error!("Recovered from a panic: {info}");
+            return;
+        }
+
+        // the crash report blocks until dismissed
+        #[cfg(windows)]
+        crate::win32::workspaces::restore();
+
+        previous(info);
+    }));
+}
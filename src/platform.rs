@@ -0,0 +1,321 @@
+use winit::{
+    event_loop::ActiveEventLoop,
+    raw_window_handle::HasWindowHandle,
+    window::{Window, WindowAttributes, WindowLevel}
+};
+use glutin::{
+    config::{ColorBufferType, ConfigTemplateBuilder, GlConfig as _},
+    context::{ContextApi, ContextAttributesBuilder, GlProfile, NotCurrentGlContext, Version, PossiblyCurrentContext as OpenGlContext},
+    display::{GetGlDisplay, GlDisplay},
+    error::ErrorKind,
+    surface::{GlSurface, Surface, SurfaceAttributesBuilder, SwapInterval, WindowSurface}
+};
+use imgui_winit_support::{HiDpiMode, WinitPlatform};
+use std::cmp::Reverse;
+use std::num::NonZeroU32;
+use imgui_glow_renderer::{
+    glow,
+    AutoRenderer
+};
+use imgui::internal::RawCast;
+use imgui_sys::ImGuiFreeType_GetBuilderForFreeType;
+use anyhow::{anyhow, bail, Context, Error, Result};
+use imgui::Context as ImGuiContext;
+use crate::fonts::{build_fonts, FontFiles};
+use crate::gl::GlError;
+use glutin::config::Config as GlConfig;
+use imgui_glow_renderer::glow::HasContext;
+use winit::dpi::{PhysicalPosition, Position, Size};
+use winit::monitor::{MonitorHandle, VideoModeHandle};
+
+/// The mode matching the monitor's current resolution at its highest
+/// refresh rate, falling back to the largest mode, as the platform
+/// lists video modes in no particular order.
+pub fn best_video_mode(monitor: &MonitorHandle) -> Result<VideoModeHandle> {
+    let current = monitor.size();
+
+    monitor
+        .video_modes()
+        .max_by_key(|mode| {
+            let size = mode.size();
+            (size == current, size.width as u64 * size.height as u64, mode.refresh_rate_millihertz())
+        })
+        .with_context(|| format!("Monitor '{}' reported no video modes", monitor.name().unwrap_or_default()))
+}
+
+/// `position` when it's on one of the available monitors, otherwise
+/// `monitor`'s top-left corner, e.g. when the monitor it was saved
+/// on has since been disconnected.
+pub fn window_position(event_loop: &ActiveEventLoop, monitor: &MonitorHandle, position: Option<[i32; 2]>) -> PhysicalPosition<i32> {
+    let on_screen = position.filter(|[x, y]| {
+        event_loop.available_monitors().any(|monitor| {
+            let (origin, size) = (monitor.position(), monitor.size());
+
+            *x >= origin.x
+                && *y >= origin.y
+                && *x < origin.x + size.width as i32
+                && *y < origin.y + size.height as i32
+        })
+    });
+
+    on_screen.map_or_else(|| monitor.position(), |[x, y]| PhysicalPosition::new(x, y))
+}
+
+/// Whether `e` comes from a lost context or surface (e.g. a driver
+/// reset or resolution change) rather than a bug in VeilDE.
+pub fn is_recoverable(e: &Error) -> bool {
+    let lost = e.chain()
+        .filter_map(|cause| cause.downcast_ref::<GlError>())
+        .any(GlError::is_context_lost);
+
+    lost || e.chain()
+        .filter_map(|cause| cause.downcast_ref::<glutin::error::Error>())
+        .any(|cause| matches!(
+            cause.error_kind(),
+            ErrorKind::ContextLost | ErrorKind::BadContext | ErrorKind::BadSurface | ErrorKind::BadCurrentSurface | ErrorKind::BadNativeWindow
+        ))
+}
+
+pub fn init_imgui(fonts: &FontFiles, font_size: f64, scale: f64) -> Result<ImGuiContext> {
+    let mut context = ImGuiContext::create();
+
+    context.set_ini_filename(None);
+
+    // freetype doesn't enable itself
+    // due to a bug in the 'imgui-sys'
+    // crate, that has yet to be patched
+    //
+    // https://github.com/imgui-rs/imgui-rs/issues/773
+    unsafe { context.fonts().raw_mut().FontBuilderIO = ImGuiFreeType_GetBuilderForFreeType(); }
+    context.io_mut().font_global_scale = 1f32; // scale through font data for high quality
+
+    let glyph_offset = build_fonts(&mut context, fonts, font_size, scale).context("Failed to build fonts")?;
+
+    log::debug!("Calculated glyph offset: {glyph_offset}");
+
+    Ok(context)
+}
+
+pub fn init_winit(imgui: &mut ImGuiContext, window: &Window) -> Result<WinitPlatform> {
+    let mut context = WinitPlatform::new(imgui);
+
+    // fonts are rasterized at the physical
+    // pixel size, so imgui works in physical
+    // pixels rather than scaling everything
+    context.attach_window(imgui.io_mut(), window, HiDpiMode::Locked(1f64));
+
+    Ok(context)
+}
+
+pub fn init_glutin<T: Into<Size<>>, S: Into<Position<>>>(event_loop: &ActiveEventLoop, title: &str, size: T, position: S, windowed: bool) -> Result<(Window, GlConfig)> {
+    let attributes = if windowed {
+        // for development, an ordinary window
+        WindowAttributes::default()
+            .with_title(title)
+            .with_inner_size(size)
+            .with_resizable(true)
+            .with_position(position)
+    } else {
+        WindowAttributes::default()
+            .with_title(title)
+            .with_inner_size(size)
+            // both `Fullscreen::Exclusive` and `Fullscreen::Borderless`
+            // lose transparency on Windows, as DWM promotes any window
+            // exactly covering a monitor to a fullscreen flip surface
+            // and stops compositing it; a borderless window slightly
+            // larger than the monitor stays composited and transparent
+            .with_fullscreen(None)
+            .with_decorations(false)
+            .with_transparent(true)
+            .with_position(position)
+            .with_window_level(WindowLevel::AlwaysOnBottom)
+    };
+
+    // the window being transparent doesn't mean the
+    // framebuffer is, some drivers leave out its alpha
+    let template = match windowed {
+        true => ConfigTemplateBuilder::new(),
+        false => ConfigTemplateBuilder::new().with_transparency(true).with_alpha_size(8),
+    };
+
+    let (window, config) = glutin_winit::DisplayBuilder::new()
+        .with_window_attributes(Some(attributes))
+        .build(
+        event_loop,
+        template,
+        |cfg| {
+            cfg.max_by_key(config_score).context("Failed to get next configuration value").unwrap()
+        }
+    ).map_err(|e| match windowed {
+        true => anyhow!("Failed to initialize glutin: {e}"),
+        false => anyhow!("Failed to initialize glutin, the driver may have no transparent framebuffer config ({e}), try updating it or running with --windowed"),
+    })?;
+
+    log::info!(
+        "Using framebuffer config: {:?}, alpha {}, depth {}, stencil {}, {} samples, sRGB {}, transparency {:?}, hardware accelerated {}",
+        config.color_buffer_type(),
+        config.alpha_size(),
+        config.depth_size(),
+        config.stencil_size(),
+        config.num_samples(),
+        config.srgb_capable(),
+        config.supports_transparency(),
+        config.hardware_accelerated()
+    );
+
+    if !windowed && (config.alpha_size() == 0 || config.supports_transparency() == Some(false)) {
+        bail!("The framebuffer config has no alpha channel, so the desktop can't be transparent; try updating the graphics driver or running with --windowed");
+    }
+
+    Ok(
+        (window.context("Failed to create window")?, config)
+    )
+}
+
+/// Ranks a framebuffer config, the greatest being picked when several GPUs or
+/// drivers offer them. Transparency comes first, as the desktop is composited
+/// over the windows behind it, then hardware acceleration, 8 bit color and
+/// alpha, and sRGB. Nothing is drawn with multisampling, depth or stencil,
+/// so less of them wastes less memory.
+fn config_score(config: &GlConfig) -> impl Ord {
+    let color_size = match config.color_buffer_type() {
+        Some(ColorBufferType::Rgb { r_size, g_size, b_size }) => r_size.min(g_size).min(b_size),
+        _ => 0,
+    };
+
+    (
+        config.supports_transparency().unwrap_or(false),
+        config.hardware_accelerated(),
+        config.alpha_size() >= 8,
+        color_size >= 8,
+        config.srgb_capable(),
+        Reverse(config.num_samples()),
+        Reverse(config.depth_size() as u16 + config.stencil_size() as u16),
+    )
+}
+
+pub fn init_opengl(window: &Window, config: &GlConfig, size: [u32; 2], debug: bool) -> Result<(OpenGlContext, Surface<WindowSurface>)> {
+    let window_handle = window
+        .window_handle()
+        .context("Failed to get window handle for context")?
+        .as_raw();
+
+    // the shaders are written against 330
+    let core_attributes = ContextAttributesBuilder::new()
+        .with_profile(GlProfile::Core)
+        .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 3))))
+        .with_debug(debug)
+        .build(Some(window_handle));
+
+    // glutin does not provide a
+    // safe alternative to creating
+    // display contexts with winit
+    let context = match unsafe { config.display().create_context(config, &core_attributes) } {
+        Ok(context) => context,
+        Err(e) => {
+            // e.g. GLES only drivers, where `detect_glsl_version` adapts the shaders
+            log::warn!("Failed to create OpenGL 3.3 core context, falling back to the driver default: {e}");
+
+            unsafe {
+                config.display().create_context(
+                    config,
+                    &ContextAttributesBuilder::new()
+                        .with_debug(debug)
+                        .build(Some(window_handle))
+                ).context("Failed to create OpenGL context")?
+            }
+        }
+    };
+
+    // glutin does not provide a safe
+    // alternative to creating window
+    // surfaces with winit
+    let surface = unsafe {
+        config
+            .display()
+            .create_window_surface(
+                config,
+                &SurfaceAttributesBuilder::<WindowSurface>::new()
+                    .with_srgb(Some(true))
+                    .build(
+                        window
+                            .window_handle()
+                            .context("Failed to get window handle for surface")?
+                            .as_raw(),
+                        NonZeroU32::new(size[0]).context("Window surface width was zero or out-of-bounds")?,
+                        NonZeroU32::new(size[1]).context("Window surface height was zero or out-of-bounds")?,
+                    )
+            )
+            .context("Failed to create window surface")?
+    };
+
+    Ok((
+        context.make_current(&surface)
+            .context("Failed to make OpenGL context current")?,
+
+        surface
+    ))
+}
+
+pub fn init_swap_interval(opengl: &OpenGlContext, surface: &Surface<WindowSurface>) -> Result<()> {
+    surface.set_swap_interval(
+        opengl,
+        SwapInterval::Wait(
+            NonZeroU32::new(1)
+                .context("Swap interval was zero or out-of-bounds")?
+        )
+    ).context("Failed to set swap interval")
+}
+
+/// A glow context for `opengl`, without imgui, e.g. for the splash.
+pub fn load_gl(opengl: &OpenGlContext) -> glow::Context {
+    // glow requires using `get_proc_address`,
+    // which is an inherently unsafe concept
+    unsafe {
+        glow::Context::from_loader_function_cstr(
+            |s| {
+                opengl
+                    .display()
+                    .get_proc_address(s)
+                    .cast()
+            })
+    }
+}
+
+pub fn init_glow(opengl: &OpenGlContext, imgui: &mut ImGuiContext, debug: bool) -> Result<AutoRenderer> {
+    let mut gl = load_gl(opengl);
+
+    // the renderer takes ownership of the
+    // context, so this has to happen first
+    if debug {
+        init_debug_output(&mut gl);
+    }
+
+    AutoRenderer::new(gl, imgui).context("Failed to create GLOW context")
+}
+
+/// Routes driver messages into the log, as glow
+/// doesn't report errors from most GL calls.
+fn init_debug_output(gl: &mut glow::Context) {
+    if !gl.supports_debug() {
+        log::warn!("OpenGL debug output is not supported by this driver");
+        return;
+    }
+
+    // no safe way to achieve this
+    unsafe {
+        gl.enable(glow::DEBUG_OUTPUT);
+        gl.enable(glow::DEBUG_OUTPUT_SYNCHRONOUS); // messages arrive from the offending call
+        gl.debug_message_callback(|_source, kind, id, severity, message| {
+            match severity {
+                glow::DEBUG_SEVERITY_HIGH => log::error!("OpenGL ({kind:#x}, {id}): {message}"),
+                glow::DEBUG_SEVERITY_MEDIUM => log::warn!("OpenGL ({kind:#x}, {id}): {message}"),
+                glow::DEBUG_SEVERITY_LOW => log::info!("OpenGL ({kind:#x}, {id}): {message}"),
+
+                // buffer placement hints
+                // and the like, every frame
+                _ => { }
+            }
+        });
+    }
+}
@@ -0,0 +1,187 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use anyhow::{bail, Context, Result};
+use imgui::Ui;
+use libloading::Library;
+use crate::config::Config;
+use crate::consts::*;
+use crate::panics::catch_unwind;
+use crate::utils::resolve_path;
+use crate::widgets::Widget;
+
+/// Exports a plugin's entry point, `create` being called with the
+/// config for each monitor's widget, e.g. `export_plugin!(MyWidget::new)`.
+///
+/// Plugins must be built with the same compiler and VeilDE version,
+/// as `Widget` isn't a stable ABI, which the version check can't fully catch.
+#[macro_export]
+macro_rules! export_plugin {
+    ($create:path) => {
+        #[unsafe(no_mangle)]
+        pub static VEILDE_PLUGIN_ABI: u32 = $crate::consts::PLUGIN_ABI_VERSION;
+
+        #[unsafe(no_mangle)]
+        pub fn veilde_plugin(config: &$crate::config::Config) -> Box<dyn $crate::Widget> {
+            Box::new($create(config))
+        }
+    };
+}
+
+type CreateWidget = fn(&Config) -> Box<dyn Widget>;
+
+/// A shared library from `PLUGIN_DIRECTORY`, loaded until the process exits.
+pub struct Plugin {
+    pub name: String, // the file's name without its extension
+    create: CreateWidget,
+    _library: Library, // after `create`, which points into it
+}
+
+static PLUGINS: OnceLock<Vec<Plugin>> = OnceLock::new();
+
+/// Loads every plugin once, those that fail are logged and skipped.
+pub fn load_plugins() {
+    PLUGINS.get_or_init(|| {
+        let paths = match plugin_paths() {
+            Ok(paths) => paths,
+            Err(e) => {
+                log::warn!("{:?}", e.context("Failed to list plugins"));
+                return Vec::new();
+            }
+        };
+
+        paths
+            .iter()
+            .filter_map(|path| {
+                load_plugin(path)
+                    .with_context(|| format!("Failed to load plugin '{}'", path.display()))
+                    .inspect(|plugin| log::info!("Loaded plugin '{}'", plugin.name))
+                    .inspect_err(|e| log::warn!("{e:?}"))
+                    .ok()
+            })
+            .collect()
+    });
+}
+
+/// The loaded plugins, in file name order.
+pub fn plugins() -> &'static [Plugin] {
+    PLUGINS.get().map_or(&[], Vec::as_slice)
+}
+
+fn plugin_paths() -> Result<Vec<PathBuf>> {
+    let directory = resolve_path(PLUGIN_DIRECTORY)?;
+
+    // plugins are optional
+    if !directory.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(&directory)
+        .with_context(|| format!("Failed to read '{}'", directory.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == std::env::consts::DLL_EXTENSION))
+        .collect();
+
+    paths.sort();
+    Ok(paths)
+}
+
+fn load_plugin(path: &Path) -> Result<Plugin> {
+    let name = path
+        .file_stem()
+        .context("Plugin path has no file name")?
+        .to_string_lossy()
+        .into_owned();
+
+    // loading runs the library's initializers, it's
+    // trusted like the executable's own directory
+    let library = unsafe { Library::new(path) }.context("Failed to load library")?;
+
+    // checked before the entry point, whose
+    // signature is only valid for this version
+    let abi = unsafe {
+        **library
+            .get::<*const u32>(PLUGIN_ABI_SYMBOL)
+            .context("Library isn't a VeilDE plugin, it has no ABI version")?
+    };
+
+    if abi != PLUGIN_ABI_VERSION {
+        bail!("Plugin ABI version {abi} is incompatible, expected {PLUGIN_ABI_VERSION}");
+    }
+
+    let create = unsafe {
+        *library
+            .get::<CreateWidget>(PLUGIN_ENTRY_SYMBOL)
+            .context("Plugin has no entry point")?
+    };
+
+    Ok(Plugin { name, create, _library: library })
+}
+
+/// A plugin's widget, disabled instead of ending the desktop when it panics.
+pub struct PluginWidget {
+    name: &'static str,
+    widget: RefCell<Option<Box<dyn Widget>>>, // `None` once disabled
+}
+
+impl PluginWidget {
+    pub fn new(plugin: &'static Plugin, config: &Config) -> Self {
+        let widget = catch_unwind(|| (plugin.create)(config))
+            .inspect_err(|_| log::error!("Plugin '{}' panicked while creating its widget", plugin.name))
+            .ok();
+
+        Self {
+            name: &plugin.name,
+            widget: RefCell::new(widget),
+        }
+    }
+
+    fn call<R>(&self, default: R, f: impl FnOnce(&mut dyn Widget) -> R) -> R {
+        let mut widget = self.widget.borrow_mut();
+
+        let Some(inner) = widget.as_mut() else {
+            return default;
+        };
+
+        match catch_unwind(|| f(inner.as_mut())) {
+            Ok(result) => result,
+
+            Err(_) => {
+                log::error!("Plugin '{}' panicked and was disabled", self.name);
+
+                // its state may be broken, so
+                // dropping it could panic again
+                std::mem::forget(widget.take());
+                default
+            }
+        }
+    }
+}
+
+impl Widget for PluginWidget {
+    fn render(&mut self, ui: &Ui) {
+        self.call((), |widget| widget.render(ui));
+    }
+
+    fn update(&mut self, dt: Duration) {
+        self.call((), |widget| widget.update(dt));
+    }
+
+    fn visible(&self) -> bool {
+        self.call(false, |widget| widget.visible())
+    }
+
+    fn essential(&self) -> bool {
+        self.call(false, |widget| widget.essential())
+    }
+
+    fn desktop_menu(&mut self, ui: &Ui) {
+        self.call((), |widget| widget.desktop_menu(ui));
+    }
+
+    fn apply_config(&mut self, config: &Config) {
+        self.call((), |widget| widget.apply_config(config));
+    }
+}
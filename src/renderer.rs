@@ -1,92 +1,748 @@
+use std::path::Path;
 use std::rc::Rc;
-use anyhow::{anyhow, bail};
+use std::time::Duration;
+use anyhow::{anyhow, bail, Context};
+use imgui::TextureId;
 use imgui_glow_renderer::glow;
-use imgui_glow_renderer::glow::{HasContext, NativeProgram, NativeShader, NativeVertexArray};
+use imgui_glow_renderer::glow::{
+    HasContext, NativeBuffer, NativeFramebuffer, NativeProgram, NativeTexture, NativeUniformLocation, NativeVertexArray
+};
 use anyhow::Result;
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use crate::consts::*;
+use crate::gl::Gl;
+use crate::utils::resolve_path;
 
 pub struct VeilDERenderer {
-    gl: Rc<glow::Context>,
+    gl: Gl,
+    glsl_version: String,
     program: NativeProgram,
     vertex_array: NativeVertexArray,
+    vertex_buffer: NativeBuffer,
+    vertex_count: i32,
+    quad_vertex_array: NativeVertexArray, // empty, the quad comes from `gl_VertexID`
+    uniforms: VeilDEUniforms,
+    wallpaper: Option<VeilDEWallpaper>,
+    blur: VeilDEBlur,
+    night_light: VeilDENightLight,
+    screensaver: VeilDEScreensaver,
 }
 
-impl VeilDERenderer {
-    pub fn new(gl: &Rc<glow::Context>) -> Result<Self> {
-        unsafe {
-            let program = gl
-                .create_program()
-                .map_err(|_| anyhow!("Failed to create OpenGL program"))?;
+/// How a wallpaper image is fit to the screen.
+#[allow(unused)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WallpaperMode {
+    /// Fills the screen, ignoring the image aspect ratio.
+    Stretch,
+    /// Fills the screen, cropping the image to keep its aspect ratio.
+    Cover,
+}
 
-            let vertex_array = gl
-                .create_vertex_array()
-                .map_err(|_| anyhow!("Failed to create vertex array"))?;
+struct WallpaperImage {
+    texture: NativeTexture,
+    size: [f32; 2],
+}
+
+impl WallpaperImage {
+    fn upload(gl: &glow::Context, image: &RgbaImage) -> Result<Self> {
+        let texture = upload_rgba_texture(gl, image.width(), image.height(), image.as_raw())
+            .context("Failed to upload wallpaper texture")?;
+
+        Ok(Self { texture, size: [image.width() as f32, image.height() as f32] })
+    }
+
+    fn uv_scale(&self, resolution: [f32; 2], mode: WallpaperMode) -> [f32; 2] {
+        let screen_aspect = resolution[0] / resolution[1];
+        let image_aspect = self.size[0] / self.size[1];
+
+        match mode {
+            WallpaperMode::Stretch => [1f32, 1f32],
+            WallpaperMode::Cover if image_aspect > screen_aspect => [screen_aspect / image_aspect, 1f32],
+            WallpaperMode::Cover => [1f32, image_aspect / screen_aspect],
+        }
+    }
+}
 
-            let mut shaders = [
-                (glow::VERTEX_SHADER, crate::consts::VERTEX_SHADER_SOURCE, Option::<NativeShader>::None),
-                (glow::FRAGMENT_SHADER, crate::consts::FRAGMENT_SHADER_SOURCE, Option::<NativeShader>::None)
-            ];
+struct VeilDEWallpaper {
+    program: NativeProgram,
+    uv_scale: Option<NativeUniformLocation>,
+    incoming_uv_scale: Option<NativeUniformLocation>,
+    incoming_sampler: Option<NativeUniformLocation>,
+    mix: Option<NativeUniformLocation>,
+    image: WallpaperImage,
+    incoming: Option<WallpaperImage>, // crossfading in over `image`
+    fade: f32, // progress of the crossfade, 0 to 1
+    mode: WallpaperMode,
+}
+
+impl VeilDEWallpaper {
+    fn delete(&self, gl: &Gl) {
+        gl.delete_program(self.program);
+
+        for image in std::iter::once(&self.image).chain(&self.incoming) {
+            gl.delete_texture(image.texture);
+        }
+    }
+}
 
-            for (kind, source, handle) in shaders.iter_mut() {
-                let shader = gl
-                    .create_shader(*kind)
-                    .map_err(|_| anyhow!("Failed to create shader"))?;
+/// Two-pass Gaussian blur of a screen region, drawn back
+/// over it so translucent windows get a frosted backdrop.
+struct VeilDEBlur {
+    program: NativeProgram,
+    resolution: Option<NativeUniformLocation>,
+    direction: Option<NativeUniformLocation>,
+    radius: Option<NativeUniformLocation>,
+    tint: Option<NativeUniformLocation>,
+    targets: Option<VeilDEBlurTargets>, // sized to the last blurred region
+}
+
+/// A copy of the region, and a framebuffer per blur pass.
+struct VeilDEBlurTargets {
+    size: [i32; 2],
+    source: NativeTexture,
+    passes: [(NativeFramebuffer, NativeTexture); 2],
+}
 
-                gl.shader_source(shader, format!("#version 330\n{}", *source).as_str());
-                gl.compile_shader(shader);
+impl VeilDEBlurTargets {
+    fn new(gl: &glow::Context, size: [i32; 2]) -> Result<Self> {
+        let source = create_texture(gl, size[0] as u32, size[1] as u32, None)?;
+        let first = create_framebuffer(gl, size)?;
+        let second = create_framebuffer(gl, size)?;
+
+        Ok(
+            Self {
+                size,
+                source,
+                passes: [first, second],
+            }
+        )
+    }
+
+    fn delete(&self, gl: &Gl) {
+        gl.delete_texture(self.source);
+
+        for (framebuffer, texture) in self.passes {
+            gl.delete_framebuffer(framebuffer);
+            gl.delete_texture(texture);
+        }
+    }
+}
+
+impl VeilDEBlur {
+    fn new(gl: &Gl, glsl_version: &str) -> Result<Self> {
+        let program = compile_program(gl.context(), glsl_version, BLUR_VERTEX_SHADER_SOURCE, BLUR_FRAGMENT_SHADER_SOURCE)
+            .context("Failed to compile blur shaders")?;
+
+        Ok(
+            Self {
+                program,
+                resolution: gl.uniform_location(program, "u_resolution"),
+                direction: gl.uniform_location(program, "u_direction"),
+                radius: gl.uniform_location(program, "u_radius"),
+                tint: gl.uniform_location(program, "u_tint"),
+                targets: None,
+            }
+        )
+    }
+
+    fn delete(&mut self, gl: &Gl) {
+        gl.delete_program(self.program);
+
+        if let Some(targets) = self.targets.take() {
+            targets.delete(gl);
+        }
+    }
+}
+
+/// Color temperature filter, multiplied over the finished frame.
+struct VeilDENightLight {
+    program: NativeProgram,
+    temperature: Option<NativeUniformLocation>,
+    strength: Option<NativeUniformLocation>,
+}
+
+impl VeilDENightLight {
+    fn new(gl: &Gl, glsl_version: &str) -> Result<Self> {
+        let program = compile_program(gl.context(), glsl_version, BLUR_VERTEX_SHADER_SOURCE, NIGHT_LIGHT_FRAGMENT_SHADER_SOURCE)
+            .context("Failed to compile night light shaders")?;
+
+        Ok(
+            Self {
+                program,
+                temperature: gl.uniform_location(program, "u_temperature"),
+                strength: gl.uniform_location(program, "u_strength"),
+            }
+        )
+    }
+
+    fn delete(&self, gl: &Gl) {
+        gl.delete_program(self.program);
+    }
+}
+
+/// Animated pattern shown while idle, fading to black.
+struct VeilDEScreensaver {
+    program: NativeProgram,
+    resolution: Option<NativeUniformLocation>,
+    time: Option<NativeUniformLocation>,
+    dim: Option<NativeUniformLocation>,
+}
 
-                if !gl.get_shader_compile_status(shader) {
-                    bail!(gl.get_shader_info_log(shader));
+impl VeilDEScreensaver {
+    fn new(gl: &Gl, glsl_version: &str) -> Result<Self> {
+        let program = compile_program(gl.context(), glsl_version, BLUR_VERTEX_SHADER_SOURCE, SCREENSAVER_FRAGMENT_SHADER_SOURCE)
+            .context("Failed to compile screensaver shaders")?;
+
+        Ok(
+            Self {
+                program,
+                resolution: gl.uniform_location(program, "u_resolution"),
+                time: gl.uniform_location(program, "u_time"),
+                dim: gl.uniform_location(program, "u_dim"),
+            }
+        )
+    }
+
+    fn delete(&self, gl: &Gl) {
+        gl.delete_program(self.program);
+    }
+}
+
+/// Uniform locations of the background program, `None` when
+/// the shaders don't use (or the driver optimized out) a uniform.
+struct VeilDEUniforms {
+    resolution: Option<NativeUniformLocation>,
+    time: Option<NativeUniformLocation>,
+}
+
+impl VeilDEUniforms {
+    fn new(gl: &Gl, program: NativeProgram) -> Self {
+        Self {
+            resolution: gl.uniform_location(program, "u_resolution"),
+            time: gl.uniform_location(program, "u_time"),
+        }
+    }
+}
+
+// bound before linking rather than with `layout(location)`,
+// which older shading language versions don't support
+const POSITION_ATTRIBUTE: u32 = 0;
+
+/// Picks the `#version` directive for the shaders from
+/// the shading language version reported by the driver.
+pub fn detect_glsl_version(gl: &glow::Context) -> String {
+    if let Some(version) = GLSL_VERSION {
+        return version.to_string();
+    }
+
+    // e.g. "4.60 NVIDIA" or "OpenGL ES GLSL ES 3.00"
+    let reported = unsafe { gl.get_parameter_string(glow::SHADING_LANGUAGE_VERSION) };
+
+    let number = reported
+        .split_whitespace()
+        .find_map(|part| {
+            let (major, minor) = part.split_once('.')?;
+            Some(major.parse::<u32>().ok()? * 100 + minor.get(..2)?.parse::<u32>().ok()?)
+        });
+
+    match number {
+        _ if reported.contains("ES") => "300 es".to_string(),
+
+        // the shaders are written against 330, newer
+        // versions are compatible but older ones may
+        // still compile if they support `in`/`out`
+        Some(number) if number < 330 => number.to_string(),
+        _ => "330".to_string(),
+    }
+}
+
+fn load_shader_source(path: &str, fallback: &str) -> Result<String> {
+    let path = resolve_path(path)?;
+
+    if !path.exists() {
+        return Ok(fallback.to_string());
+    }
+
+    std::fs::read_to_string(&path).with_context(|| format!("Failed to read shader '{}'", path.display()))
+}
+
+/// Compiles and links the background program from the shaders
+/// on disk, falling back to the embedded sources for missing files.
+fn compile_background_program(gl: &glow::Context, glsl_version: &str) -> Result<NativeProgram> {
+    let vertex_source = load_shader_source(VERTEX_SHADER_PATH, VERTEX_SHADER_SOURCE)?;
+    let fragment_source = load_shader_source(FRAGMENT_SHADER_PATH, FRAGMENT_SHADER_SOURCE)?;
+
+    compile_program(gl, glsl_version, &vertex_source, &fragment_source)
+}
+
+pub fn compile_program(gl: &glow::Context, glsl_version: &str, vertex_source: &str, fragment_source: &str) -> Result<NativeProgram> {
+    unsafe {
+        let program = gl
+            .create_program()
+            .map_err(|_| anyhow!("Failed to create OpenGL program"))?;
+
+        let mut shaders = Vec::new();
+        let mut result = Ok(());
+
+        for (kind, source) in [(glow::VERTEX_SHADER, vertex_source), (glow::FRAGMENT_SHADER, fragment_source)] {
+            let shader = match gl.create_shader(kind) {
+                Ok(shader) => shader,
+                Err(_) => {
+                    result = Err(anyhow!("Failed to create shader"));
+                    break;
                 }
+            };
+
+            // ES has no default float precision in fragment shaders
+            let precision = if glsl_version.ends_with("es") { "precision mediump float;\n" } else { "" };
+
+            gl.shader_source(shader, format!("#version {glsl_version}\n{precision}{source}").as_str());
+            gl.compile_shader(shader);
 
-                gl.attach_shader(program, shader);
-                *handle = Some(shader);
+            if !gl.get_shader_compile_status(shader) {
+                result = Err(anyhow!(
+                    "Failed to compile shader with '#version {glsl_version}': {}",
+                    gl.get_shader_info_log(shader)
+                ));
+                gl.delete_shader(shader);
+                break;
             }
 
+            gl.attach_shader(program, shader);
+            shaders.push(shader);
+        }
+
+        if result.is_ok() {
+            gl.bind_attrib_location(program, POSITION_ATTRIBUTE, "a_position");
             gl.link_program(program);
             if !gl.get_program_link_status(program) {
-                bail!(gl.get_program_info_log(program));
+                result = Err(anyhow!(gl.get_program_info_log(program)));
             }
+        }
+
+        // cleanup shaders
+        for shader in shaders {
+            gl.detach_shader(program, shader);
+            gl.delete_shader(shader);
+        }
+
+        if let Err(e) = result {
+            gl.delete_program(program);
+            return Err(e);
+        }
 
-            // cleanup shaders
-            for &(_, _, shader) in &shaders {
-                gl.detach_shader(program, shader.unwrap());
-                gl.delete_shader(shader.unwrap());
+        Ok(program)
+    }
+}
+
+/// Uploads tightly packed RGBA8 pixels to a new linearly filtered texture.
+pub fn upload_rgba_texture(gl: &glow::Context, width: u32, height: u32, pixels: &[u8]) -> Result<NativeTexture> {
+    create_texture(gl, width, height, Some(pixels))
+}
+
+/// A linearly filtered RGBA8 texture, left uninitialized when `pixels` is `None`.
+fn create_texture(gl: &glow::Context, width: u32, height: u32, pixels: Option<&[u8]>) -> Result<NativeTexture> {
+    unsafe {
+        let texture = gl
+            .create_texture()
+            .map_err(|_| anyhow!("Failed to create texture"))?;
+
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA8 as i32,
+            width as i32,
+            height as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            glow::PixelUnpackData::Slice(pixels)
+        );
+        gl.bind_texture(glow::TEXTURE_2D, None);
+
+        Ok(texture)
+    }
+}
+
+/// A framebuffer rendering into a new texture of `size`.
+fn create_framebuffer(gl: &glow::Context, size: [i32; 2]) -> Result<(NativeFramebuffer, NativeTexture)> {
+    let texture = create_texture(gl, size[0] as u32, size[1] as u32, None)?;
+
+    unsafe {
+        let framebuffer = match gl.create_framebuffer() {
+            Ok(framebuffer) => framebuffer,
+            Err(_) => {
+                gl.delete_texture(texture);
+                bail!("Failed to create framebuffer");
             }
+        };
+
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+        gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::TEXTURE_2D, Some(texture), 0);
+        let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+
+        if status != glow::FRAMEBUFFER_COMPLETE {
+            gl.delete_framebuffer(framebuffer);
+            gl.delete_texture(texture);
+            bail!("Framebuffer is incomplete ({status:#x})");
+        }
+
+        Ok((framebuffer, texture))
+    }
+}
+
+/// The imgui texture id for a GL texture, as `AutoRenderer` maps ids to texture names.
+#[cfg_attr(not(windows), allow(dead_code))] // only windows-specific widgets draw textures
+pub fn imgui_texture_id(texture: NativeTexture) -> TextureId {
+    TextureId::new(texture.0.get() as usize)
+}
+
+impl VeilDERenderer {
+    pub fn new(context: &Rc<glow::Context>) -> Result<Self> {
+        let gl = Gl::new(context);
+
+        unsafe {
+            let glsl_version = detect_glsl_version(context);
+            let program = compile_background_program(context, &glsl_version)?;
+            let blur = VeilDEBlur::new(&gl, &glsl_version)?;
+            let night_light = VeilDENightLight::new(&gl, &glsl_version)?;
+            let screensaver = VeilDEScreensaver::new(&gl, &glsl_version)?;
+
+            let vertex_array = context
+                .create_vertex_array()
+                .map_err(|_| anyhow!("Failed to create vertex array"))?;
+
+            let vertex_buffer = context
+                .create_buffer()
+                .map_err(|_| anyhow!("Failed to create vertex buffer"))?;
+
+            // full-screen quads read no attributes, so drawing
+            // 4 vertices with `vertex_array` would read past
+            // the end of `BACKGROUND_VERTICES`
+            let quad_vertex_array = context
+                .create_vertex_array()
+                .map_err(|_| anyhow!("Failed to create vertex array"))?;
+
+            let vertices = BACKGROUND_VERTICES
+                .iter()
+                .flatten()
+                .flat_map(|component| component.to_ne_bytes())
+                .collect::<Vec<u8>>();
+
+            context.bind_vertex_array(Some(vertex_array));
+            context.bind_buffer(glow::ARRAY_BUFFER, Some(vertex_buffer));
+            context.buffer_data_u8_slice(glow::ARRAY_BUFFER, &vertices, glow::STATIC_DRAW);
+            context.enable_vertex_attrib_array(POSITION_ATTRIBUTE);
+            context.vertex_attrib_pointer_f32(POSITION_ATTRIBUTE, 2, glow::FLOAT, false, size_of::<[f32; 2]>() as i32, 0);
+            context.bind_vertex_array(None);
+            context.bind_buffer(glow::ARRAY_BUFFER, None);
+
+            gl.check("creating the renderer")?;
 
             Ok(
                 Self {
-                    gl: gl.clone(),
+                    uniforms: VeilDEUniforms::new(&gl, program),
+                    gl,
+                    glsl_version,
                     program,
-                    vertex_array
+                    vertex_array,
+                    vertex_buffer,
+                    vertex_count: BACKGROUND_VERTICES.len() as i32,
+                    quad_vertex_array,
+                    wallpaper: None,
+                    blur,
+                    night_light,
+                    screensaver,
                 }
             )
         }
     }
 
-    #[allow(unused)]
-    pub fn draw(&mut self) -> Result<()> {
-        unsafe {
-            self.gl.clear_color(0.0, 0.0, 0.0, 0.0);
-            self.gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
+    /// Recompiles the shaders, keeping the current program if they fail to compile or link.
+    pub fn reload_shaders(&mut self) -> Result<()> {
+        let program = compile_background_program(self.gl.context(), &self.glsl_version).context("Failed to reload shaders")?;
+        self.gl.delete_program(self.program);
+        self.program = program;
+        self.uniforms = VeilDEUniforms::new(&self.gl, program);
 
-            self.gl.enable(glow::BLEND);
-            self.gl.enable(glow::DEPTH_TEST);
-            self.gl.enable(glow::ALPHA);
-            
-            self.gl.blend_func(glow::SRC_ALPHA, glow::ONE_MINUS_SRC_ALPHA);
+        Ok(())
+    }
 
-            self.gl.use_program(Some(self.program));
+    /// Decodes the image at `path` and draws it behind everything instead of the background shader.
+    pub fn load_wallpaper<P: AsRef<Path>>(&mut self, path: P, mode: WallpaperMode) -> Result<()> {
+        let path = path.as_ref();
+        let image = image::open(path)
+            .with_context(|| format!("Failed to decode wallpaper '{}'", path.display()))?
+            .to_rgba8();
+
+        self.set_wallpaper(&image, mode)
+    }
+
+    /// Draws `image` behind everything instead of the background shader.
+    pub fn set_wallpaper(&mut self, image: &RgbaImage, mode: WallpaperMode) -> Result<()> {
+        let program = compile_program(
+            self.gl.context(),
+            &self.glsl_version,
+            WALLPAPER_VERTEX_SHADER_SOURCE,
+            WALLPAPER_FRAGMENT_SHADER_SOURCE
+        ).context("Failed to compile wallpaper shaders")?;
+
+        let image = match WallpaperImage::upload(self.gl.context(), image) {
+            Ok(image) => image,
+            Err(e) => {
+                self.gl.delete_program(program);
+                return Err(e);
+            }
+        };
+
+        let wallpaper = VeilDEWallpaper {
+            program,
+            uv_scale: self.gl.uniform_location(program, "u_uv_scale"),
+            incoming_uv_scale: self.gl.uniform_location(program, "u_incoming_uv_scale"),
+            incoming_sampler: self.gl.uniform_location(program, "u_incoming"),
+            mix: self.gl.uniform_location(program, "u_mix"),
+            image,
+            incoming: None,
+            fade: 0f32,
+            mode,
+        };
+
+        if let Some(previous) = self.wallpaper.replace(wallpaper) {
+            previous.delete(&self.gl);
+        }
+
+        Ok(())
+    }
+
+    /// Fades from the current wallpaper to `image` over `WALLPAPER_CROSSFADE_DURATION`,
+    /// or shows it right away without a wallpaper to fade from.
+    pub fn crossfade_wallpaper(&mut self, image: &RgbaImage, mode: WallpaperMode) -> Result<()> {
+        let Some(wallpaper) = &mut self.wallpaper else {
+            return self.set_wallpaper(image, mode);
+        };
+
+        let incoming = WallpaperImage::upload(self.gl.context(), image)?;
+
+        // a fade still running is cut short
+        if let Some(previous) = wallpaper.incoming.replace(incoming) {
+            self.gl.delete_texture(std::mem::replace(&mut wallpaper.image, previous).texture);
+        }
+
+        wallpaper.fade = 0f32;
+        wallpaper.mode = mode;
+
+        Ok(())
+    }
+
+    /// Moves a running crossfade along, returning whether it's still running.
+    pub fn advance_crossfade(&mut self, dt: Duration) -> bool {
+        let Some(wallpaper) = &mut self.wallpaper else {
+            return false;
+        };
+
+        if wallpaper.incoming.is_none() {
+            return false;
+        }
+
+        wallpaper.fade += dt.as_secs_f32() / WALLPAPER_CROSSFADE_DURATION.as_secs_f32();
+
+        if wallpaper.fade < 1f32 {
+            return true;
+        }
+
+        if let Some(incoming) = wallpaper.incoming.take() {
+            self.gl.delete_texture(std::mem::replace(&mut wallpaper.image, incoming).texture);
+        }
+
+        wallpaper.fade = 0f32;
+
+        false
+    }
+
+    /// Removes the wallpaper, going back to the background shader.
+    pub fn clear_wallpaper(&mut self) {
+        if let Some(wallpaper) = self.wallpaper.take() {
+            wallpaper.delete(&self.gl);
+        }
+    }
+
+    pub fn draw(&mut self, resolution: [f32; 2], time: f32) -> Result<()> {
+        // the background is a single flat layer, so there's
+        // no depth buffer requested and no depth testing
+        self.gl.set_blend(Some((glow::SRC_ALPHA, glow::ONE_MINUS_SRC_ALPHA)));
+
+        if let Some(wallpaper) = &self.wallpaper {
+            let uv_scale = wallpaper.image.uv_scale(resolution, wallpaper.mode);
+
+            // without a fade, the incoming texture is the
+            // current one again, weighted out by `u_mix`
+            let incoming = wallpaper.incoming.as_ref().unwrap_or(&wallpaper.image);
+            let incoming_uv_scale = incoming.uv_scale(resolution, wallpaper.mode);
+
+            self.gl.bind_vertex_array(Some(self.quad_vertex_array));
+            self.gl.use_program(Some(wallpaper.program));
+            self.gl.uniform_2_f32(wallpaper.uv_scale.as_ref(), uv_scale);
+            self.gl.uniform_2_f32(wallpaper.incoming_uv_scale.as_ref(), incoming_uv_scale);
+            self.gl.uniform_1_i32(wallpaper.incoming_sampler.as_ref(), 1);
+            self.gl.uniform_1_f32(wallpaper.mix.as_ref(), wallpaper.fade);
+            self.gl.bind_texture(1, Some(incoming.texture));
+            self.gl.bind_texture(0, Some(wallpaper.image.texture));
+
+            self.gl.draw_arrays(glow::TRIANGLE_STRIP, 4); // full-screen quad
+
+            self.gl.bind_texture(1, None);
+            self.gl.bind_texture(0, None);
+        } else {
             self.gl.bind_vertex_array(Some(self.vertex_array));
+            self.gl.use_program(Some(self.program));
+            self.gl.uniform_2_f32(self.uniforms.resolution.as_ref(), resolution);
+            self.gl.uniform_1_f32(self.uniforms.time.as_ref(), time);
 
-            self.gl.draw_arrays(glow::TRIANGLES, 0, 3); // shaders are bound
+            self.gl.draw_arrays(glow::TRIANGLES, self.vertex_count);
         }
+
+        // imgui composites on top
+        // and does not expect any
+        // bindings
+        self.gl.bind_vertex_array(None);
+        self.gl.use_program(None);
+
+        self.gl.check("drawing the background")?;
+
         Ok(())
     }
 
-    pub fn shutdown(&mut self) {
-        unsafe {
-            self.gl.delete_program(self.program);
-            self.gl.delete_vertex_array(self.vertex_array);
+    /// Blurs what's already drawn inside `rect` (x, y, width and height in pixels
+    /// from the top-left corner) and tints it, as a backdrop for the window there.
+    pub fn draw_blur(&mut self, resolution: [f32; 2], rect: [f32; 4], radius: f32, tint: [f32; 4]) -> Result<()> {
+        let size = [rect[2] as i32, rect[3] as i32];
+
+        if size[0] <= 0 || size[1] <= 0 {
+            return Ok(());
+        }
+
+        // reallocated when the region changes, e.g. after `Resized`
+        if self.blur.targets.as_ref().is_none_or(|targets| targets.size != size) {
+            if let Some(targets) = self.blur.targets.take() {
+                targets.delete(&self.gl);
+            }
+
+            self.blur.targets = Some(VeilDEBlurTargets::new(self.gl.context(), size).context("Failed to create blur targets")?);
+        }
+
+        let Some(targets) = &self.blur.targets else {
+            return Ok(());
+        };
+
+        // the framebuffer's origin is the bottom-left corner
+        let x = rect[0] as i32;
+        let y = (resolution[1] - rect[1] - rect[3]) as i32;
+
+        self.gl.copy_to_texture(targets.source, x, y, size[0], size[1]);
+
+        self.gl.set_blend(None);
+        self.gl.bind_vertex_array(Some(self.quad_vertex_array));
+        self.gl.use_program(Some(self.blur.program));
+        self.gl.uniform_2_f32(self.blur.resolution.as_ref(), [size[0] as f32, size[1] as f32]);
+        self.gl.uniform_1_f32(self.blur.radius.as_ref(), radius);
+        self.gl.uniform_4_f32(self.blur.tint.as_ref(), [0f32; 4]);
+        self.gl.viewport(0, 0, size[0], size[1])?;
+
+        // horizontal then vertical, each into its own framebuffer
+        let mut input = targets.source;
+
+        for ((framebuffer, output), direction) in targets.passes.iter().zip([[1f32, 0f32], [0f32, 1f32]]) {
+            self.gl.bind_framebuffer(Some(*framebuffer));
+            self.gl.bind_texture(0, Some(input));
+            self.gl.uniform_2_f32(self.blur.direction.as_ref(), direction);
+            self.gl.draw_arrays(glow::TRIANGLE_STRIP, 4);
+
+            input = *output;
         }
+
+        // back over the region, only tinting
+        self.gl.bind_framebuffer(None);
+        self.gl.viewport(x, y, size[0], size[1])?;
+        self.gl.bind_texture(0, Some(input));
+        self.gl.uniform_1_f32(self.blur.radius.as_ref(), 0f32);
+        self.gl.uniform_4_f32(self.blur.tint.as_ref(), tint);
+        self.gl.draw_arrays(glow::TRIANGLE_STRIP, 4);
+
+        self.gl.viewport(0, 0, resolution[0] as i32, resolution[1] as i32)?;
+        self.gl.bind_texture(0, None);
+        self.gl.bind_vertex_array(None);
+        self.gl.use_program(None);
+        self.gl.set_blend(Some((glow::SRC_ALPHA, glow::ONE_MINUS_SRC_ALPHA)));
+
+        self.gl.check("blurring the taskbar backdrop")?;
+
+        Ok(())
+    }
+
+    /// Warms everything drawn so far towards `temperature` (in kelvin) by `strength`.
+    pub fn draw_night_light(&mut self, temperature: f32, strength: f32) -> Result<()> {
+        // the frame times the shader's color
+        self.gl.set_blend(Some((glow::DST_COLOR, glow::ZERO)));
+
+        self.gl.bind_vertex_array(Some(self.quad_vertex_array));
+        self.gl.use_program(Some(self.night_light.program));
+        self.gl.uniform_1_f32(self.night_light.temperature.as_ref(), temperature);
+        self.gl.uniform_1_f32(self.night_light.strength.as_ref(), strength);
+        self.gl.draw_arrays(glow::TRIANGLE_STRIP, 4);
+
+        self.gl.set_blend(Some((glow::SRC_ALPHA, glow::ONE_MINUS_SRC_ALPHA)));
+        self.gl.bind_vertex_array(None);
+        self.gl.use_program(None);
+
+        self.gl.check("drawing the night light")?;
+
+        Ok(())
+    }
+
+    /// Covers the frame with the screensaver at `time` seconds in, dimmed towards black by `dim`.
+    pub fn draw_screensaver(&mut self, resolution: [f32; 2], time: f32, dim: f32) -> Result<()> {
+        // opaque, so nothing under it matters
+        self.gl.set_blend(None);
+
+        self.gl.bind_vertex_array(Some(self.quad_vertex_array));
+        self.gl.use_program(Some(self.screensaver.program));
+        self.gl.uniform_2_f32(self.screensaver.resolution.as_ref(), resolution);
+        self.gl.uniform_1_f32(self.screensaver.time.as_ref(), time);
+        self.gl.uniform_1_f32(self.screensaver.dim.as_ref(), dim);
+        self.gl.draw_arrays(glow::TRIANGLE_STRIP, 4);
+
+        self.gl.set_blend(Some((glow::SRC_ALPHA, glow::ONE_MINUS_SRC_ALPHA)));
+        self.gl.bind_vertex_array(None);
+        self.gl.use_program(None);
+
+        self.gl.check("drawing the screensaver")?;
+
+        Ok(())
+    }
+
+    /// Checked access to the context this renderer draws with.
+    pub fn gl(&self) -> &Gl {
+        &self.gl
+    }
+
+    pub fn shutdown(&mut self) {
+        self.gl.delete_program(self.program);
+        self.gl.delete_vertex_array(self.vertex_array);
+        self.gl.delete_vertex_array(self.quad_vertex_array);
+        self.gl.delete_buffer(self.vertex_buffer);
+
+        self.blur.delete(&self.gl);
+        self.night_light.delete(&self.gl);
+        self.screensaver.delete(&self.gl);
+
+        self.clear_wallpaper();
     }
 }
\ No newline at end of file
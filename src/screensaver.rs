@@ -0,0 +1,30 @@
+use std::time::Duration;
+use crate::consts::*;
+
+/// Shown instead of the desktop after a while without input, fading to black.
+pub struct Screensaver {
+    elapsed: Duration,
+}
+
+impl Screensaver {
+    pub fn new() -> Self {
+        Self {
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub fn update(&mut self, dt: Duration) {
+        self.elapsed += dt;
+    }
+
+    /// Seconds since it started, for the shader's animation.
+    pub fn time(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+
+    /// 0 until `SCREENSAVER_DIM_DELAY`, then towards 1 (black) over `SCREENSAVER_DIM_DURATION`.
+    pub fn dim(&self) -> f32 {
+        let dimming = self.elapsed.saturating_sub(SCREENSAVER_DIM_DELAY);
+        (dimming.as_secs_f32() / SCREENSAVER_DIM_DURATION.as_secs_f32()).min(1f32)
+    }
+}
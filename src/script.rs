@@ -0,0 +1,91 @@
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+use anyhow::{anyhow, Context, Result};
+use rhai::{Dynamic, Engine, EvalAltResult};
+use crate::config::{Config, Hotkey};
+use crate::consts::*;
+
+type ScriptResult = Result<(), Box<EvalAltResult>>;
+
+/// Runs the Rhai script at `path` over `config`, e.g.
+///
+/// ```rhai
+/// set_theme("light");
+/// add_widget("clock");
+/// bind_hotkey("Ctrl+Alt+L", "lock-screen");
+/// set("taskbar_height", 40.0);
+/// ```
+///
+/// Calls only change the returned copy, so `config` is
+/// what's left to use when the script fails.
+pub fn run_script(path: &Path, config: &Config) -> Result<Config> {
+    // scripts are optional
+    if !path.exists() {
+        return Ok(config.clone());
+    }
+
+    let source = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config script '{}'", path.display()))?;
+
+    let scripted = Rc::new(RefCell::new(config.clone()));
+    let mut engine = Engine::new();
+
+    engine.set_max_operations(CONFIG_SCRIPT_OPERATION_LIMIT);
+
+    // names already on the taskbar are skipped, so
+    // a saved config doesn't get them twice
+    let config = scripted.clone();
+    engine.register_fn("add_widget", move |name: &str| {
+        let widgets = &mut config.borrow_mut().widgets;
+
+        if !widgets.iter().any(|widget| widget == name) {
+            widgets.push(name.to_string());
+        }
+    });
+
+    let config = scripted.clone();
+    engine.register_fn("set_theme", move |theme: &str| {
+        config.borrow_mut().theme = theme.to_string();
+    });
+
+    // the command is named as in IPC
+    // messages, e.g. "toggle-taskbar"
+    let config = scripted.clone();
+    engine.register_fn("bind_hotkey", move |keys: &str, command: &str| -> ScriptResult {
+        let command = serde_json::from_value(serde_json::json!({ "command": command }))
+            .map_err(|e| format!("Invalid hotkey command '{command}': {e}"))?;
+
+        let hotkeys = &mut config.borrow_mut().hotkeys;
+        hotkeys.retain(|hotkey| !hotkey.keys.eq_ignore_ascii_case(keys));
+        hotkeys.push(Hotkey { keys: keys.to_string(), command });
+
+        Ok(())
+    });
+
+    // any field, checked like the config file
+    let config = scripted.clone();
+    engine.register_fn("set", move |field: &str, value: Dynamic| -> ScriptResult {
+        let value: serde_json::Value = rhai::serde::from_dynamic(&value)?;
+        let mut config = config.borrow_mut();
+        let mut fields = serde_json::to_value(&*config).map_err(|e| e.to_string())?;
+
+        *fields.get_mut(field).ok_or_else(|| format!("Unknown config field '{field}'"))? = value;
+
+        let changed: Config = serde_json::from_value(fields)
+            .map_err(|e| format!("Invalid value for '{field}': {e}"))?;
+
+        // command line overrides aren't serialized
+        *config = Config { windowed: config.windowed, monitor: config.monitor, ..changed };
+
+        Ok(())
+    });
+
+    // rhai's errors already report the line and position
+    engine
+        .run(&source)
+        .map_err(|e| anyhow!("{e}"))
+        .with_context(|| format!("Failed to run config script '{}'", path.display()))?;
+
+    Ok(scripted.take())
+}
@@ -0,0 +1,267 @@
+use std::collections::BTreeMap;
+use anyhow::{bail, Result};
+use imgui::{Condition, Ui};
+use crate::config::{Color, Config, TaskbarPosition};
+use crate::consts::*;
+use crate::i18n::tr;
+use crate::time::parse_format;
+use crate::utils::resolve_path;
+
+const TASKBAR_POSITIONS: [TaskbarPosition; 4] = [
+    TaskbarPosition::Top,
+    TaskbarPosition::Bottom,
+    TaskbarPosition::Left,
+    TaskbarPosition::Right,
+];
+
+/// What to do with the settings once edited.
+pub enum SettingsChange {
+    /// Applied to every monitor until the config file is reloaded.
+    Apply(Config),
+    /// Applied, with the options edited since the window
+    /// opened (see `copy_edited`) written to the config file.
+    Save { draft: Config, original: Config },
+    /// Window opacities, applied and written to the
+    /// config file without changing anything else in it.
+    WindowOpacity(BTreeMap<String, f32>),
+    /// Registers or unregisters VeilDE to start with Windows.
+    #[cfg(windows)]
+    RunAtStartup(bool),
+}
+
+/// Editor for the common config options.
+pub struct Settings {
+    open: bool,
+    draft: Config,
+    original: Config, // what `draft` was when the window opened
+    error: Option<String>, // why the draft isn't applied
+    #[cfg(windows)]
+    run_at_startup: bool, // read from the registry when opened
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            draft: Config::default(),
+            original: Config::default(),
+            error: None,
+            #[cfg(windows)]
+            run_at_startup: false,
+        }
+    }
+
+    /// Opens the window, editing a copy of `config`.
+    pub fn open(&mut self, config: &Config) {
+        if !self.open {
+            self.draft = config.clone();
+            self.original = config.clone();
+            self.error = None;
+
+            #[cfg(windows)]
+            {
+                self.run_at_startup = crate::win32::startup::is_enabled();
+            }
+        }
+
+        self.open = true;
+    }
+
+    pub fn render(&mut self, ui: &Ui) -> Option<SettingsChange> {
+        if !self.open {
+            return None;
+        }
+
+        let mut edited = false;
+        let mut save = false;
+        let draft = &mut self.draft;
+
+        #[cfg(windows)]
+        let mut startup_toggled = false;
+        #[cfg(windows)]
+        let run_at_startup = &mut self.run_at_startup;
+
+        ui.window("Settings")
+            .size(SETTINGS_SIZE, Condition::FirstUseEver)
+            .collapsible(false)
+            .position_pivot([0.5f32, 0.5f32])
+            .position(ui.io().display_size.map(|size| size / 2f32), Condition::Appearing)
+            .opened(&mut self.open)
+            .build(|| {
+                // sliders and text fields apply once
+                // released rather than on every change,
+                // as fonts and wallpapers are slow to load
+                let mut item_edited = || edited |= ui.is_item_deactivated_after_edit();
+
+                // not part of the config, so it's applied right away
+                #[cfg(windows)]
+                {
+                    startup_toggled = ui.checkbox(tr("Start with Windows"), run_at_startup);
+                    ui.separator();
+                }
+
+                ui.text_disabled(tr("Font"));
+
+                ui.slider(tr("Size"), 8f64, 48f64, &mut draft.font_size);
+                item_edited();
+
+                let mut embedded = draft.font_path.is_none();
+
+                if ui.checkbox(tr("Embedded font"), &mut embedded) {
+                    draft.font_path = if embedded { None } else { Some(String::new()) };
+                }
+                item_edited();
+
+                if let Some(path) = &mut draft.font_path {
+                    ui.input_text(tr("Font path"), path).build();
+                    item_edited();
+                }
+
+                ui.separator();
+                ui.text_disabled(tr("Clock"));
+
+                ui.checkbox(tr("24-hour clock"), &mut draft.use_24h);
+                item_edited();
+
+                if !draft.use_24h {
+                    ui.input_text(tr("Time format"), &mut draft.time_format).build();
+                    item_edited();
+                }
+
+                ui.separator();
+                ui.text_disabled(tr("Taskbar"));
+
+                let mut position = TASKBAR_POSITIONS
+                    .iter()
+                    .position(|position| *position == draft.taskbar_position)
+                    .unwrap_or_default();
+
+                if ui.combo_simple_string(tr("Position"), &mut position, &[tr("Top"), tr("Bottom"), tr("Left"), tr("Right")]) {
+                    draft.taskbar_position = TASKBAR_POSITIONS[position];
+                }
+                item_edited();
+
+                ui.slider(tr("Height"), 20f32, 200f32, &mut draft.taskbar_height);
+                item_edited();
+
+                ui.slider(tr("Opacity"), 0f32, 1f32, &mut draft.taskbar_opacity);
+                item_edited();
+
+                let mut accent = draft.accent_color.is_some();
+
+                if ui.checkbox(tr("Custom accent color"), &mut accent) {
+                    draft.accent_color = accent.then_some(Color([0.26, 0.59, 0.98, 1.0])); // imgui's blue
+                }
+                item_edited();
+
+                if let Some(Color(color)) = &mut draft.accent_color {
+                    ui.color_edit4(tr("Accent color"), color);
+                    item_edited();
+                }
+
+                ui.separator();
+                ui.text_disabled(tr("Wallpaper"));
+
+                let mut shader = draft.wallpaper_path.is_none();
+
+                if ui.checkbox(tr("Background shader"), &mut shader) {
+                    draft.wallpaper_path = if shader { None } else { Some(String::new()) };
+                }
+                item_edited();
+
+                if let Some(path) = &mut draft.wallpaper_path {
+                    ui.input_text(tr("Wallpaper path"), path).build();
+                    item_edited();
+                }
+
+                ui.separator();
+
+                if let Some(error) = &self.error {
+                    ui.text_colored(LOG_ERROR_COLOR, error);
+                }
+
+                save = ui.button(tr("Save"));
+            });
+
+        #[cfg(windows)]
+        if startup_toggled {
+            return Some(SettingsChange::RunAtStartup(self.run_at_startup));
+        }
+
+        if !edited && !save {
+            return None;
+        }
+
+        // invalid drafts stay in the window
+        // until they're fixed, unapplied
+        if let Err(e) = validate(&self.draft) {
+            self.error = Some(format!("{e:#}"));
+            return None;
+        }
+
+        self.error = None;
+
+        if save {
+            Some(SettingsChange::Save { draft: self.draft.clone(), original: self.original.clone() })
+        } else {
+            Some(SettingsChange::Apply(self.draft.clone()))
+        }
+    }
+}
+
+fn validate(config: &Config) -> Result<()> {
+    for (name, path) in [("Font", &config.font_path), ("Wallpaper", &config.wallpaper_path)] {
+        let Some(path) = path else {
+            continue;
+        };
+
+        if path.trim().is_empty() {
+            bail!("{name} path can't be empty");
+        }
+
+        if !resolve_path(path)?.is_file() {
+            bail!("{name} '{path}' doesn't exist");
+        }
+    }
+
+    if config.time_format.trim().is_empty() {
+        bail!("Time format can't be empty");
+    }
+
+    // saved, a bad format would only be
+    // noticed on the next launch
+    for (name, format) in [("Time", config.time_format()), ("Date", &config.date_format)] {
+        if parse_format(format, config.locale()).is_err() {
+            bail!("{name} format '{format}' is invalid");
+        }
+    }
+
+    Ok(())
+}
+
+/// Copies the options this window edits from `draft` to `config`, but only
+/// those changed from `original`, so values the running config got from the
+/// script or a command (e.g. `send set-wallpaper`) aren't saved with them.
+pub fn copy_edited(original: &Config, draft: &Config, config: &mut Config) {
+    macro_rules! copy {
+        ($($field:ident),*) => {
+            $(
+                if draft.$field != original.$field {
+                    config.$field = draft.$field.clone();
+                }
+            )*
+        };
+    }
+
+    copy!(
+        font_size,
+        font_path,
+        use_24h,
+        time_format,
+        taskbar_position,
+        taskbar_height,
+        taskbar_opacity,
+        accent_color,
+        wallpaper_path
+    );
+}
@@ -0,0 +1,104 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+use anyhow::{anyhow, Context, Result};
+use image::RgbaImage;
+
+const IMAGE_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+
+/// Decodes an image on a background thread, so large files don't stall a frame.
+fn preload(path: PathBuf) -> Receiver<Result<RgbaImage>> {
+    let (tx, rx) = channel();
+
+    thread::spawn(move || {
+        let image = image::open(&path)
+            .map(|image| image.to_rgba8())
+            .with_context(|| format!("Failed to decode wallpaper '{}'", path.display()));
+
+        // the slideshow was stopped
+        let _ = tx.send(image);
+    });
+
+    rx
+}
+
+/// Cycles the wallpaper through the images in a directory.
+pub struct Slideshow {
+    images: Vec<PathBuf>, // sorted by name
+    index: usize, // of the image shown
+    interval: Duration,
+    since_change: Duration,
+    next: Option<(usize, Receiver<Result<RgbaImage>>)>, // index and the image being preloaded
+}
+
+impl Slideshow {
+    /// Lists the images in `directory`, which may be none of them.
+    pub fn new(directory: &Path, interval: Duration) -> Result<Self> {
+        let mut images: Vec<PathBuf> = fs::read_dir(directory)
+            .with_context(|| format!("Failed to read slideshow directory '{}'", directory.display()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .and_then(|extension| extension.to_str())
+                    .is_some_and(|extension| IMAGE_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+            })
+            .collect();
+
+        images.sort();
+
+        let mut slideshow = Self {
+            images,
+            index: 0,
+            interval,
+            since_change: Duration::ZERO,
+            next: None,
+        };
+
+        slideshow.preload_next();
+
+        Ok(slideshow)
+    }
+
+    /// The image to show first, `None` without any images.
+    pub fn current(&self) -> Option<&Path> {
+        self.images.get(self.index).map(PathBuf::as_path)
+    }
+
+    fn preload_next(&mut self) {
+        // a single image never changes
+        if self.images.len() < 2 {
+            return;
+        }
+
+        let index = (self.index + 1) % self.images.len();
+        self.next = Some((index, preload(self.images[index].clone())));
+    }
+
+    /// Returns the next image once it's time to show it and it's decoded.
+    pub fn update(&mut self, dt: Duration) -> Option<RgbaImage> {
+        self.since_change += dt;
+
+        if self.since_change < self.interval {
+            return None;
+        }
+
+        let (index, rx) = self.next.as_ref()?;
+
+        let image = match rx.try_recv() {
+            Ok(image) => image,
+            Err(TryRecvError::Empty) => return None, // still decoding, shown once it's ready
+            Err(TryRecvError::Disconnected) => Err(anyhow!("Failed to decode wallpaper, the decoder stopped")),
+        };
+
+        self.index = *index;
+        self.since_change = Duration::ZERO;
+        self.preload_next();
+
+        // broken images are skipped
+        image
+            .inspect_err(|e| log::warn!("{e:?}"))
+            .ok()
+    }
+}
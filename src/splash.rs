@@ -0,0 +1,77 @@
+use std::rc::Rc;
+use anyhow::{anyhow, Context, Result};
+use glutin::context::PossiblyCurrentContext as OpenGlContext;
+use glutin::surface::{GlSurface, Surface, WindowSurface};
+use imgui_glow_renderer::glow;
+use imgui_glow_renderer::glow::{HasContext, NativeProgram, NativeUniformLocation, NativeVertexArray};
+use winit::dpi::PhysicalSize;
+use crate::consts::*;
+use crate::gl::Gl;
+use crate::platform::load_gl;
+use crate::renderer::{compile_program, detect_glsl_version};
+
+/// A ring with a progress bar below it, shown as soon as a monitor's
+/// context is current and until the rest of the desktop is ready.
+///
+/// It only needs its own shader, so it's up
+/// before fonts, imgui and the renderer load.
+pub struct Splash {
+    gl: Gl,
+    program: NativeProgram,
+    vertex_array: NativeVertexArray, // empty, the quad comes from `gl_VertexID`
+    resolution: Option<NativeUniformLocation>,
+    progress: Option<NativeUniformLocation>,
+}
+
+impl Splash {
+    pub fn new(opengl: &OpenGlContext) -> Result<Self> {
+        let context = Rc::new(load_gl(opengl));
+        let gl = Gl::new(&context);
+
+        let program = compile_program(&context, &detect_glsl_version(&context), BLUR_VERTEX_SHADER_SOURCE, SPLASH_FRAGMENT_SHADER_SOURCE)
+            .context("Failed to compile splash shaders")?;
+
+        // no safe way to achieve this
+        let vertex_array = unsafe { context.create_vertex_array() }.map_err(|_| {
+            gl.delete_program(program);
+            anyhow!("Failed to create vertex array")
+        })?;
+
+        Ok(
+            Self {
+                resolution: gl.uniform_location(program, "u_resolution"),
+                progress: gl.uniform_location(program, "u_progress"),
+                gl,
+                program,
+                vertex_array,
+            }
+        )
+    }
+
+    /// Draws and presents the splash with its bar `progress` (0 to 1) full.
+    pub fn show(&self, opengl: &OpenGlContext, surface: &Surface<WindowSurface>, size: PhysicalSize<u32>, progress: f32) -> Result<()> {
+        self.gl.viewport(0, 0, size.width as i32, size.height as i32)?;
+        self.gl.set_blend(None);
+
+        self.gl.bind_vertex_array(Some(self.vertex_array));
+        self.gl.use_program(Some(self.program));
+        self.gl.uniform_2_f32(self.resolution.as_ref(), [size.width as f32, size.height as f32]);
+        self.gl.uniform_1_f32(self.progress.as_ref(), progress.clamp(0f32, 1f32));
+        self.gl.draw_arrays(glow::TRIANGLE_STRIP, 4);
+
+        self.gl.set_blend(Some((glow::SRC_ALPHA, glow::ONE_MINUS_SRC_ALPHA)));
+        self.gl.bind_vertex_array(None);
+        self.gl.use_program(None);
+
+        self.gl.check("drawing the splash")?;
+
+        surface.swap_buffers(opengl).context("Failed to swap surface buffers")
+    }
+}
+
+impl Drop for Splash {
+    fn drop(&mut self) {
+        self.gl.delete_program(self.program);
+        self.gl.delete_vertex_array(self.vertex_array);
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use anyhow::{bail, Context, Result};
+use imgui::{Style, StyleColor};
+use serde::Deserialize;
+use crate::config::{Color, Config};
+use crate::utils::resolve_path;
+
+/// An imgui style loaded from a TOML file, on top of a built-in preset.
+///
+/// Sizes left out keep imgui's defaults, colors left out keep the preset's.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct ThemeFile {
+    preset: Option<String>,
+    alpha: Option<f32>,
+    window_padding: Option<[f32; 2]>,
+    window_rounding: Option<f32>,
+    window_border_size: Option<f32>,
+    child_rounding: Option<f32>,
+    popup_rounding: Option<f32>,
+    frame_padding: Option<[f32; 2]>,
+    frame_rounding: Option<f32>,
+    frame_border_size: Option<f32>,
+    item_spacing: Option<[f32; 2]>,
+    item_inner_spacing: Option<[f32; 2]>,
+    scrollbar_size: Option<f32>,
+    scrollbar_rounding: Option<f32>,
+    grab_rounding: Option<f32>,
+    tab_rounding: Option<f32>,
+    colors: HashMap<String, Color>, // e.g. `window_bg = "#202020F0"`
+}
+
+fn apply_preset(style: &mut Style, name: &str) -> Result<()> {
+    match name {
+        "dark" => style.use_dark_colors(),
+        "light" => style.use_light_colors(),
+        "classic" => style.use_classic_colors(),
+        _ => bail!("Unknown theme preset '{name}', expected 'dark', 'light' or 'classic'"),
+    };
+
+    Ok(())
+}
+
+/// The preset matching the system's light/dark mode.
+fn system_preset() -> Option<&'static str> {
+    #[cfg(windows)]
+    return crate::win32::theme::apps_use_light_theme().map(|light| if light { "light" } else { "dark" });
+
+    #[cfg(not(windows))]
+    None
+}
+
+/// The theme to apply, the system's when following it and it can be read.
+pub fn theme_name(config: &Config) -> String {
+    match system_preset() {
+        Some(preset) if config.system_theme => preset.to_string(),
+        _ => config.theme.clone(),
+    }
+}
+
+/// The configured accent color, or the system's when following the system theme.
+pub fn accent_color(config: &Config) -> Option<Color> {
+    if config.accent_color.is_some() || !config.system_theme {
+        return config.accent_color;
+    }
+
+    #[cfg(windows)]
+    return crate::win32::theme::accent_color();
+
+    #[cfg(not(windows))]
+    None
+}
+
+/// `WindowBg` as written in theme files, `window_bg`.
+fn color_key(color: StyleColor) -> String {
+    let mut key = String::new();
+
+    for (index, character) in format!("{color:?}").chars().enumerate() {
+        if character.is_uppercase() && index > 0 {
+            key.push('_');
+        }
+
+        key.push(character.to_ascii_lowercase());
+    }
+
+    key
+}
+
+/// Resets `style` to `default` and applies `theme`, either a preset name or the path of a theme file.
+pub fn apply_theme(style: &mut Style, default: &Style, theme: &str) -> Result<()> {
+    *style = *default;
+
+    if apply_preset(style, theme).is_ok() {
+        return Ok(());
+    }
+
+    let path = resolve_path(theme)?;
+    let source = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read theme '{}'", path.display()))?;
+    let file: ThemeFile = toml::from_str(&source)
+        .with_context(|| format!("Failed to parse theme '{}'", path.display()))?;
+
+    apply_preset(style, file.preset.as_deref().unwrap_or("dark"))?;
+
+    let sizes = [
+        (&mut style.alpha, file.alpha),
+        (&mut style.window_rounding, file.window_rounding),
+        (&mut style.window_border_size, file.window_border_size),
+        (&mut style.child_rounding, file.child_rounding),
+        (&mut style.popup_rounding, file.popup_rounding),
+        (&mut style.frame_rounding, file.frame_rounding),
+        (&mut style.frame_border_size, file.frame_border_size),
+        (&mut style.scrollbar_size, file.scrollbar_size),
+        (&mut style.scrollbar_rounding, file.scrollbar_rounding),
+        (&mut style.grab_rounding, file.grab_rounding),
+        (&mut style.tab_rounding, file.tab_rounding),
+    ];
+
+    for (size, value) in sizes {
+        if let Some(value) = value {
+            *size = value;
+        }
+    }
+
+    let spacings = [
+        (&mut style.window_padding, file.window_padding),
+        (&mut style.frame_padding, file.frame_padding),
+        (&mut style.item_spacing, file.item_spacing),
+        (&mut style.item_inner_spacing, file.item_inner_spacing),
+    ];
+
+    for (spacing, value) in spacings {
+        if let Some(value) = value {
+            *spacing = value;
+        }
+    }
+
+    for (key, Color(color)) in file.colors {
+        match StyleColor::VARIANTS.into_iter().find(|variant| color_key(*variant) == key) {
+            Some(variant) => style[variant] = color,
+
+            // a typo shouldn't
+            // lose the whole theme
+            None => log::warn!("Unknown color '{key}' in theme '{}'", path.display()),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use std::cell::Cell;
+use anyhow::{Context, Result};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, Locale};
+
+/// Where widgets get the current time from, so it can be pinned (e.g. around midnight or a DST change).
+pub trait Clock {
+    fn now(&self) -> DateTime<Local>;
+}
+
+/// The system's local time.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// A time that only changes when set.
+pub struct FixedClock {
+    time: Cell<DateTime<Local>>,
+}
+
+impl FixedClock {
+    pub fn new(time: DateTime<Local>) -> Self {
+        Self { time: Cell::new(time) }
+    }
+
+    pub fn set(&self, time: DateTime<Local>) {
+        self.time.set(time);
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Local> {
+        self.time.get()
+    }
+}
+
+/// Parses a strftime `format` once, so a typo (e.g. a trailing
+/// `%` or `%Q`) is an error here instead of a panic when formatting.
+pub fn parse_format(format: &str, locale: Locale) -> Result<Vec<Item<'static>>> {
+    StrftimeItems::new_with_locale(format, locale)
+        .parse_to_owned()
+        .with_context(|| format!("Invalid time format '{format}'"))
+}
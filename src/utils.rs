@@ -0,0 +1,29 @@
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+use native_dialog::{DialogBuilder, MessageLevel};
+
+/// Resolves `path` relative to the directory containing the executable,
+/// absolute paths are returned unchanged.
+pub fn resolve_path<P: AsRef<Path>>(path: P) -> Result<PathBuf> {
+    let path = path.as_ref();
+
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+
+    let executable = std::env::current_exe().context("Failed to get executable path")?;
+    let directory = executable.parent().context("Executable path has no parent directory")?;
+
+    Ok(directory.join(path))
+}
+
+/// Asks before doing something that can't be undone, `true` when confirmed.
+pub fn confirm(title: &str, text: &str) -> Result<bool> {
+    DialogBuilder::message()
+        .set_title(title)
+        .set_text(text)
+        .set_level(MessageLevel::Warning)
+        .confirm()
+        .show()
+        .context("Failed to show confirmation dialog")
+}
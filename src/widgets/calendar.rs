@@ -0,0 +1,86 @@
+use std::rc::Rc;
+use chrono::{Datelike, Months, NaiveDate};
+use imgui::{Direction, Ui};
+use crate::consts::*;
+use crate::time::Clock;
+
+/// Monthly calendar shown when the taskbar clock is clicked.
+pub struct Calendar {
+    clock: Rc<dyn Clock>,
+    month: NaiveDate, // first day of the displayed month
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+impl Calendar {
+    pub fn new(clock: Rc<dyn Clock>) -> Self {
+        Self {
+            month: first_of_month(clock.now().date_naive()),
+            clock,
+        }
+    }
+
+    /// Opens the popup at the current month, anchoring its bottom-right corner at `anchor`.
+    pub fn open(&mut self, ui: &Ui, anchor: [f32; 2]) {
+        self.month = first_of_month(self.clock.now().date_naive());
+
+        // imgui-rs has no safe way to
+        // position the next window
+        unsafe {
+            imgui::sys::igSetNextWindowPos(
+                imgui::sys::ImVec2::new(anchor[0], anchor[1]),
+                imgui::sys::ImGuiCond_Always as i32,
+                imgui::sys::ImVec2::new(1f32, 1f32)
+            );
+        }
+
+        ui.open_popup(CALENDAR_POPUP_ID);
+    }
+
+    pub fn render(&mut self, ui: &Ui) {
+        ui.popup(CALENDAR_POPUP_ID, || {
+            let today = self.clock.now().date_naive();
+
+            if ui.arrow_button("_calendar_previous", Direction::Left) {
+                self.month = self.month - Months::new(1);
+            }
+
+            ui.same_line();
+            ui.text(self.month.format("%B %Y").to_string());
+            ui.same_line();
+
+            if ui.arrow_button("_calendar_next", Direction::Right) {
+                self.month = self.month + Months::new(1);
+            }
+
+            let Some(_table) = ui.begin_table("_calendar_days", 7) else {
+                return;
+            };
+
+            for weekday in ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"] {
+                ui.table_next_column();
+                ui.text_disabled(weekday);
+            }
+
+            // pad the first week up to the weekday the month starts on
+            for _ in 0..self.month.weekday().num_days_from_sunday() {
+                ui.table_next_column();
+            }
+
+            // chrono handles month lengths and leap years
+            let next_month = self.month + Months::new(1);
+
+            for day in self.month.iter_days().take_while(|day| *day < next_month) {
+                ui.table_next_column();
+
+                if day == today {
+                    ui.text_colored(CALENDAR_TODAY_COLOR, day.day().to_string());
+                } else {
+                    ui.text(day.day().to_string());
+                }
+            }
+        });
+    }
+}
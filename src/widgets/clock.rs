@@ -0,0 +1,238 @@
+use std::fmt::{Display, Write};
+use std::rc::Rc;
+use chrono::format::Item;
+use chrono::{DateTime, Locale, TimeZone};
+use imgui::Ui;
+use crate::config::Config;
+use crate::consts::*;
+use crate::fonts::request_glyphs;
+use crate::time;
+use crate::widgets::Widget;
+use crate::widgets::calendar::Calendar;
+
+/// Time and date, opening a calendar when clicked.
+pub struct Clock {
+    clock: Rc<dyn time::Clock>,
+    time_format: Vec<Item<'static>>,
+    date_format: Vec<Item<'static>>,
+    locale: Locale,
+    calendar: Calendar,
+    second: Option<i64>, // the unix timestamp `time` and `date` were formatted at
+    time: String,
+    date: String,
+}
+
+impl Clock {
+    pub fn new(config: &Config, clock: Rc<dyn time::Clock>) -> Self {
+        Self {
+            calendar: Calendar::new(clock.clone()),
+            clock,
+            time_format: parse_or(config.time_format(), config.locale(), TIME_FORMAT),
+            date_format: parse_or(&config.date_format, config.locale(), DATE_FORMAT),
+            locale: config.locale(),
+            second: None,
+            time: String::new(),
+            date: String::new(),
+        }
+    }
+
+    fn refresh(&mut self) {
+        self.refresh_at(&self.clock.now());
+    }
+
+    /// Formats the time and date again if `now`'s second changed. Any time
+    /// zone is taken, so tests can pass explicit offsets around DST changes.
+    fn refresh_at<Tz: TimeZone>(&mut self, now: &DateTime<Tz>) where Tz::Offset: Display {
+        // formatting allocates, and the text
+        // only changes once a second at most
+        if self.second != Some(now.timestamp()) {
+            self.second = Some(now.timestamp());
+            self.time = format(now, &self.time_format, self.locale);
+            self.date = format(now, &self.date_format, self.locale);
+        }
+    }
+}
+
+/// `format` parsed, or `fallback` when it's invalid.
+fn parse_or(format: &str, locale: Locale, fallback: &str) -> Vec<Item<'static>> {
+    time::parse_format(format, locale).unwrap_or_else(|e| {
+        log::warn!("{e:?}");
+        time::parse_format(fallback, locale).unwrap_or_default()
+    })
+}
+
+/// Empty when formatting fails, e.g. on a
+/// field the locale has no data for.
+fn format<Tz: TimeZone>(now: &DateTime<Tz>, items: &[Item], locale: Locale) -> String where Tz::Offset: Display {
+    let mut text = String::new();
+
+    if write!(text, "{}", now.format_localized_with_items(items.iter(), locale)).is_err() {
+        log::debug!("Failed to format the time");
+        text.clear();
+    }
+
+    text
+}
+
+impl Widget for Clock {
+    fn essential(&self) -> bool {
+        true
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        self.refresh();
+
+        // flush right and centered vertically, both
+        // measured from the font so it follows the
+        // configured size and monitor scale
+        let padding = ui.clone_style().window_padding;
+        let window_size = ui.window_size();
+        let right = ui.current_column_offset() + ui.current_column_width() - padding[0];
+        let line_height = ui.text_line_height();
+        let mut y = (window_size[1] - line_height * 2f32) / 2f32;
+        let mut clicked = false;
+
+        for line in [&self.time, &self.date] {
+            let width = ui.calc_text_size(line)[0];
+
+            request_glyphs(ui, line);
+            ui.set_cursor_pos([right - width, y]);
+            ui.text(line);
+            clicked |= ui.is_item_clicked();
+
+            y += line_height;
+        }
+
+        if clicked {
+            let window_pos = ui.window_pos();
+            self.calendar.open(ui, [window_pos[0] + right, window_pos[1]]);
+        }
+
+        self.calendar.render(ui);
+    }
+
+    fn apply_config(&mut self, config: &Config) {
+        self.locale = config.locale();
+
+        // the last good format is kept
+        match time::parse_format(config.time_format(), self.locale) {
+            Ok(items) => self.time_format = items,
+            Err(e) => log::warn!("{e:?}"),
+        }
+
+        match time::parse_format(&config.date_format, self.locale) {
+            Ok(items) => self.date_format = items,
+            Err(e) => log::warn!("{e:?}"),
+        }
+
+        self.second = None; // reformatted with the new settings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use chrono::{DateTime, FixedOffset, Local, Locale, TimeZone};
+    use crate::config::Config;
+    use crate::consts::*;
+    use crate::time::{parse_format, FixedClock};
+    use crate::widgets::Widget;
+    use super::Clock;
+
+    fn clock() -> Clock {
+        let config = Config {
+            time_format: "%H:%M:%S".to_string(),
+            use_24h: false,
+            date_format: "%a %d.%m.%Y".to_string(),
+            locale: Some("en_US".to_string()),
+            ..Config::default()
+        };
+
+        // only `refresh_at` is called, so it's never read
+        Clock::new(&config, Rc::new(FixedClock::new(Local::now())))
+    }
+
+    /// A time in Central European time, `hours` ahead of UTC.
+    fn cet(hours: i32, year: i32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(hours * 3600)
+            .unwrap()
+            .with_ymd_and_hms(year, month, day, hour, minute, second)
+            .unwrap()
+    }
+
+    #[test]
+    fn formats_across_midnight() {
+        let mut clock = clock();
+
+        clock.refresh_at(&cet(1, 2026, 1, 14, 23, 59, 59));
+
+        assert_eq!(clock.time, "23:59:59");
+        assert_eq!(clock.date, "Wed 14.01.2026");
+
+        clock.refresh_at(&cet(1, 2026, 1, 15, 0, 0, 0));
+
+        assert_eq!(clock.time, "00:00:00");
+        assert_eq!(clock.date, "Thu 15.01.2026");
+    }
+
+    #[test]
+    fn formats_across_daylight_saving_time() {
+        let mut clock = clock();
+
+        // clocks go forward from 02:00 CET to 03:00 CEST, a second later
+        let before = cet(1, 2026, 3, 29, 1, 59, 59);
+        let after = cet(2, 2026, 3, 29, 3, 0, 0);
+        assert_eq!(after.timestamp() - before.timestamp(), 1);
+
+        clock.refresh_at(&before);
+        assert_eq!(clock.time, "01:59:59");
+
+        clock.refresh_at(&after);
+        assert_eq!(clock.time, "03:00:00");
+        assert_eq!(clock.date, "Sun 29.03.2026");
+
+        // and back from 03:00 CEST to 02:00 CET, the hour repeating
+        let before = cet(2, 2026, 10, 25, 2, 59, 59);
+        let after = cet(1, 2026, 10, 25, 2, 0, 0);
+        assert_eq!(after.timestamp() - before.timestamp(), 1);
+
+        clock.refresh_at(&before);
+        assert_eq!(clock.time, "02:59:59");
+
+        clock.refresh_at(&after);
+        assert_eq!(clock.time, "02:00:00");
+        assert_eq!(clock.date, "Sun 25.10.2026");
+    }
+
+    #[test]
+    fn keeps_the_last_good_format() {
+        let bad = Config {
+            time_format: "%H:%M %".to_string(),
+            use_24h: false,
+            date_format: "%Q".to_string(),
+            locale: Some("en_US".to_string()),
+            ..Config::default()
+        };
+
+        assert!(parse_format(&bad.time_format, Locale::en_US).is_err());
+        assert!(parse_format(&bad.date_format, Locale::en_US).is_err());
+
+        // the defaults are used at startup
+        let mut clock = Clock::new(&bad, Rc::new(FixedClock::new(Local::now())));
+
+        assert_eq!(clock.time_format, parse_format(TIME_FORMAT, Locale::en_US).unwrap());
+        assert_eq!(clock.date_format, parse_format(DATE_FORMAT, Locale::en_US).unwrap());
+
+        let good = Config {
+            time_format: "%H:%M:%S".to_string(),
+            ..bad.clone()
+        };
+
+        clock.apply_config(&good);
+        clock.apply_config(&bad);
+        clock.refresh();
+
+        assert_eq!(clock.time_format, parse_format("%H:%M:%S", Locale::en_US).unwrap());
+        assert_eq!(clock.date_format, parse_format(DATE_FORMAT, Locale::en_US).unwrap());
+    }
+}
@@ -0,0 +1,105 @@
+use std::time::Duration;
+use imgui::Ui;
+use sysinfo::Disks as SystemDisks;
+use crate::config::Config;
+use crate::consts::*;
+use crate::widgets::Widget;
+
+struct DiskUsage {
+    mount_point: String,
+    available: u64,
+    total: u64,
+}
+
+impl DiskUsage {
+    fn used_fraction(&self) -> f32 {
+        if self.total == 0 { 0f32 } else { 1f32 - self.available as f32 / self.total as f32 }
+    }
+}
+
+/// Free space of the configured drives, sampled at most once per `DISKS_INTERVAL`.
+pub struct Disks {
+    disks: SystemDisks,
+    filter: Vec<String>, // mount points to show, all of them when empty
+    usage: Vec<DiskUsage>,
+    since_refresh: Option<Duration>, // `None` until the first refresh
+}
+
+impl Disks {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            disks: SystemDisks::new(),
+            filter: config.disks.clone(),
+            usage: Vec::new(),
+            since_refresh: None,
+        }
+    }
+}
+
+impl Widget for Disks {
+    fn update(&mut self, dt: Duration) {
+        if let Some(since_refresh) = self.since_refresh.as_mut() {
+            *since_refresh += dt;
+
+            if *since_refresh < DISKS_INTERVAL {
+                return;
+            }
+        }
+
+        // also drops removed drives, e.g. a USB stick
+        self.disks.refresh(true);
+
+        self.usage = self.disks
+            .iter()
+            .map(|disk| DiskUsage {
+                mount_point: disk.mount_point().to_string_lossy().into_owned(),
+                available: disk.available_space(),
+                total: disk.total_space(),
+            })
+            .filter(|disk| self.filter.is_empty() || self.filter.contains(&disk.mount_point))
+            .collect();
+
+        self.since_refresh = Some(Duration::ZERO);
+    }
+
+    fn visible(&self) -> bool {
+        !self.usage.is_empty()
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        const GIB: f64 = 1024f64 * 1024f64 * 1024f64;
+
+        // the fullest drive is the one worth seeing
+        let Some(fullest) = self.usage.iter().max_by(|a, b| a.used_fraction().total_cmp(&b.used_fraction())) else {
+            return;
+        };
+
+        let label = format!("{} {:.0} GB free##_disks", fullest.mount_point, fullest.available as f64 / GIB);
+
+        if ui.button(label) {
+            ui.open_popup(DISKS_POPUP_ID);
+        }
+
+        ui.popup(DISKS_POPUP_ID, || {
+            for disk in &self.usage {
+                ui.progress_bar(disk.used_fraction())
+                    .size([DISKS_BAR_WIDTH, ui.text_line_height()])
+                    .build();
+                ui.same_line();
+                ui.text(format!(
+                    "{} {:.1}/{:.1} GB free",
+                    disk.mount_point,
+                    disk.available as f64 / GIB,
+                    disk.total as f64 / GIB
+                ));
+            }
+        });
+    }
+
+    fn apply_config(&mut self, config: &Config) {
+        if config.disks != self.filter {
+            self.filter = config.disks.clone();
+            self.since_refresh = None; // refiltered on the next frame
+        }
+    }
+}
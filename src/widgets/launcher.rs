@@ -0,0 +1,119 @@
+use std::process::Command;
+use anyhow::{Context, Result};
+use imgui::{Condition, Ui};
+use serde::{Deserialize, Serialize};
+use crate::actions::{Action, POWER_MENU_ACTIONS};
+use crate::config::Config;
+use crate::consts::*;
+use crate::fonts::request_glyphs;
+use crate::i18n::tr;
+
+/// A program listed in the launcher.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct LauncherEntry {
+    pub name: String,
+    pub exec: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl LauncherEntry {
+    pub fn launch(&self) -> Result<()> {
+        Command::new(&self.exec)
+            .args(&self.args)
+            .spawn()
+            .with_context(|| format!("Failed to launch '{}' ({})", self.name, self.exec))?;
+
+        Ok(())
+    }
+}
+
+/// Start menu button and the launcher window it toggles.
+pub struct Launcher {
+    open: bool,
+    filter: String,
+}
+
+impl Launcher {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            filter: String::new(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.filter.clear();
+    }
+
+    pub fn render_button(&mut self, ui: &Ui) {
+        if ui.button(tr(LAUNCHER_BUTTON_LABEL)) {
+            self.toggle();
+        }
+    }
+
+    /// Draws the launcher at `anchor`, `pivot` being the corner placed there
+    /// (e.g. `[0, 1]` for the bottom-left corner), returning the entry chosen this frame.
+    pub fn render_window(&mut self, ui: &Ui, config: &Config, anchor: [f32; 2], pivot: [f32; 2]) -> Option<Action> {
+        if !self.open {
+            return None;
+        }
+
+        let mut chosen = None;
+
+        ui.window("_launcher")
+            .title_bar(false)
+            .resizable(false)
+            .movable(false)
+            .size(LAUNCHER_SIZE, Condition::Always)
+            .position(anchor, Condition::Always)
+            .position_pivot(pivot)
+            .build(|| {
+                if ui.is_window_appearing() {
+                    ui.set_keyboard_focus_here();
+                }
+
+                ui.input_text("##_launcher_filter", &mut self.filter)
+                    .hint(tr("Search"))
+                    .build();
+
+                let filter = self.filter.to_lowercase();
+
+                // leaves a row for the power button
+                ui.child_window("_launcher_entries")
+                    .size([0f32, -ui.frame_height_with_spacing()])
+                    .build(|| {
+                        for (index, entry) in config.launcher.iter().enumerate().filter(|(_, entry)| entry.name.to_lowercase().contains(&filter)) {
+                            request_glyphs(ui, &entry.name);
+
+                            if ui.selectable(&entry.name) {
+                                chosen = Some(Action::Launch(index));
+                            }
+                        }
+                    });
+
+                if ui.button(tr("Power")) {
+                    ui.open_popup(POWER_MENU_ID);
+                }
+
+                ui.popup(POWER_MENU_ID, || {
+                    for action in POWER_MENU_ACTIONS {
+                        if ui.menu_item(action.name(config)) {
+                            chosen = Some(*action);
+                        }
+                    }
+                });
+            });
+
+        if chosen.is_some() {
+            self.open = false;
+        }
+
+        chosen
+    }
+}
@@ -0,0 +1,38 @@
+pub mod calendar;
+pub mod clock;
+pub mod disks;
+pub mod launcher;
+pub mod network;
+pub mod system;
+pub mod system_info;
+pub mod timer;
+pub mod weather;
+
+use std::time::Duration;
+use imgui::Ui;
+use crate::config::Config;
+
+/// Something drawn in its own taskbar column.
+pub trait Widget {
+    /// Draws the widget, the cursor is at the start of its column.
+    fn render(&mut self, ui: &Ui);
+
+    /// Called every frame before rendering with the time since the last frame.
+    fn update(&mut self, _dt: Duration) { }
+
+    /// Hidden widgets don't get a column, e.g. when their hardware is missing.
+    fn visible(&self) -> bool {
+        true
+    }
+
+    /// Essential widgets stay fully visible in do not disturb mode, the rest are dimmed.
+    fn essential(&self) -> bool {
+        false
+    }
+
+    /// Adds items to the menu opened by right-clicking the desktop.
+    fn desktop_menu(&mut self, _ui: &Ui) { }
+
+    /// Called when the configuration is reloaded.
+    fn apply_config(&mut self, _config: &Config) { }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use imgui::Ui;
+use sysinfo::Networks;
+use crate::consts::*;
+use crate::fonts::has_glyph;
+use crate::widgets::Widget;
+
+struct Interface {
+    name: String,
+    received: u64, // bytes per second
+    transmitted: u64,
+    connected: bool,
+}
+
+/// Network throughput, sampled at most once per `NETWORK_INTERVAL`.
+pub struct Network {
+    networks: Networks,
+    totals: HashMap<String, (u64, u64)>, // received and transmitted bytes at the last refresh
+    interfaces: Vec<Interface>,
+    since_refresh: Option<Duration>, // `None` until the first refresh
+}
+
+impl Network {
+    pub fn new() -> Self {
+        Self {
+            networks: Networks::new(),
+            totals: HashMap::new(),
+            interfaces: Vec::new(),
+            since_refresh: None,
+        }
+    }
+}
+
+/// `bytes` with a binary unit, e.g. "1.5 MB".
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+
+    while value >= 1024f64 && unit < UNITS.len() - 1 {
+        value /= 1024f64;
+        unit += 1;
+    }
+
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+impl Widget for Network {
+    fn update(&mut self, dt: Duration) {
+        let elapsed = match self.since_refresh.as_mut() {
+            Some(since_refresh) => {
+                *since_refresh += dt;
+
+                if *since_refresh < NETWORK_INTERVAL {
+                    return;
+                }
+
+                since_refresh.as_secs_f64()
+            }
+
+            None => 0f64,
+        };
+
+        self.networks.refresh(true);
+
+        let mut totals = HashMap::new();
+
+        self.interfaces = self.networks
+            .iter()
+            .map(|(name, data)| {
+                let total = (data.total_received(), data.total_transmitted());
+
+                // interfaces without an earlier sample (including
+                // all of them at first) have no rate to show yet
+                let (received, transmitted) = match self.totals.get(name) {
+                    Some(previous) if elapsed > 0f64 => (
+                        (total.0.saturating_sub(previous.0) as f64 / elapsed) as u64,
+                        (total.1.saturating_sub(previous.1) as f64 / elapsed) as u64,
+                    ),
+
+                    _ => (0, 0),
+                };
+
+                totals.insert(name.clone(), total);
+
+                Interface {
+                    name: name.clone(),
+                    received,
+                    transmitted,
+                    connected: data.ip_networks().iter().any(|network| !network.addr.is_loopback()),
+                }
+            })
+            .collect();
+
+        self.interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+        self.totals = totals;
+        self.since_refresh = Some(Duration::ZERO);
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        let connected = self.interfaces.iter().any(|interface| interface.connected);
+        let received = self.interfaces.iter().map(|interface| interface.received).sum::<u64>();
+        let transmitted = self.interfaces.iter().map(|interface| interface.transmitted).sum::<u64>();
+
+        let status = match (connected, has_glyph(ui, ICON_WIFI)) {
+            (false, _) => "Offline".to_string(),
+            (true, true) => ICON_WIFI.to_string(),
+            (true, false) => "Net".to_string(),
+        };
+
+        let label = format!(
+            "{status} {}/s in {}/s out##_network",
+            format_bytes(received),
+            format_bytes(transmitted)
+        );
+
+        if ui.button(label) {
+            ui.open_popup(NETWORK_POPUP_ID);
+        }
+
+        ui.popup(NETWORK_POPUP_ID, || {
+            for interface in &self.interfaces {
+                let state = if interface.connected { "connected" } else { "disconnected" };
+
+                ui.text(format!(
+                    "{} ({state}): {}/s in, {}/s out",
+                    interface.name,
+                    format_bytes(interface.received),
+                    format_bytes(interface.transmitted)
+                ));
+            }
+        });
+    }
+}
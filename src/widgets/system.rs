@@ -0,0 +1,67 @@
+use std::time::Duration;
+use imgui::Ui;
+use sysinfo::System;
+use crate::consts::*;
+use crate::widgets::Widget;
+
+/// CPU and RAM usage, sampled at most once per `SYSTEM_USAGE_INTERVAL`.
+pub struct SystemUsage {
+    system: System,
+    since_refresh: Option<Duration>, // `None` until the first refresh
+    cpu: f32,
+    used_memory: u64,
+    total_memory: u64,
+}
+
+impl SystemUsage {
+    pub fn new() -> Self {
+        Self {
+            system: System::new(),
+            since_refresh: None,
+            cpu: 0f32,
+            used_memory: 0,
+            total_memory: 0,
+        }
+    }
+}
+
+impl Widget for SystemUsage {
+    fn update(&mut self, dt: Duration) {
+        if let Some(since_refresh) = self.since_refresh.as_mut() {
+            *since_refresh += dt;
+
+            if *since_refresh < SYSTEM_USAGE_INTERVAL {
+                return;
+            }
+        }
+
+        // cpu usage is measured between two
+        // refreshes, so the first reading is 0
+        self.system.refresh_cpu_usage();
+        self.system.refresh_memory();
+
+        self.cpu = self.system.global_cpu_usage();
+        self.used_memory = self.system.used_memory();
+        self.total_memory = self.system.total_memory();
+        self.since_refresh = Some(Duration::ZERO);
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        const GIB: f64 = 1024f64 * 1024f64 * 1024f64;
+
+        let bar_size = [SYSTEM_USAGE_BAR_WIDTH, ui.text_line_height()];
+        let memory = if self.total_memory == 0 { 0f32 } else { self.used_memory as f32 / self.total_memory as f32 };
+
+        ui.progress_bar(self.cpu / 100f32).size(bar_size).build();
+        ui.same_line();
+        ui.text(format!("CPU {:.0}%", self.cpu));
+
+        ui.progress_bar(memory).size(bar_size).build();
+        ui.same_line();
+        ui.text(format!(
+            "RAM {:.1}/{:.1} GB",
+            self.used_memory as f64 / GIB,
+            self.total_memory as f64 / GIB
+        ));
+    }
+}
@@ -0,0 +1,87 @@
+use std::time::Duration;
+use imgui::Ui;
+use sysinfo::{CpuRefreshKind, System};
+use crate::consts::*;
+use crate::widgets::Widget;
+
+/// Uptime with the hostname, and the OS, CPU and RAM in a popup.
+/// Only the uptime changes, so the rest is read once.
+pub struct SystemInfo {
+    host_name: String,
+    os_version: String,
+    cpu_brand: String,
+    physical_cores: Option<usize>,
+    logical_cores: usize,
+    total_memory: u64,
+    uptime: u64, // in seconds
+    since_refresh: Option<Duration>, // `None` until the first refresh
+}
+
+impl SystemInfo {
+    pub fn new() -> Self {
+        let mut system = System::new();
+        system.refresh_cpu_list(CpuRefreshKind::nothing());
+        system.refresh_memory();
+
+        Self {
+            host_name: System::host_name().unwrap_or_else(|| "Unknown host".to_string()),
+            os_version: System::long_os_version().or_else(System::name).unwrap_or_else(|| "Unknown OS".to_string()),
+            cpu_brand: system.cpus().first().map(|cpu| cpu.brand().trim().to_string()).unwrap_or_default(),
+            physical_cores: System::physical_core_count(),
+            logical_cores: system.cpus().len(),
+            total_memory: system.total_memory(),
+            uptime: 0,
+            since_refresh: None,
+        }
+    }
+}
+
+/// e.g. "3d 4:05:06", or "4:05:06" within the first day.
+fn format_uptime(seconds: u64) -> String {
+    let time = format!("{}:{:02}:{:02}", seconds / 3600 % 24, seconds / 60 % 60, seconds % 60);
+
+    match seconds / 86400 {
+        0 => time,
+        days => format!("{days}d {time}"),
+    }
+}
+
+impl Widget for SystemInfo {
+    fn update(&mut self, dt: Duration) {
+        if let Some(since_refresh) = self.since_refresh.as_mut() {
+            *since_refresh += dt;
+
+            if *since_refresh < SYSTEM_INFO_INTERVAL {
+                return;
+            }
+        }
+
+        // read from the OS rather than counted, so
+        // it stays right across sleep and hibernation
+        self.uptime = System::uptime();
+        self.since_refresh = Some(Duration::ZERO);
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        const GIB: f64 = 1024f64 * 1024f64 * 1024f64;
+
+        if ui.button(format!("{} up {}##_system_info", self.host_name, format_uptime(self.uptime))) {
+            ui.open_popup(SYSTEM_INFO_POPUP_ID);
+        }
+
+        ui.popup(SYSTEM_INFO_POPUP_ID, || {
+            ui.text(&self.os_version);
+
+            if !self.cpu_brand.is_empty() {
+                ui.text(&self.cpu_brand);
+            }
+
+            match self.physical_cores {
+                Some(physical) => ui.text(format!("{physical} cores, {} threads", self.logical_cores)),
+                None => ui.text(format!("{} threads", self.logical_cores)),
+            }
+
+            ui.text(format!("{:.1} GB RAM", self.total_memory as f64 / GIB));
+        });
+    }
+}
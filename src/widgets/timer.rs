@@ -0,0 +1,129 @@
+use std::time::Duration;
+use imgui::{StyleColor, Ui};
+use crate::config::Config;
+use crate::consts::*;
+use crate::i18n::tr;
+use crate::widgets::Widget;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Work,
+    Break,
+}
+
+/// Pomodoro timer alternating between work and break intervals.
+pub struct Timer {
+    work: Duration,
+    rest: Duration,
+    phase: Phase,
+    remaining: Duration,
+    running: bool,
+    flash: Duration, // left to flash for after a phase ends
+}
+
+impl Timer {
+    pub fn new(config: &Config) -> Self {
+        let work = Duration::from_secs(config.timer_work_minutes as u64 * 60);
+
+        Self {
+            work,
+            rest: Duration::from_secs(config.timer_break_minutes as u64 * 60),
+            phase: Phase::Work,
+            remaining: work,
+            running: false,
+            flash: Duration::ZERO,
+        }
+    }
+
+    fn length(&self, phase: Phase) -> Duration {
+        match phase {
+            Phase::Work => self.work,
+            Phase::Break => self.rest,
+        }
+    }
+
+    fn next_phase(&mut self) {
+        self.phase = match self.phase {
+            Phase::Work => Phase::Break,
+            Phase::Break => Phase::Work,
+        };
+        self.reset();
+    }
+
+    fn reset(&mut self) {
+        self.remaining = self.length(self.phase);
+        self.running = false;
+    }
+}
+
+impl Widget for Timer {
+    fn update(&mut self, dt: Duration) {
+        self.flash = self.flash.saturating_sub(dt);
+
+        if !self.running {
+            return;
+        }
+
+        self.remaining = self.remaining.saturating_sub(dt);
+
+        if !self.remaining.is_zero() {
+            return;
+        }
+
+        // the next phase waits to be started
+        self.next_phase();
+        self.flash = TIMER_FLASH_DURATION;
+
+        log::info!("Timer elapsed");
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        let seconds = self.remaining.as_secs_f32().ceil() as u64;
+        let phase = match self.phase {
+            Phase::Work => tr("Work"),
+            Phase::Break => tr("Break"),
+        };
+
+        // alternates every second
+        let flashing = self.flash.as_secs() % 2 == 1;
+        let color = flashing.then(|| ui.push_style_color(StyleColor::Text, TIMER_FLASH_COLOR));
+
+        if ui.button(format!("{phase} {:02}:{:02}##_timer", seconds / 60, seconds % 60)) {
+            ui.open_popup(TIMER_POPUP_ID);
+        }
+
+        drop(color);
+
+        ui.popup(TIMER_POPUP_ID, || {
+            if ui.button(if self.running { tr("Pause") } else { tr("Start") }) {
+                self.running = !self.running;
+                self.flash = Duration::ZERO;
+            }
+
+            ui.same_line();
+
+            if ui.button(tr("Reset")) {
+                self.reset();
+            }
+
+            ui.same_line();
+
+            if ui.button(tr("Skip")) {
+                self.next_phase();
+            }
+        });
+    }
+
+    fn apply_config(&mut self, config: &Config) {
+        let previous = self.length(self.phase);
+
+        self.work = Duration::from_secs(config.timer_work_minutes as u64 * 60);
+        self.rest = Duration::from_secs(config.timer_break_minutes as u64 * 60);
+
+        // an untouched timer shows the new length,
+        // one in progress keeps counting down
+        if !self.running && self.remaining == previous {
+            self.remaining = self.length(self.phase);
+        }
+    }
+}
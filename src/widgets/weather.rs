@@ -0,0 +1,218 @@
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+use anyhow::{Context, Result};
+use imgui::Ui;
+use serde::Deserialize;
+use crate::config::Config;
+use crate::consts::*;
+use crate::fonts::has_glyph;
+use crate::widgets::Widget;
+
+#[derive(Deserialize)]
+struct Forecast {
+    current: Conditions,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+struct Conditions {
+    #[serde(rename = "temperature_2m")]
+    temperature: f32, // in celsius
+    weather_code: u32, // WMO code
+}
+
+impl Conditions {
+    fn icon(&self) -> char {
+        match self.weather_code {
+            0 | 1 => ICON_WEATHER_CLEAR,
+            2 => ICON_WEATHER_PARTLY_CLOUDY,
+            45 | 48 => ICON_WEATHER_FOG,
+            51..=67 | 80..=82 => ICON_WEATHER_RAIN,
+            71..=77 | 85 | 86 => ICON_WEATHER_SNOW,
+            95..=99 => ICON_BOLT,
+            _ => ICON_WEATHER_CLOUDY,
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self.weather_code {
+            0 => "Clear",
+            1 => "Mostly clear",
+            2 => "Partly cloudy",
+            3 => "Overcast",
+            45 | 48 => "Fog",
+            51..=57 => "Drizzle",
+            61..=67 | 80..=82 => "Rain",
+            71..=77 | 85 | 86 => "Snow",
+            95..=99 => "Thunderstorm",
+            _ => "Cloudy",
+        }
+    }
+}
+
+async fn fetch(client: &reqwest::Client, [latitude, longitude]: [f64; 2]) -> Result<Conditions> {
+    let forecast: Forecast = client
+        .get(WEATHER_API_URL)
+        .query(&[
+            ("latitude", latitude.to_string()),
+            ("longitude", longitude.to_string()),
+            ("current", "temperature_2m,weather_code".to_string()),
+        ])
+        .timeout(WEATHER_REQUEST_TIMEOUT)
+        .send()
+        .await
+        .context("Failed to request weather")?
+        .error_for_status()
+        .context("Weather service returned an error")?
+        .json()
+        .await
+        .context("Failed to parse weather")?;
+
+    Ok(forecast.current)
+}
+
+/// Runs requests for a location on a background thread, so a slow or
+/// missing network never holds up a frame. The thread ends with the sender.
+fn spawn_fetcher(results: Sender<Result<Conditions>>) -> Result<Sender<[f64; 2]>> {
+    let (requests, requested) = channel::<[f64; 2]>();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("Failed to create weather runtime")?;
+
+    thread::Builder::new()
+        .name("weather".to_string())
+        .spawn(move || {
+            let client = reqwest::Client::new();
+
+            for location in requested {
+                // the widget is gone once nobody's listening
+                if results.send(runtime.block_on(fetch(&client, location))).is_err() {
+                    break;
+                }
+            }
+        })
+        .context("Failed to spawn weather thread")?;
+
+    Ok(requests)
+}
+
+/// Temperature and conditions at the configured location, hidden without one.
+pub struct Weather {
+    location: Option<[f64; 2]>, // latitude and longitude
+    interval: Duration,
+    requests: Option<Sender<[f64; 2]>>, // `None` when the fetcher couldn't start
+    results: Receiver<Result<Conditions>>,
+    conditions: Option<(Conditions, Instant)>, // the latest, and when it was fetched
+    error: Option<String>, // of the last fetch, the conditions being stale
+    pending: bool, // a request hasn't been answered yet
+    since_refresh: Option<Duration>, // `None` until the first refresh
+}
+
+impl Weather {
+    pub fn new(config: &Config) -> Self {
+        let (sender, results) = channel();
+
+        let requests = spawn_fetcher(sender)
+            .inspect_err(|e| log::warn!("{e:?}"))
+            .ok();
+
+        Self {
+            location: config.weather_location,
+            interval: Duration::from_secs(config.weather_interval_minutes.max(1) as u64 * 60),
+            requests,
+            results,
+            conditions: None,
+            error: None,
+            pending: false,
+            since_refresh: None,
+        }
+    }
+}
+
+impl Widget for Weather {
+    fn update(&mut self, dt: Duration) {
+        for result in self.results.try_iter() {
+            self.pending = false;
+
+            match result {
+                Ok(conditions) => {
+                    self.conditions = Some((conditions, Instant::now()));
+                    self.error = None;
+                }
+
+                // the last conditions stay
+                // up, marked as out of date
+                Err(e) => {
+                    log::warn!("{e:?}");
+                    self.error = Some(format!("{e:#}"));
+                }
+            }
+        }
+
+        let (Some(location), Some(requests)) = (self.location, &self.requests) else {
+            return;
+        };
+
+        if let Some(since_refresh) = self.since_refresh.as_mut() {
+            *since_refresh += dt;
+
+            if *since_refresh < self.interval || self.pending {
+                return;
+            }
+        }
+
+        self.pending = requests.send(location).is_ok();
+        self.since_refresh = Some(Duration::ZERO);
+    }
+
+    fn visible(&self) -> bool {
+        self.location.is_some()
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        let Some((conditions, fetched)) = self.conditions else {
+            ui.text_disabled("-");
+
+            if let Some(error) = &self.error {
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(error);
+                }
+            }
+
+            return;
+        };
+
+        let icon = conditions.icon();
+        let temperature = format!("{:.0}\u{b0}C", conditions.temperature);
+
+        if has_glyph(ui, icon) {
+            ui.text(format!("{icon} {temperature}"));
+        } else {
+            ui.text(&temperature);
+        }
+
+        if ui.is_item_hovered() {
+            let minutes = fetched.elapsed().as_secs() / 60;
+            let mut tooltip = format!("{}\nUpdated {minutes} min ago", conditions.description());
+
+            if let Some(error) = &self.error {
+                tooltip.push_str(&format!("\n{error}"));
+            }
+
+            ui.tooltip_text(tooltip);
+        }
+    }
+
+    fn apply_config(&mut self, config: &Config) {
+        self.interval = Duration::from_secs(config.weather_interval_minutes.max(1) as u64 * 60);
+
+        if config.weather_location != self.location {
+            self.location = config.weather_location;
+            self.conditions = None;
+            self.error = None;
+            self.since_refresh = None; // fetched on the next frame
+        }
+    }
+}
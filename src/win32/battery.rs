@@ -0,0 +1,98 @@
+use std::time::Duration;
+use imgui::Ui;
+use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+use crate::consts::*;
+use crate::fonts::has_glyph;
+use crate::widgets::Widget;
+
+// `SYSTEM_POWER_STATUS` sentinels
+const AC_ONLINE: u8 = 1;
+const NO_BATTERY: u8 = 128;
+const UNKNOWN: u8 = 255;
+
+#[derive(Clone, Copy)]
+struct BatteryStatus {
+    percent: u8,
+    charging: bool,
+}
+
+fn battery_status() -> Option<BatteryStatus> {
+    let mut status = SYSTEM_POWER_STATUS::default();
+    unsafe { GetSystemPowerStatus(&mut status).ok()? };
+
+    if status.BatteryFlag & NO_BATTERY != 0 || status.BatteryFlag == UNKNOWN || status.BatteryLifePercent == UNKNOWN {
+        return None;
+    }
+
+    Some(BatteryStatus {
+        percent: status.BatteryLifePercent,
+        charging: status.ACLineStatus == AC_ONLINE,
+    })
+}
+
+/// Battery charge and charging state, hidden on machines without a battery.
+pub struct Battery {
+    status: Option<BatteryStatus>,
+    since_refresh: Option<Duration>, // `None` until the first refresh
+}
+
+impl Battery {
+    pub fn new() -> Self {
+        Self {
+            status: None,
+            since_refresh: None,
+        }
+    }
+}
+
+impl Widget for Battery {
+    fn essential(&self) -> bool {
+        true
+    }
+
+    fn update(&mut self, dt: Duration) {
+        if let Some(since_refresh) = self.since_refresh.as_mut() {
+            *since_refresh += dt;
+
+            if *since_refresh < BATTERY_INTERVAL {
+                return;
+            }
+        }
+
+        self.status = battery_status();
+        self.since_refresh = Some(Duration::ZERO);
+    }
+
+    fn visible(&self) -> bool {
+        self.status.is_some()
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        let Some(status) = self.status else {
+            return;
+        };
+
+        let icon = match status.percent {
+            88.. => ICON_BATTERY_FULL,
+            63.. => ICON_BATTERY_THREE_QUARTERS,
+            38.. => ICON_BATTERY_HALF,
+            13.. => ICON_BATTERY_QUARTER,
+            _ => ICON_BATTERY_EMPTY,
+        };
+
+        if has_glyph(ui, icon) {
+            let charging = if status.charging { ICON_BOLT.to_string() } else { String::new() };
+
+            ui.text(format!("{icon}{charging} {}%", status.percent));
+            return;
+        }
+
+        let state = if status.charging { "charging" } else { "on battery" };
+
+        ui.progress_bar(status.percent as f32 / 100f32)
+            .size([SYSTEM_USAGE_BAR_WIDTH, ui.text_line_height()])
+            .build();
+        ui.same_line();
+        ui.text(format!("{}% ({state})", status.percent));
+    }
+}
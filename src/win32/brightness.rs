@@ -0,0 +1,406 @@
+use std::ffi::c_void;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+use anyhow::{anyhow, bail, Context, Result};
+use imgui::Ui;
+use windows::core::{w, BOOL, BSTR, PCWSTR};
+use windows::Win32::Devices::Display::{
+    DestroyPhysicalMonitor, GetMonitorBrightness, GetNumberOfPhysicalMonitorsFromHMONITOR, GetPhysicalMonitorsFromHMONITOR,
+    SetMonitorBrightness, PHYSICAL_MONITOR
+};
+use windows::Win32::Foundation::{LPARAM, RECT};
+use windows::Win32::Graphics::Gdi::{EnumDisplayMonitors, HDC, HMONITOR};
+use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED, SAFEARRAY};
+use windows::Win32::System::Ole::{SafeArrayGetElement, SafeArrayGetLBound, SafeArrayGetUBound};
+use windows::Win32::System::Variant::{VARIANT, VT_ARRAY, VT_UI1};
+use windows::Win32::System::Wmi::{
+    IWbemClassObject, IWbemLocator, IWbemServices, WbemLocator, WBEM_FLAG_FORWARD_ONLY, WBEM_FLAG_RETURN_IMMEDIATELY,
+    WBEM_GENERIC_FLAG_TYPE, WBEM_INFINITE
+};
+use crate::consts::*;
+use crate::fonts::has_glyph;
+use crate::widgets::Widget;
+
+/// How a display takes its brightness.
+enum Control {
+    /// External monitors, over DDC/CI.
+    Ddc(PHYSICAL_MONITOR),
+    /// Built-in panels, through the `WmiMonitorBrightnessMethods` instance at this path.
+    Wmi(IWbemServices, BSTR),
+}
+
+/// A display that supports brightness, in the range it reported.
+struct Display {
+    control: Control,
+    min: u32,
+    max: u32,
+}
+
+impl Display {
+    /// The display behind `monitor`, `None` when it doesn't support brightness.
+    fn ddc(monitor: PHYSICAL_MONITOR) -> Option<Self> {
+        let (mut min, mut current, mut max) = (0, 0, 0);
+        let supported = unsafe { GetMonitorBrightness(monitor.hPhysicalMonitor, &mut min, &mut current, &mut max) } != 0;
+
+        // dropping destroys the handle
+        let display = Self { control: Control::Ddc(monitor), min, max };
+
+        (supported && max > min).then_some(display)
+    }
+
+    /// The brightness in the display's own units.
+    fn value(&self) -> Result<u32> {
+        match &self.control {
+            Control::Ddc(monitor) => {
+                let (mut min, mut current, mut max) = (0, 0, 0);
+
+                if unsafe { GetMonitorBrightness(monitor.hPhysicalMonitor, &mut min, &mut current, &mut max) } == 0 {
+                    bail!("Failed to get monitor brightness");
+                }
+
+                Ok(current)
+            }
+
+            Control::Wmi(services, _) => {
+                let brightness = unsafe { query_first(services, "SELECT CurrentBrightness FROM WmiMonitorBrightness")? };
+                let value = unsafe { get_property(&brightness, w!("CurrentBrightness"))? };
+
+                u8::try_from(&value).map(u32::from).context("Failed to read current brightness")
+            }
+        }
+    }
+
+    fn set_value(&self, value: u32) -> Result<()> {
+        match &self.control {
+            Control::Ddc(monitor) => {
+                if unsafe { SetMonitorBrightness(monitor.hPhysicalMonitor, value) } == 0 {
+                    bail!("Failed to set monitor brightness");
+                }
+
+                Ok(())
+            }
+
+            Control::Wmi(services, path) => unsafe { wmi_set_brightness(services, path, value as u8) },
+        }
+    }
+
+    /// The brightness from 0 to 1.
+    fn level(&self) -> Result<f32> {
+        let value = self.value()?.clamp(self.min, self.max);
+        Ok((value - self.min) as f32 / (self.max - self.min) as f32)
+    }
+
+    fn set_level(&self, level: f32) -> Result<()> {
+        let value = self.min + ((self.max - self.min) as f32 * level.clamp(0f32, 1f32)).round() as u32;
+        self.set_value(value)
+    }
+}
+
+impl Drop for Display {
+    fn drop(&mut self) {
+        if let Control::Ddc(monitor) = &self.control {
+            unsafe { let _ = DestroyPhysicalMonitor(monitor.hPhysicalMonitor); }
+        }
+    }
+}
+
+unsafe extern "system" fn enum_monitor(monitor: HMONITOR, _hdc: HDC, _rect: *mut RECT, lparam: LPARAM) -> BOOL {
+    let monitors = unsafe { &mut *(lparam.0 as *mut Vec<HMONITOR>) };
+    monitors.push(monitor);
+
+    true.into()
+}
+
+/// External monitors supporting DDC/CI brightness.
+fn ddc_displays() -> Vec<Display> {
+    let mut monitors = Vec::<HMONITOR>::new();
+
+    unsafe {
+        let _ = EnumDisplayMonitors(None, None, Some(enum_monitor), LPARAM(&mut monitors as *mut _ as isize));
+    }
+
+    monitors
+        .into_iter()
+        .flat_map(|monitor| {
+            let mut count = 0;
+
+            // one per output when a monitor is cloned
+            let physical = unsafe {
+                GetNumberOfPhysicalMonitorsFromHMONITOR(monitor, &mut count).and_then(|_| {
+                    let mut physical = vec![PHYSICAL_MONITOR::default(); count as usize];
+                    GetPhysicalMonitorsFromHMONITOR(monitor, &mut physical).map(|_| physical)
+                })
+            };
+
+            physical
+                .inspect_err(|e| log::debug!("Failed to get physical monitors: {e}"))
+                .unwrap_or_default()
+        })
+        .filter_map(Display::ddc)
+        .collect()
+}
+
+/// The built-in panel, which doesn't speak DDC/CI. Its range
+/// is the lowest and highest of the levels it supports.
+fn wmi_display() -> Result<Display> {
+    unsafe {
+        let locator: IWbemLocator = CoCreateInstance(&WbemLocator, None, CLSCTX_INPROC_SERVER)
+            .context("Failed to create WMI locator")?;
+
+        let services = locator
+            .ConnectServer(&BSTR::from("ROOT\\WMI"), &BSTR::new(), &BSTR::new(), &BSTR::new(), 0, &BSTR::new(), None)
+            .context("Failed to connect to WMI")?;
+
+        let brightness = query_first(&services, "SELECT Level FROM WmiMonitorBrightness")?;
+        let levels = byte_array(&get_property(&brightness, w!("Level"))?)?;
+
+        let (Some(&min), Some(&max)) = (levels.iter().min(), levels.iter().max()) else {
+            bail!("The display reported no brightness levels");
+        };
+
+        if max <= min {
+            bail!("The display has a single brightness level");
+        }
+
+        let methods = query_first(&services, "SELECT __PATH FROM WmiMonitorBrightnessMethods")?;
+        let path = BSTR::try_from(&get_property(&methods, w!("__PATH"))?).context("Failed to read brightness methods path")?;
+
+        Ok(
+            Display {
+                control: Control::Wmi(services, path),
+                min: min as u32,
+                max: max as u32,
+            }
+        )
+    }
+}
+
+/// Every display supporting brightness, with their ranges queried once.
+fn displays() -> Vec<Display> {
+    let mut displays = ddc_displays();
+
+    // desktops have no built-in panel
+    match wmi_display() {
+        Ok(display) => displays.insert(0, display),
+        Err(e) => log::debug!("{e:?}"),
+    }
+
+    displays
+}
+
+unsafe fn query_first(services: &IWbemServices, query: &str) -> Result<IWbemClassObject> {
+    unsafe {
+        let objects = services
+            .ExecQuery(&BSTR::from("WQL"), &BSTR::from(query), WBEM_FLAG_FORWARD_ONLY | WBEM_FLAG_RETURN_IMMEDIATELY, None)
+            .with_context(|| format!("Failed to run WMI query '{query}'"))?;
+
+        let mut object = [None];
+        let mut returned = 0;
+        objects.Next(WBEM_INFINITE.0, &mut object, &mut returned).ok().context("Failed to get WMI query result")?;
+
+        object[0].take().ok_or_else(|| anyhow!("WMI query '{query}' had no results"))
+    }
+}
+
+unsafe fn get_property(object: &IWbemClassObject, name: PCWSTR) -> Result<VARIANT> {
+    let mut value = VARIANT::default();
+
+    unsafe {
+        object
+            .Get(name, 0, &mut value, None, None)
+            .with_context(|| format!("Failed to get WMI property '{}'", name.display()))?;
+    }
+
+    Ok(value)
+}
+
+unsafe fn byte_array(value: &VARIANT) -> Result<Vec<u8>> {
+    let value = value.as_raw();
+
+    unsafe {
+        if value.Anonymous.Anonymous.vt != (VT_ARRAY | VT_UI1).0 {
+            bail!("Expected an array of bytes");
+        }
+
+        let array = value.Anonymous.Anonymous.Anonymous.parray as *const SAFEARRAY;
+        let lower = SafeArrayGetLBound(array, 1).context("Failed to get array bounds")?;
+        let upper = SafeArrayGetUBound(array, 1).context("Failed to get array bounds")?;
+
+        (lower..=upper)
+            .map(|index| {
+                let mut element = 0u8;
+                SafeArrayGetElement(array, &index, &mut element as *mut u8 as *mut c_void).context("Failed to get array element")?;
+                Ok(element)
+            })
+            .collect()
+    }
+}
+
+unsafe fn wmi_set_brightness(services: &IWbemServices, path: &BSTR, brightness: u8) -> Result<()> {
+    unsafe {
+        let mut class = None;
+        services
+            .GetObject(&BSTR::from("WmiMonitorBrightnessMethods"), WBEM_GENERIC_FLAG_TYPE(0), None, Some(&mut class), None)
+            .context("Failed to get brightness methods")?;
+
+        let class = class.context("Failed to get brightness methods")?;
+
+        let mut signature = None;
+        class
+            .GetMethod(w!("WmiSetBrightness"), 0, &mut signature, std::ptr::null_mut())
+            .context("Failed to get WmiSetBrightness")?;
+
+        let parameters = signature.context("Failed to get WmiSetBrightness")?.SpawnInstance(0)?;
+
+        // applied straight away
+        parameters.Put(w!("Timeout"), 0, &VARIANT::from(0u32), 0)?;
+        parameters.Put(w!("Brightness"), 0, &VARIANT::from(brightness), 0)?;
+
+        services
+            .ExecMethod(path, &BSTR::from("WmiSetBrightness"), WBEM_GENERIC_FLAG_TYPE(0), None, &parameters, None, None)
+            .context("Failed to set display brightness")
+    }
+}
+
+/// Talks to the displays on a background thread, as each DDC/CI call takes
+/// tens of milliseconds. The thread ends with the sender, or straight away
+/// without a display to control.
+fn spawn_worker(updates: Sender<Option<f32>>) -> Result<Sender<f32>> {
+    let (levels, requested) = channel::<f32>();
+
+    thread::Builder::new()
+        .name("brightness".to_string())
+        .spawn(move || {
+            // WMI calls are made from this thread only
+            let _ = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+
+            let displays = displays();
+
+            loop {
+                let level = displays
+                    .first()
+                    .and_then(|display| display.level().inspect_err(|e| log::debug!("{e:?}")).ok());
+
+                if updates.send(level).is_err() || displays.is_empty() {
+                    break;
+                }
+
+                match requested.recv_timeout(BRIGHTNESS_INTERVAL) {
+                    Ok(mut level) => {
+                        // only where a drag ended up matters
+                        while let Ok(next) = requested.try_recv() {
+                            level = next;
+                        }
+
+                        for display in &displays {
+                            if let Err(e) = display.set_level(level) {
+                                log::debug!("{e:?}");
+                            }
+                        }
+                    }
+
+                    Err(RecvTimeoutError::Timeout) => { }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        })
+        .context("Failed to spawn brightness thread")?;
+
+    Ok(levels)
+}
+
+/// The brightness of every display that supports it, kept at the same level.
+pub struct Brightness {
+    levels: Option<Sender<f32>>, // `None` when the worker couldn't start
+    updates: Receiver<Option<f32>>,
+    level: Option<f32>, // `None` without a display to control
+}
+
+impl Brightness {
+    pub fn new() -> Self {
+        let (sender, updates) = channel();
+
+        let levels = spawn_worker(sender)
+            .inspect_err(|e| log::warn!("{e:?}"))
+            .ok();
+
+        Self {
+            levels,
+            updates,
+            level: None,
+        }
+    }
+
+    /// Picks up the level the displays reported since the last call.
+    pub fn update(&mut self) {
+        while let Ok(level) = self.updates.try_recv() {
+            self.level = level;
+        }
+    }
+
+    /// The brightness from 0 to 1, `None` when no display supports it.
+    pub fn level(&self) -> Option<f32> {
+        self.level
+    }
+
+    pub fn set_level(&mut self, level: f32) {
+        // shown straight away, the displays follow
+        self.level = Some(level);
+
+        if let Some(levels) = &self.levels {
+            let _ = levels.send(level);
+        }
+    }
+}
+
+/// A taskbar button with the brightness, opening a slider.
+pub struct BrightnessSlider {
+    brightness: Brightness,
+}
+
+impl BrightnessSlider {
+    pub fn new() -> Self {
+        Self {
+            brightness: Brightness::new(),
+        }
+    }
+}
+
+impl Widget for BrightnessSlider {
+    fn update(&mut self, _dt: Duration) {
+        self.brightness.update();
+    }
+
+    fn visible(&self) -> bool {
+        self.brightness.level().is_some()
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        let Some(level) = self.brightness.level() else {
+            return;
+        };
+
+        let label = match has_glyph(ui, ICON_BRIGHTNESS) {
+            true => format!("{ICON_BRIGHTNESS} {:.0}%", level * 100f32),
+            false => format!("Bri {:.0}%", level * 100f32),
+        };
+
+        if ui.button(format!("{label}##_brightness")) {
+            ui.open_popup(BRIGHTNESS_POPUP_ID);
+        }
+
+        ui.popup(BRIGHTNESS_POPUP_ID, || {
+            let mut percent = level * 100f32;
+
+            // sent every frame of a drag, the worker skips to the latest
+            if ui
+                .slider_config("##_brightness_slider", 0f32, 100f32)
+                .display_format("%.0f%%")
+                .build(&mut percent)
+            {
+                self.brightness.set_level(percent / 100f32);
+            }
+        });
+    }
+}
@@ -0,0 +1,464 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::Rc;
+use anyhow::{anyhow, bail, Context, Result};
+use image::imageops::thumbnail;
+use image::RgbaImage;
+use imgui::Ui;
+use imgui_glow_renderer::glow;
+use imgui_glow_renderer::glow::{HasContext, NativeTexture};
+use windows::core::{w, PCWSTR};
+use windows::Win32::Foundation::{GlobalFree, HANDLE, HGLOBAL, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::DataExchange::{
+    AddClipboardFormatListener, CloseClipboard, EmptyClipboard, GetClipboardData, IsClipboardFormatAvailable, OpenClipboard,
+    RemoveClipboardFormatListener, SetClipboardData,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, RegisterClassW, UnregisterClassW, HWND_MESSAGE, WINDOW_EX_STYLE,
+    WINDOW_STYLE, WM_CLIPBOARDUPDATE, WNDCLASSW,
+};
+use crate::consts::*;
+use crate::fonts::{has_glyph, request_glyphs};
+use crate::i18n::tr;
+use crate::renderer::{imgui_texture_id, upload_rgba_texture};
+use crate::widgets::Widget;
+
+const CLIPBOARD_CLASS: PCWSTR = w!("VeilDEClipboard");
+
+// standard clipboard formats, from `Win32_System_Ole`
+const CF_DIB: u32 = 8;
+const CF_UNICODETEXT: u32 = 13;
+
+/// A copied image, kept as the device independent bitmap it came as.
+pub struct ClipboardImage {
+    dib: Vec<u8>,
+    size: [u32; 2],
+    preview: RgbaImage, // scaled down to `CLIPBOARD_PREVIEW_SIZE`
+}
+
+pub enum ClipboardEntry {
+    Text(String),
+    Image(ClipboardImage),
+}
+
+impl ClipboardEntry {
+    fn same_as(&self, other: &ClipboardEntry) -> bool {
+        match (self, other) {
+            (ClipboardEntry::Text(a), ClipboardEntry::Text(b)) => a == b,
+            (ClipboardEntry::Image(a), ClipboardEntry::Image(b)) => a.dib == b.dib,
+            _ => false,
+        }
+    }
+}
+
+thread_local! {
+    // the listener window procedure runs on
+    // the event loop thread, as do the widgets
+    static HISTORY: RefCell<VecDeque<ClipboardEntry>> = const { RefCell::new(VecDeque::new()) }; // newest first
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+    static LIMIT: Cell<usize> = const { Cell::new(CLIPBOARD_HISTORY_LIMIT) };
+    static LISTENER: Cell<Option<HWND>> = const { Cell::new(None) }; // owns the clipboard when restoring
+}
+
+/// Copies the bytes of a clipboard memory handle.
+unsafe fn global_bytes(handle: HANDLE) -> Option<Vec<u8>> {
+    let memory = HGLOBAL(handle.0);
+
+    unsafe {
+        let size = GlobalSize(memory);
+        let pointer = GlobalLock(memory) as *const u8;
+
+        if pointer.is_null() {
+            return None;
+        }
+
+        let bytes = std::slice::from_raw_parts(pointer, size).to_vec();
+        let _ = GlobalUnlock(memory);
+
+        Some(bytes)
+    }
+}
+
+/// Converts an uncompressed 24 or 32 bit DIB to RGBA pixels.
+fn dib_image(dib: &[u8]) -> Option<RgbaImage> {
+    let u32_at = |offset: usize| Some(u32::from_le_bytes(dib.get(offset..offset + 4)?.try_into().ok()?));
+    let u16_at = |offset: usize| Some(u16::from_le_bytes(dib.get(offset..offset + 2)?.try_into().ok()?));
+
+    let header_size = u32_at(0)? as usize;
+    let width = u32_at(4)? as i32;
+    let height = u32_at(8)? as i32;
+    let bit_count = u16_at(14)?;
+    let compression = u32_at(16)?;
+
+    // BI_RGB, or BI_BITFIELDS with the usual BGRA masks after a short header
+    let masks = match compression {
+        0 => 0,
+        3 if header_size == 40 => 12,
+        3 => 0,
+        _ => return None,
+    };
+
+    if width <= 0 || height == 0 || !matches!(bit_count, 24 | 32) {
+        return None;
+    }
+
+    let (width, rows) = (width as usize, height.unsigned_abs() as usize);
+    let bytes_per_pixel = bit_count as usize / 8;
+    let stride = (width * bytes_per_pixel).div_ceil(4) * 4;
+    let pixels = dib.get(header_size + masks..)?;
+
+    if pixels.len() < stride * rows {
+        return None;
+    }
+
+    let mut rgba = Vec::with_capacity(width * rows * 4);
+
+    for row in 0..rows {
+        // positive heights are stored bottom row first
+        let source = if height > 0 { rows - 1 - row } else { row };
+
+        for pixel in pixels[source * stride..][..width * bytes_per_pixel].chunks_exact(bytes_per_pixel) {
+            let alpha = if bytes_per_pixel == 4 { pixel[3] } else { 255 };
+            rgba.extend_from_slice(&[pixel[2], pixel[1], pixel[0], alpha]);
+        }
+    }
+
+    // most applications leave the alpha channel empty
+    if rgba.chunks_exact(4).all(|pixel| pixel[3] == 0) {
+        rgba.chunks_exact_mut(4).for_each(|pixel| pixel[3] = 255);
+    }
+
+    RgbaImage::from_raw(width as u32, rows as u32, rgba)
+}
+
+/// Reads the text or image on the clipboard, `None` for anything else.
+fn read_clipboard(hwnd: HWND) -> Option<ClipboardEntry> {
+    unsafe {
+        OpenClipboard(Some(hwnd)).ok()?;
+
+        let entry = if IsClipboardFormatAvailable(CF_UNICODETEXT).is_ok() {
+            GetClipboardData(CF_UNICODETEXT)
+                .ok()
+                .and_then(|handle| global_bytes(handle))
+                .map(|bytes| {
+                    let units: Vec<u16> = bytes.chunks_exact(2).map(|unit| u16::from_le_bytes([unit[0], unit[1]])).collect();
+                    let length = units.iter().position(|&unit| unit == 0).unwrap_or(units.len());
+
+                    ClipboardEntry::Text(String::from_utf16_lossy(&units[..length]))
+                })
+                .filter(|entry| !matches!(entry, ClipboardEntry::Text(text) if text.trim().is_empty()))
+        } else if IsClipboardFormatAvailable(CF_DIB).is_ok() {
+            GetClipboardData(CF_DIB)
+                .ok()
+                .and_then(|handle| global_bytes(handle))
+                .filter(|dib| dib.len() <= CLIPBOARD_IMAGE_LIMIT)
+                .and_then(|dib| {
+                    let image = dib_image(&dib)?;
+                    let scale = (CLIPBOARD_PREVIEW_SIZE[0] as f32 / image.width() as f32)
+                        .min(CLIPBOARD_PREVIEW_SIZE[1] as f32 / image.height() as f32)
+                        .min(1f32);
+
+                    Some(ClipboardEntry::Image(ClipboardImage {
+                        size: [image.width(), image.height()],
+                        preview: thumbnail(
+                            &image,
+                            (image.width() as f32 * scale).max(1f32) as u32,
+                            (image.height() as f32 * scale).max(1f32) as u32
+                        ),
+                        dib,
+                    }))
+                })
+        } else {
+            None
+        };
+
+        let _ = CloseClipboard();
+
+        entry
+    }
+}
+
+/// Adds `entry` to the front of the history, moving it there if it was already copied.
+fn remember(entry: ClipboardEntry) {
+    HISTORY.with_borrow_mut(|history| {
+        if let Some(index) = history.iter().position(|existing| existing.same_as(&entry)) {
+            history.remove(index);
+        }
+
+        history.push_front(entry);
+        history.truncate(LIMIT.get());
+    });
+
+    GENERATION.set(GENERATION.get() + 1);
+}
+
+/// Puts the entry at `index` back on the clipboard, which moves it to the front.
+fn restore(index: usize) -> Result<()> {
+    let hwnd = LISTENER.get().context("Clipboard history isn't running")?;
+
+    let (format, bytes) = HISTORY.with_borrow(|history| match history.get(index) {
+        Some(ClipboardEntry::Text(text)) => Some((
+            CF_UNICODETEXT,
+            text.encode_utf16().chain([0]).flat_map(u16::to_le_bytes).collect::<Vec<u8>>(),
+        )),
+        Some(ClipboardEntry::Image(image)) => Some((CF_DIB, image.dib.clone())),
+        None => None,
+    }).context("Clipboard entry no longer exists")?;
+
+    unsafe {
+        let memory = GlobalAlloc(GMEM_MOVEABLE, bytes.len()).context("Failed to allocate clipboard memory")?;
+        let pointer = GlobalLock(memory) as *mut u8;
+
+        if pointer.is_null() {
+            let _ = GlobalFree(Some(memory));
+            bail!("Failed to lock clipboard memory");
+        }
+
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), pointer, bytes.len());
+        let _ = GlobalUnlock(memory);
+
+        if let Err(e) = OpenClipboard(Some(hwnd)) {
+            let _ = GlobalFree(Some(memory));
+            return Err(anyhow!(e).context("Failed to open clipboard"));
+        }
+
+        let _ = EmptyClipboard();
+
+        // the clipboard owns the memory once it's set
+        let result = SetClipboardData(format, Some(HANDLE(memory.0)));
+        let _ = CloseClipboard();
+
+        if let Err(e) = result {
+            let _ = GlobalFree(Some(memory));
+            return Err(anyhow!(e).context("Failed to set clipboard data"));
+        }
+    }
+
+    // the update message moves it to the front
+    Ok(())
+}
+
+unsafe extern "system" fn window_proc(hwnd: HWND, message: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if message == WM_CLIPBOARDUPDATE {
+        // other formats (e.g. files) aren't kept
+        if let Some(entry) = read_clipboard(hwnd) {
+            remember(entry);
+        }
+
+        return LRESULT(0);
+    }
+
+    unsafe { DefWindowProcW(hwnd, message, wparam, lparam) }
+}
+
+/// A message-only window told about every clipboard change.
+pub struct ClipboardHost {
+    hwnd: HWND,
+}
+
+impl ClipboardHost {
+    pub fn new(limit: usize) -> Result<Self> {
+        unsafe {
+            let instance = GetModuleHandleW(None).context("Failed to get module handle")?;
+
+            let class = WNDCLASSW {
+                lpfnWndProc: Some(window_proc),
+                hInstance: instance.into(),
+                lpszClassName: CLIPBOARD_CLASS,
+                ..Default::default()
+            };
+
+            if RegisterClassW(&class) == 0 {
+                bail!("Failed to register clipboard window class");
+            }
+
+            let hwnd = CreateWindowExW(
+                WINDOW_EX_STYLE::default(),
+                CLIPBOARD_CLASS,
+                w!(""),
+                WINDOW_STYLE::default(),
+                0,
+                0,
+                0,
+                0,
+                Some(HWND_MESSAGE),
+                None,
+                Some(instance.into()),
+                None
+            ).context("Failed to create clipboard window")?;
+
+            // dropping the host cleans up
+            let host = Self { hwnd };
+
+            AddClipboardFormatListener(hwnd).context("Failed to listen for clipboard changes")?;
+            LISTENER.set(Some(hwnd));
+            host.set_limit(limit);
+
+            Ok(host)
+        }
+    }
+
+    pub fn set_limit(&self, limit: usize) {
+        LIMIT.set(limit);
+        HISTORY.with_borrow_mut(|history| history.truncate(limit));
+        GENERATION.set(GENERATION.get() + 1);
+    }
+}
+
+impl Drop for ClipboardHost {
+    fn drop(&mut self) {
+        LISTENER.set(None);
+
+        unsafe {
+            let _ = RemoveClipboardFormatListener(self.hwnd);
+            let _ = DestroyWindow(self.hwnd);
+
+            if let Ok(instance) = GetModuleHandleW(None) {
+                let _ = UnregisterClassW(CLIPBOARD_CLASS, Some(instance.into()));
+            }
+        }
+    }
+}
+
+/// Taskbar button opening the history kept by the `ClipboardHost`.
+pub struct ClipboardHistory {
+    gl: Rc<glow::Context>,
+    previews: Vec<Option<NativeTexture>>, // parallel to `HISTORY`, `None` for text
+    generation: Option<u64>,
+    filter: String,
+}
+
+impl ClipboardHistory {
+    pub fn new(gl: Rc<glow::Context>) -> Self {
+        Self {
+            gl,
+            previews: Vec::new(),
+            generation: None,
+            filter: String::new(),
+        }
+    }
+
+    fn delete_previews(&mut self) {
+        for texture in self.previews.drain(..).flatten() {
+            unsafe { self.gl.delete_texture(texture); }
+        }
+    }
+
+    fn upload_previews(&mut self) {
+        self.delete_previews();
+
+        self.previews = HISTORY.with_borrow(|history| {
+            history
+                .iter()
+                .map(|entry| {
+                    let ClipboardEntry::Image(image) = entry else {
+                        return None;
+                    };
+
+                    upload_rgba_texture(&self.gl, image.preview.width(), image.preview.height(), image.preview.as_raw()).ok()
+                })
+                .collect()
+        });
+
+        self.generation = Some(GENERATION.get());
+    }
+}
+
+impl Widget for ClipboardHistory {
+    fn visible(&self) -> bool {
+        LISTENER.get().is_some()
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        let label = if has_glyph(ui, ICON_CLIPBOARD) { ICON_CLIPBOARD.to_string() } else { "Clipboard".to_string() };
+
+        if ui.button(format!("{label}##_clipboard")) {
+            self.filter.clear();
+            ui.open_popup(CLIPBOARD_POPUP_ID);
+        }
+
+        let mut restored = None;
+        let mut clear = false;
+
+        ui.popup(CLIPBOARD_POPUP_ID, || {
+            // only while open, as the history changes with every copy
+            if self.generation != Some(GENERATION.get()) {
+                self.upload_previews();
+            }
+
+            ui.input_text("##_clipboard_filter", &mut self.filter)
+                .hint(tr("Search"))
+                .build();
+
+            ui.same_line();
+            clear = ui.button(tr("Clear"));
+            ui.separator();
+
+            let filter = self.filter.to_lowercase();
+
+            HISTORY.with_borrow(|history| {
+                for (index, (entry, preview)) in history.iter().zip(&self.previews).enumerate() {
+                    let chosen = match (entry, preview) {
+                        (ClipboardEntry::Text(text), _) => {
+                            if !text.to_lowercase().contains(&filter) {
+                                continue;
+                            }
+
+                            let line = text.lines().next().unwrap_or_default();
+                            let label = match line.char_indices().nth(CLIPBOARD_ENTRY_LENGTH) {
+                                Some((end, _)) => format!("{}...", &line[..end]),
+                                None if line.len() < text.trim_end().len() => format!("{line}..."),
+                                None => line.to_string(),
+                            };
+
+                            request_glyphs(ui, &label);
+                            ui.selectable(format!("{label}##_clipboard_{index}"))
+                        }
+
+                        (ClipboardEntry::Image(image), preview) => {
+                            if !filter.is_empty() && !"image".contains(&filter) {
+                                continue;
+                            }
+
+                            let chosen = ui.selectable(format!("Image {}x{}##_clipboard_{index}", image.size[0], image.size[1]));
+
+                            if let Some(preview) = preview {
+                                let size = [image.preview.width() as f32, image.preview.height() as f32];
+                                imgui::Image::new(imgui_texture_id(*preview), size).build(ui);
+                            }
+
+                            chosen
+                        }
+                    };
+
+                    if chosen {
+                        restored = Some(index);
+                    }
+                }
+
+                if history.is_empty() {
+                    ui.text_disabled(tr(CLIPBOARD_EMPTY_TEXT));
+                }
+            });
+        });
+
+        if clear {
+            HISTORY.with_borrow_mut(VecDeque::clear);
+            GENERATION.set(GENERATION.get() + 1);
+        }
+
+        if let Some(index) = restored {
+            if let Err(e) = restore(index) {
+                log::warn!("{:?}", e.context("Failed to restore clipboard entry"));
+            }
+        }
+    }
+}
+
+impl Drop for ClipboardHistory {
+    fn drop(&mut self) {
+        self.delete_previews();
+    }
+}
@@ -0,0 +1,155 @@
+use std::sync::mpsc::channel;
+use std::thread::JoinHandle;
+use anyhow::{bail, Context, Error, Result};
+use windows::Win32::Foundation::{LPARAM, WPARAM};
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    RegisterHotKey, UnregisterHotKey, HOT_KEY_MODIFIERS, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT, MOD_WIN,
+    VK_DELETE, VK_DOWN, VK_END, VK_ESCAPE, VK_F1, VK_HOME, VK_INSERT, VK_LEFT, VK_NEXT, VK_PRIOR, VK_RETURN, VK_RIGHT,
+    VK_SNAPSHOT, VK_SPACE, VK_TAB, VK_UP,
+};
+use windows::Win32::UI::WindowsAndMessaging::{GetMessageW, PostThreadMessageW, MSG, WM_HOTKEY, WM_QUIT};
+use winit::event_loop::EventLoopProxy;
+use crate::config::Hotkey;
+use crate::ipc::IpcCommand;
+
+/// Parses e.g. "Ctrl+Alt+S" into `RegisterHotKey` modifiers and a virtual key.
+fn parse_keys(keys: &str) -> Result<(HOT_KEY_MODIFIERS, u32)> {
+    let mut modifiers = MOD_NOREPEAT; // held keys fire once
+    let mut key = None;
+
+    for part in keys.split('+').map(str::trim) {
+        let modifier = match part.to_lowercase().as_str() {
+            "ctrl" | "control" => Some(MOD_CONTROL),
+            "alt" => Some(MOD_ALT),
+            "shift" => Some(MOD_SHIFT),
+            "win" | "super" => Some(MOD_WIN),
+            _ => None,
+        };
+
+        if let Some(modifier) = modifier {
+            modifiers |= modifier;
+            continue;
+        }
+
+        if key.is_some() {
+            bail!("Hotkey '{keys}' has more than one key");
+        }
+
+        key = Some(parse_key(part).with_context(|| format!("Hotkey '{keys}' has an unknown key '{part}'"))?);
+    }
+
+    Ok((modifiers, key.with_context(|| format!("Hotkey '{keys}' has no key"))?))
+}
+
+fn parse_key(name: &str) -> Option<u32> {
+    let upper = name.to_uppercase();
+
+    // letters and digits are their own virtual keys
+    if let [character] = upper.as_bytes() {
+        return character.is_ascii_alphanumeric().then_some(*character as u32);
+    }
+
+    if let Some(number) = upper.strip_prefix('F').and_then(|number| number.parse::<u32>().ok()) {
+        return (1..=24).contains(&number).then(|| VK_F1.0 as u32 + number - 1);
+    }
+
+    let key = match upper.as_str() {
+        "SPACE" => VK_SPACE,
+        "ENTER" | "RETURN" => VK_RETURN,
+        "TAB" => VK_TAB,
+        "ESC" | "ESCAPE" => VK_ESCAPE,
+        "PRINTSCREEN" => VK_SNAPSHOT,
+        "INSERT" => VK_INSERT,
+        "DELETE" => VK_DELETE,
+        "HOME" => VK_HOME,
+        "END" => VK_END,
+        "PAGEUP" => VK_PRIOR,
+        "PAGEDOWN" => VK_NEXT,
+        "LEFT" => VK_LEFT,
+        "RIGHT" => VK_RIGHT,
+        "UP" => VK_UP,
+        "DOWN" => VK_DOWN,
+        _ => return None,
+    };
+
+    Some(key.0 as u32)
+}
+
+/// Registers system-wide hotkeys, sending their commands to the event loop
+/// while VeilDE isn't focused. Unregisters them when dropped.
+pub struct HotkeyHost {
+    thread_id: u32,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl HotkeyHost {
+    /// Registers `hotkeys`, along with the errors of those that failed,
+    /// e.g. when another program already registered the same keys.
+    pub fn new(hotkeys: &[Hotkey], proxy: EventLoopProxy<IpcCommand>) -> Result<(Self, Vec<Error>)> {
+        let hotkeys = hotkeys.to_vec();
+        let (tx, rx) = channel();
+
+        // hotkeys are delivered to the message
+        // queue of the thread registering them
+        let thread = std::thread::spawn(move || {
+            let mut errors = Vec::new();
+            let mut registered = Vec::new();
+
+            for (id, hotkey) in hotkeys.iter().enumerate() {
+                let result = parse_keys(&hotkey.keys).and_then(|(modifiers, key)| {
+                    unsafe { RegisterHotKey(None, id as i32, modifiers, key) }
+                        .with_context(|| format!("Failed to register hotkey '{}', it may be in use", hotkey.keys))
+                });
+
+                match result {
+                    Ok(()) => registered.push(id as i32),
+                    Err(e) => errors.push(e),
+                }
+            }
+
+            let _ = tx.send((unsafe { GetCurrentThreadId() }, errors));
+
+            let mut message = MSG::default();
+
+            // until `WM_QUIT` from `drop`
+            while unsafe { GetMessageW(&mut message, None, 0, 0) }.as_bool() {
+                if message.message != WM_HOTKEY {
+                    continue;
+                }
+
+                let Some(hotkey) = hotkeys.get(message.wParam.0) else {
+                    continue;
+                };
+
+                if proxy.send_event(hotkey.command.clone()).is_err() {
+                    break; // the event loop is gone
+                }
+            }
+
+            for id in registered {
+                unsafe { let _ = UnregisterHotKey(None, id); }
+            }
+        });
+
+        let (thread_id, errors) = rx.recv().context("Hotkey thread exited during registration")?;
+
+        Ok((
+            Self {
+                thread_id,
+                thread: Some(thread),
+            },
+            errors
+        ))
+    }
+}
+
+impl Drop for HotkeyHost {
+    fn drop(&mut self) {
+        unsafe { let _ = PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0)); }
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use std::time::Duration;
+use windows::Win32::System::SystemInformation::GetTickCount;
+use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+/// Time since the last keyboard or mouse input to any application.
+pub fn idle_time() -> Duration {
+    let mut info = LASTINPUTINFO {
+        cbSize: size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+
+    unsafe {
+        if !GetLastInputInfo(&mut info).as_bool() {
+            return Duration::ZERO;
+        }
+
+        // both wrap around after 49.7 days
+        Duration::from_millis(GetTickCount().wrapping_sub(info.dwTime) as u64)
+    }
+}
@@ -0,0 +1,87 @@
+use std::fs::File;
+use std::os::windows::io::{FromRawHandle, RawHandle};
+use anyhow::{bail, Context, Result};
+use windows::core::HSTRING;
+use windows::Win32::Foundation::ERROR_PIPE_CONNECTED;
+use windows::Win32::Storage::FileSystem::PIPE_ACCESS_DUPLEX;
+use windows::Win32::System::Pipes::{ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_TYPE_BYTE, PIPE_WAIT};
+use winit::event_loop::EventLoopProxy;
+use crate::consts::*;
+use crate::ipc::{read_message, write_message, IpcCommand, IpcResponse};
+
+/// Serves `IpcCommand`s on a named pipe, one client at a time,
+/// forwarding them to the event loop until it's closed.
+pub fn spawn_server(proxy: EventLoopProxy<IpcCommand>) {
+    std::thread::spawn(move || {
+        if let Err(e) = serve(proxy) {
+            log::error!("{:?}", e.context("IPC server stopped"));
+        }
+    });
+}
+
+fn serve(proxy: EventLoopProxy<IpcCommand>) -> Result<()> {
+    loop {
+        let mut pipe = accept()?;
+
+        let command = match read_message::<_, IpcCommand>(&mut pipe) {
+            Ok(command) => command,
+
+            // a bad client shouldn't stop the server
+            Err(e) => {
+                log::warn!("{e:?}");
+                let _ = write_message(&mut pipe, &IpcResponse { error: Some(format!("{e:#}")) });
+                continue;
+            }
+        };
+
+        log::info!("Received IPC command {command:?}");
+
+        let closed = proxy.send_event(command).is_err();
+        let error = closed.then(|| "VeilDE is shutting down".to_string());
+
+        if let Err(e) = write_message(&mut pipe, &IpcResponse { error }) {
+            log::warn!("{e:?}");
+        }
+
+        // lets the client read the response
+        // before the pipe is closed on drop
+        let _ = pipe.sync_all();
+
+        if closed {
+            return Ok(());
+        }
+    }
+}
+
+/// Creates a pipe instance and waits for a client to connect to it.
+fn accept() -> Result<File> {
+    let pipe = unsafe {
+        CreateNamedPipeW(
+            &HSTRING::from(IPC_PIPE_NAME),
+            PIPE_ACCESS_DUPLEX,
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+            1,
+            IPC_MESSAGE_LIMIT as u32,
+            IPC_MESSAGE_LIMIT as u32,
+            0,
+            None
+        )
+    };
+
+    if pipe.is_invalid() {
+        bail!("Failed to create named pipe '{IPC_PIPE_NAME}'");
+    }
+
+    // closes the handle from here on
+    let file = unsafe { File::from_raw_handle(pipe.0 as RawHandle) };
+
+    // a client connecting between creating and
+    // waiting is reported as an error, but is fine
+    if let Err(e) = unsafe { ConnectNamedPipe(pipe, None) } {
+        if e.code() != ERROR_PIPE_CONNECTED.to_hresult() {
+            return Err(e).context("Failed to wait for IPC client");
+        }
+    }
+
+    Ok(file)
+}
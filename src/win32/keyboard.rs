@@ -0,0 +1,93 @@
+use std::time::Duration;
+use imgui::Ui;
+use windows::Win32::Globalization::{GetLocaleInfoW, LOCALE_SISO639LANGNAME};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    ActivateKeyboardLayout, GetKeyState, GetKeyboardLayout, HKL_NEXT, KLF_SETFORPROCESS, VK_CAPITAL, VK_NUMLOCK, VK_SCROLL,
+};
+use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+use crate::consts::*;
+use crate::fonts::has_glyph;
+use crate::widgets::Widget;
+
+#[derive(Clone, Default, PartialEq)]
+struct KeyboardState {
+    layout: String, // ISO 639 language, e.g. "EN"
+    caps_lock: bool,
+    num_lock: bool,
+    scroll_lock: bool,
+}
+
+fn keyboard_state() -> KeyboardState {
+    unsafe {
+        // the layout follows the window being typed into
+        let thread = GetWindowThreadProcessId(GetForegroundWindow(), None);
+        let layout = GetKeyboardLayout(thread);
+
+        let mut name = [0u16; 9];
+        let language = (layout.0 as usize & 0xFFFF) as u32; // the low word
+        let length = GetLocaleInfoW(language, LOCALE_SISO639LANGNAME, Some(&mut name)) as usize;
+
+        // toggled keys have the low bit set
+        let toggled = |key: u16| GetKeyState(key as i32) & 1 != 0;
+
+        KeyboardState {
+            layout: String::from_utf16_lossy(&name[..length.saturating_sub(1)]).to_uppercase(),
+            caps_lock: toggled(VK_CAPITAL.0),
+            num_lock: toggled(VK_NUMLOCK.0),
+            scroll_lock: toggled(VK_SCROLL.0),
+        }
+    }
+}
+
+/// Keyboard layout and lock keys, switching to the next layout when clicked.
+pub struct Keyboard {
+    state: KeyboardState,
+    since_refresh: Option<Duration>, // `None` until the first refresh
+}
+
+impl Keyboard {
+    pub fn new() -> Self {
+        Self {
+            state: KeyboardState::default(),
+            since_refresh: None,
+        }
+    }
+}
+
+impl Widget for Keyboard {
+    fn update(&mut self, dt: Duration) {
+        if let Some(since_refresh) = self.since_refresh.as_mut() {
+            *since_refresh += dt;
+
+            if *since_refresh < KEYBOARD_INTERVAL {
+                return;
+            }
+        }
+
+        self.state = keyboard_state();
+        self.since_refresh = Some(Duration::ZERO);
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        let icon = if has_glyph(ui, ICON_KEYBOARD) { format!("{ICON_KEYBOARD} ") } else { String::new() };
+
+        if ui.button(format!("{icon}{}##_keyboard", self.state.layout)) {
+            // a failed switch just keeps the current layout
+            if let Err(e) = unsafe { ActivateKeyboardLayout(HKL_NEXT, KLF_SETFORPROCESS) } {
+                log::warn!("Failed to switch keyboard layout: {e}");
+            }
+
+            self.since_refresh = None;
+        }
+
+        for (name, on) in [("CAPS", self.state.caps_lock), ("NUM", self.state.num_lock), ("SCRL", self.state.scroll_lock)] {
+            ui.same_line();
+
+            if on {
+                ui.text(name);
+            } else {
+                ui.text_disabled(name);
+            }
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use std::rc::Rc;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+use anyhow::{Context, Result};
+use image::imageops::thumbnail;
+use image::RgbaImage;
+use imgui::Ui;
+use imgui_glow_renderer::glow;
+use imgui_glow_renderer::glow::{HasContext, NativeTexture};
+use windows::Media::Control::{
+    GlobalSystemMediaTransportControlsSession, GlobalSystemMediaTransportControlsSessionManager,
+    GlobalSystemMediaTransportControlsSessionPlaybackStatus,
+};
+use windows::Storage::Streams::{DataReader, IRandomAccessStreamReference};
+use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
+use crate::consts::*;
+use crate::fonts::{has_glyph, request_glyphs};
+use crate::renderer::{imgui_texture_id, upload_rgba_texture};
+use crate::widgets::Widget;
+
+#[derive(Clone, Copy)]
+enum Transport {
+    Previous,
+    PlayPause,
+    Next,
+}
+
+/// What the current media session is playing.
+#[derive(Clone)]
+struct NowPlaying {
+    title: String,
+    artist: String,
+    album: String,
+    playing: bool,
+    can_previous: bool,
+    can_play_pause: bool,
+    can_next: bool,
+}
+
+enum Update {
+    Session(Option<NowPlaying>), // `None` without a session
+    Art(Option<RgbaImage>), // sent when the track changes
+}
+
+fn now_playing(session: &GlobalSystemMediaTransportControlsSession) -> Result<NowPlaying> {
+    let properties = session
+        .TryGetMediaPropertiesAsync()
+        .and_then(|operation| operation.get())
+        .context("Failed to get media properties")?;
+
+    let playback = session.GetPlaybackInfo().context("Failed to get playback info")?;
+    let controls = playback.Controls().context("Failed to get playback controls")?;
+
+    Ok(NowPlaying {
+        title: properties.Title().map(|title| title.to_string()).unwrap_or_default(),
+        artist: properties.Artist().map(|artist| artist.to_string()).unwrap_or_default(),
+        album: properties.AlbumTitle().map(|album| album.to_string()).unwrap_or_default(),
+        playing: playback.PlaybackStatus().is_ok_and(|status| status == GlobalSystemMediaTransportControlsSessionPlaybackStatus::Playing),
+        can_previous: controls.IsPreviousEnabled().unwrap_or_default(),
+        can_play_pause: controls.IsPlayPauseToggleEnabled().unwrap_or_default(),
+        can_next: controls.IsNextEnabled().unwrap_or_default(),
+    })
+}
+
+/// Reads and decodes the album art, scaled down to `MEDIA_ART_SIZE`.
+fn album_art(reference: &IRandomAccessStreamReference) -> Result<RgbaImage> {
+    let stream = reference
+        .OpenReadAsync()
+        .and_then(|operation| operation.get())
+        .context("Failed to open album art")?;
+
+    let size = stream.Size().context("Failed to get album art size")? as u32;
+    let reader = DataReader::CreateDataReader(&stream.GetInputStreamAt(0)?).context("Failed to create album art reader")?;
+
+    reader.LoadAsync(size)
+        .and_then(|operation| operation.get())
+        .context("Failed to read album art")?;
+
+    let mut bytes = vec![0u8; size as usize];
+    reader.ReadBytes(&mut bytes).context("Failed to read album art")?;
+
+    let image = image::load_from_memory(&bytes).context("Failed to decode album art")?.to_rgba8();
+    let scale = (MEDIA_ART_SIZE[0] as f32 / image.width() as f32)
+        .min(MEDIA_ART_SIZE[1] as f32 / image.height() as f32)
+        .min(1f32);
+
+    Ok(thumbnail(
+        &image,
+        (image.width() as f32 * scale).max(1f32) as u32,
+        (image.height() as f32 * scale).max(1f32) as u32
+    ))
+}
+
+fn send_transport(session: &GlobalSystemMediaTransportControlsSession, transport: Transport) -> Result<()> {
+    let operation = match transport {
+        Transport::Previous => session.TrySkipPreviousAsync(),
+        Transport::PlayPause => session.TryTogglePlayPauseAsync(),
+        Transport::Next => session.TrySkipNextAsync(),
+    };
+
+    // `false` when the player ignored it, which isn't worth reporting
+    operation
+        .and_then(|operation| operation.get())
+        .context("Failed to send media control")?;
+
+    Ok(())
+}
+
+/// Polls the media session manager on a background thread, as its
+/// asynchronous calls block until the player answers. The thread
+/// ends with the sender.
+fn spawn_watcher(updates: Sender<Update>) -> Result<Sender<Transport>> {
+    let (transports, requested) = channel::<Transport>();
+
+    thread::Builder::new()
+        .name("media".to_string())
+        .spawn(move || {
+            // blocking on WinRT operations needs a multithreaded apartment
+            let _ = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+
+            let manager = match GlobalSystemMediaTransportControlsSessionManager::RequestAsync().and_then(|operation| operation.get()) {
+                Ok(manager) => manager,
+                Err(e) => {
+                    log::warn!("{:?}", anyhow::Error::from(e).context("Failed to get media session manager"));
+                    return;
+                }
+            };
+
+            let mut last = None;
+
+            loop {
+                // errors when there's no session
+                let session = manager.GetCurrentSession().ok();
+
+                let playing = session.as_ref().and_then(|session| {
+                    now_playing(session).inspect_err(|e| log::debug!("{e:?}")).ok()
+                });
+
+                let track = playing.as_ref().map(|playing| (playing.title.clone(), playing.artist.clone(), playing.album.clone()));
+                let art = (track != last).then(|| {
+                    session
+                        .as_ref()
+                        .and_then(|session| session.TryGetMediaPropertiesAsync().and_then(|operation| operation.get()).ok())
+                        .and_then(|properties| properties.Thumbnail().ok())
+                        .and_then(|reference| album_art(&reference).inspect_err(|e| log::debug!("{e:?}")).ok())
+                });
+
+                last = track;
+
+                // the widget is gone once nobody's listening
+                if updates.send(Update::Session(playing)).is_err() {
+                    break;
+                }
+
+                if let Some(art) = art {
+                    if updates.send(Update::Art(art)).is_err() {
+                        break;
+                    }
+                }
+
+                // polled again straight after a transport
+                match requested.recv_timeout(MEDIA_INTERVAL) {
+                    Ok(transport) => {
+                        if let Some(session) = &session {
+                            if let Err(e) = send_transport(session, transport) {
+                                log::warn!("{e:?}");
+                            }
+                        }
+                    }
+
+                    Err(RecvTimeoutError::Timeout) => { }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        })
+        .context("Failed to spawn media thread")?;
+
+    Ok(transports)
+}
+
+/// Track and transport controls of the current media session, hidden without one.
+pub struct Media {
+    gl: Rc<glow::Context>,
+    transports: Option<Sender<Transport>>, // `None` when the watcher couldn't start
+    updates: Receiver<Update>,
+    playing: Option<NowPlaying>,
+    art: Option<(NativeTexture, [f32; 2])>, // and its size
+}
+
+impl Media {
+    pub fn new(gl: Rc<glow::Context>) -> Self {
+        let (sender, updates) = channel();
+
+        let transports = spawn_watcher(sender)
+            .inspect_err(|e| log::warn!("{e:?}"))
+            .ok();
+
+        Self {
+            gl,
+            transports,
+            updates,
+            playing: None,
+            art: None,
+        }
+    }
+
+    fn set_art(&mut self, image: Option<RgbaImage>) {
+        if let Some((texture, _)) = self.art.take() {
+            unsafe { self.gl.delete_texture(texture); }
+        }
+
+        self.art = image.and_then(|image| {
+            upload_rgba_texture(&self.gl, image.width(), image.height(), image.as_raw())
+                .inspect_err(|e| log::warn!("{e:?}"))
+                .ok()
+                .map(|texture| (texture, [image.width() as f32, image.height() as f32]))
+        });
+    }
+
+    fn send(&mut self, transport: Transport) {
+        // shown straight away, the next update confirms it
+        if let (Transport::PlayPause, Some(playing)) = (transport, self.playing.as_mut()) {
+            playing.playing = !playing.playing;
+        }
+
+        if let Some(transports) = &self.transports {
+            let _ = transports.send(transport);
+        }
+    }
+}
+
+impl Widget for Media {
+    fn update(&mut self, _dt: Duration) {
+        while let Ok(update) = self.updates.try_recv() {
+            match update {
+                Update::Session(playing) => self.playing = playing,
+                Update::Art(image) => self.set_art(image),
+            }
+        }
+    }
+
+    fn visible(&self) -> bool {
+        self.playing.is_some()
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        let Some(playing) = self.playing.clone() else {
+            return;
+        };
+
+        let icon_or = |icon: char, fallback: &str| if has_glyph(ui, icon) { icon.to_string() } else { fallback.to_string() };
+        let mut transport = None;
+
+        let buttons = [
+            (Transport::Previous, icon_or(ICON_BACKWARD, "<"), playing.can_previous),
+            (Transport::PlayPause, if playing.playing { icon_or(ICON_PAUSE, "Pause") } else { icon_or(ICON_PLAY, "Play") }, playing.can_play_pause),
+            (Transport::Next, icon_or(ICON_FORWARD, ">"), playing.can_next),
+        ];
+
+        for (index, (button, label, enabled)) in buttons.into_iter().enumerate() {
+            if index > 0 {
+                ui.same_line();
+            }
+
+            let _disabled = ui.begin_disabled(!enabled);
+
+            if ui.button(format!("{label}##_media_{index}")) {
+                transport = Some(button);
+            }
+        }
+
+        let track = match playing.artist.is_empty() {
+            true => playing.title.clone(),
+            false => format!("{} - {}", playing.artist, playing.title),
+        };
+
+        let label = match track.char_indices().nth(MEDIA_TITLE_LENGTH) {
+            Some((end, _)) => format!("{}...", &track[..end]),
+            None => track.clone(),
+        };
+
+        request_glyphs(ui, &track);
+        ui.same_line();
+
+        if ui.button(format!("{label}##_media_track")) {
+            ui.open_popup(MEDIA_POPUP_ID);
+        }
+
+        ui.popup(MEDIA_POPUP_ID, || {
+            if let Some((texture, size)) = self.art {
+                imgui::Image::new(imgui_texture_id(texture), size).build(ui);
+            }
+
+            ui.text(&playing.title);
+
+            if !playing.artist.is_empty() {
+                ui.text(&playing.artist);
+            }
+
+            if !playing.album.is_empty() {
+                ui.text_disabled(&playing.album);
+            }
+        });
+
+        if let Some(transport) = transport {
+            self.send(transport);
+        }
+    }
+}
+
+impl Drop for Media {
+    fn drop(&mut self) {
+        self.set_art(None);
+    }
+}
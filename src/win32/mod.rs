@@ -0,0 +1,49 @@
+pub mod battery;
+pub mod brightness;
+pub mod clipboard;
+pub mod hotkeys;
+pub mod idle;
+pub mod ipc;
+pub mod keyboard;
+pub mod media;
+pub mod open_windows;
+pub mod power;
+pub mod quick_settings;
+pub mod startup;
+pub mod theme;
+pub mod thumbnails;
+pub mod tiling;
+pub mod tray;
+pub mod visualizer;
+pub mod volume;
+pub mod workspace_switcher;
+pub mod workspaces;
+
+use anyhow::{bail, Context, Result};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::{SetWindowPos, HWND_BOTTOM, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE};
+use winit::raw_window_handle::{HasWindowHandle, RawWindowHandle};
+use winit::window::Window;
+
+pub fn hwnd(window: &Window) -> Result<HWND> {
+    match window.window_handle().context("Failed to get window handle")?.as_raw() {
+        RawWindowHandle::Win32(handle) => Ok(HWND(handle.hwnd.get() as *mut _)),
+        _ => bail!("Window is not a Win32 window"),
+    }
+}
+
+/// Moves `window` below every other top-level window without activating it,
+/// as winit only applies `WindowLevel::AlwaysOnBottom` when the level is set.
+pub fn send_to_bottom(window: &Window) -> Result<()> {
+    unsafe {
+        SetWindowPos(
+            hwnd(window)?,
+            Some(HWND_BOTTOM),
+            0,
+            0,
+            0,
+            0,
+            SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE
+        ).context("Failed to set window position")
+    }
+}
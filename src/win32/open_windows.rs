@@ -0,0 +1,230 @@
+use std::rc::Rc;
+use std::time::Duration;
+use imgui::Ui;
+use imgui_glow_renderer::glow;
+use imgui_glow_renderer::glow::{HasContext, NativeTexture};
+use windows::core::BOOL;
+use windows::Win32::Foundation::{HWND, LPARAM};
+use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED};
+use windows::Win32::System::Threading::GetCurrentProcessId;
+use windows::Win32::UI::WindowsAndMessaging::{
+    EnumWindows, GetWindow, GetWindowLongW, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId, IsIconic,
+    IsWindowVisible, SetForegroundWindow, ShowWindow, GWL_EXSTYLE, GW_OWNER, SW_RESTORE, WS_EX_TOOLWINDOW,
+};
+use crate::consts::*;
+use crate::fonts::request_glyphs;
+use crate::i18n::tr;
+use crate::renderer::{imgui_texture_id, upload_rgba_texture};
+use crate::widgets::Widget;
+use crate::win32::thumbnails::capture_window;
+
+pub struct OpenWindow {
+    pub hwnd: HWND,
+    pub title: String,
+}
+
+impl OpenWindow {
+    pub fn focus(&self) {
+        unsafe {
+            if IsIconic(self.hwnd).as_bool() {
+                let _ = ShowWindow(self.hwnd, SW_RESTORE);
+            }
+
+            // clicking the taskbar made VeilDE the foreground
+            // process, so it's allowed to hand focus over
+            let _ = SetForegroundWindow(self.hwnd);
+        }
+    }
+}
+
+/// Whether `hwnd` would get a button on a regular taskbar.
+fn is_taskbar_window(hwnd: HWND) -> bool {
+    unsafe {
+        if !IsWindowVisible(hwnd).as_bool() || GetWindowTextLengthW(hwnd) == 0 {
+            return false;
+        }
+
+        // owned windows (dialogs) and tool windows belong to another window
+        if GetWindow(hwnd, GW_OWNER).is_ok_and(|owner| !owner.is_invalid()) {
+            return false;
+        }
+
+        if GetWindowLongW(hwnd, GWL_EXSTYLE) as u32 & WS_EX_TOOLWINDOW.0 != 0 {
+            return false;
+        }
+
+        // VeilDE's own windows
+        let mut process = 0;
+        GetWindowThreadProcessId(hwnd, Some(&mut process));
+
+        if process == GetCurrentProcessId() {
+            return false;
+        }
+
+        // suspended store apps and windows on other virtual desktops
+        let mut cloaked = 0u32;
+        let _ = DwmGetWindowAttribute(hwnd, DWMWA_CLOAKED, &mut cloaked as *mut _ as *mut _, size_of::<u32>() as u32);
+
+        cloaked == 0
+    }
+}
+
+unsafe extern "system" fn enum_window(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let windows = unsafe { &mut *(lparam.0 as *mut Vec<OpenWindow>) };
+
+    if is_taskbar_window(hwnd) {
+        let mut title = [0u16; 256];
+        let length = unsafe { GetWindowTextW(hwnd, &mut title) } as usize;
+
+        windows.push(OpenWindow {
+            hwnd,
+            title: String::from_utf16_lossy(&title[..length]),
+        });
+    }
+
+    true.into()
+}
+
+/// Lists the top-level windows that would appear on a regular taskbar.
+pub fn open_windows() -> Vec<OpenWindow> {
+    let mut windows = Vec::new();
+
+    unsafe {
+        let _ = EnumWindows(Some(enum_window), LPARAM(&mut windows as *mut _ as isize));
+    }
+
+    windows
+}
+
+/// Preview of the hovered window's button.
+struct Thumbnail {
+    hwnd: HWND,
+    texture: Option<(NativeTexture, [f32; 2])>, // and its size, `None` when it couldn't be captured
+    since_capture: Duration,
+}
+
+/// Taskbar widget with a button per open window, focusing it when clicked.
+pub struct OpenWindows {
+    gl: Rc<glow::Context>,
+    windows: Vec<OpenWindow>,
+    thumbnail: Option<Thumbnail>, // `None` while no button is hovered
+    since_refresh: Option<Duration>, // `None` until the first refresh
+}
+
+impl OpenWindows {
+    pub fn new(gl: Rc<glow::Context>) -> Self {
+        Self {
+            gl,
+            windows: Vec::new(),
+            thumbnail: None,
+            since_refresh: None,
+        }
+    }
+
+    fn release_thumbnail(&mut self) {
+        if let Some((texture, _)) = self.thumbnail.take().and_then(|thumbnail| thumbnail.texture) {
+            unsafe { self.gl.delete_texture(texture); }
+        }
+    }
+
+    /// Captures `hovered` again once the last capture is old enough,
+    /// releasing the thumbnail when nothing is hovered anymore.
+    fn refresh_thumbnail(&mut self, hovered: Option<HWND>) {
+        let Some(hwnd) = hovered else {
+            self.release_thumbnail();
+            return;
+        };
+
+        let current = self.thumbnail
+            .as_ref()
+            .is_some_and(|thumbnail| thumbnail.hwnd == hwnd && thumbnail.since_capture < THUMBNAIL_INTERVAL);
+
+        if current {
+            return;
+        }
+
+        let texture = capture_window(hwnd, THUMBNAIL_SIZE).and_then(|image| {
+            let texture = upload_rgba_texture(&self.gl, image.width(), image.height(), image.as_raw())
+                .inspect_err(|e| log::warn!("{:?}", e.context("Failed to upload window thumbnail")))
+                .ok()?;
+
+            Some((texture, [image.width() as f32, image.height() as f32]))
+        });
+
+        self.release_thumbnail();
+        self.thumbnail = Some(Thumbnail { hwnd, texture, since_capture: Duration::ZERO });
+    }
+}
+
+impl Widget for OpenWindows {
+    fn essential(&self) -> bool {
+        true
+    }
+
+    fn update(&mut self, dt: Duration) {
+        if let Some(thumbnail) = self.thumbnail.as_mut() {
+            thumbnail.since_capture += dt;
+        }
+
+        if let Some(since_refresh) = self.since_refresh.as_mut() {
+            *since_refresh += dt;
+
+            if *since_refresh < OPEN_WINDOWS_INTERVAL {
+                return;
+            }
+        }
+
+        self.windows = open_windows();
+        self.since_refresh = Some(Duration::ZERO);
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        let mut hovered = None;
+
+        for (index, window) in self.windows.iter().enumerate() {
+            if index > 0 {
+                ui.same_line();
+            }
+
+            let label = match window.title.char_indices().nth(OPEN_WINDOW_TITLE_LENGTH) {
+                Some((end, _)) => format!("{}...", &window.title[..end]),
+                None => window.title.clone(),
+            };
+
+            request_glyphs(ui, &window.title);
+
+            if ui.button(format!("{label}##_window_{:?}", window.hwnd.0)) {
+                window.focus();
+            }
+
+            if !ui.is_item_hovered() {
+                continue;
+            }
+
+            hovered = Some(window.hwnd);
+
+            // captured after this frame, so the
+            // first frame hovered only has the title
+            let thumbnail = self.thumbnail.as_ref().filter(|thumbnail| thumbnail.hwnd == window.hwnd);
+
+            ui.tooltip(|| {
+                ui.text(&window.title);
+
+                match thumbnail.map(|thumbnail| thumbnail.texture) {
+                    Some(Some((texture, size))) => imgui::Image::new(imgui_texture_id(texture), size).build(ui),
+                    Some(None) if unsafe { IsIconic(window.hwnd).as_bool() } => ui.text_disabled(tr("Minimized")),
+                    Some(None) => ui.text_disabled(tr("No preview")),
+                    None => { }
+                }
+            });
+        }
+
+        self.refresh_thumbnail(hovered);
+    }
+}
+
+impl Drop for OpenWindows {
+    fn drop(&mut self) {
+        self.release_thumbnail();
+    }
+}
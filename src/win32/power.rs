@@ -0,0 +1,74 @@
+use anyhow::{bail, Context, Result};
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{CloseHandle, GetLastError, ERROR_NOT_ALL_ASSIGNED, HANDLE, LUID};
+use windows::Win32::Security::{
+    AdjustTokenPrivileges, LookupPrivilegeValueW, LUID_AND_ATTRIBUTES, SE_PRIVILEGE_ENABLED, SE_SHUTDOWN_NAME, TOKEN_ADJUST_PRIVILEGES,
+    TOKEN_PRIVILEGES, TOKEN_QUERY
+};
+use windows::Win32::System::Shutdown::{
+    ExitWindowsEx, EWX_LOGOFF, EWX_POWEROFF, EWX_REBOOT, SHTDN_REASON_FLAG_PLANNED, SHTDN_REASON_MAJOR_OTHER, SHTDN_REASON_MINOR_OTHER
+};
+use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+/// How the session ends.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EndSession {
+    SignOut,
+    Restart,
+    ShutDown,
+}
+
+/// Asks Windows to end the session, which closes every program (VeilDE included) first.
+pub fn end_session(end: EndSession) -> Result<()> {
+    let flags = match end {
+        EndSession::SignOut => EWX_LOGOFF,
+        EndSession::Restart => EWX_REBOOT,
+        EndSession::ShutDown => EWX_POWEROFF,
+    };
+
+    // signing out only needs the user's own session
+    if end != EndSession::SignOut {
+        enable_shutdown_privilege()?;
+    }
+
+    unsafe {
+        ExitWindowsEx(flags, SHTDN_REASON_MAJOR_OTHER | SHTDN_REASON_MINOR_OTHER | SHTDN_REASON_FLAG_PLANNED)
+            .context("Failed to end the session")
+    }
+}
+
+/// Processes have the privilege to shut down, but it's disabled until they ask for it.
+fn enable_shutdown_privilege() -> Result<()> {
+    unsafe {
+        let mut token = HANDLE::default();
+        OpenProcessToken(GetCurrentProcess(), TOKEN_ADJUST_PRIVILEGES | TOKEN_QUERY, &mut token)
+            .context("Failed to open process token")?;
+
+        let result = adjust_shutdown_privilege(token);
+        let _ = CloseHandle(token);
+        result
+    }
+}
+
+unsafe fn adjust_shutdown_privilege(token: HANDLE) -> Result<()> {
+    let mut luid = LUID::default();
+
+    unsafe {
+        LookupPrivilegeValueW(PCWSTR::null(), SE_SHUTDOWN_NAME, &mut luid).context("Failed to look up shutdown privilege")?;
+
+        let privileges = TOKEN_PRIVILEGES {
+            PrivilegeCount: 1,
+            Privileges: [LUID_AND_ATTRIBUTES { Luid: luid, Attributes: SE_PRIVILEGE_ENABLED }],
+        };
+
+        AdjustTokenPrivileges(token, false, Some(&privileges), 0, None, None).context("Failed to enable shutdown privilege")?;
+
+        // succeeds without granting what the account
+        // doesn't have, e.g. restricted by group policy
+        if GetLastError() == ERROR_NOT_ALL_ASSIGNED {
+            bail!("This account isn't allowed to shut down the computer");
+        }
+    }
+
+    Ok(())
+}
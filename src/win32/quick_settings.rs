@@ -0,0 +1,244 @@
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+use anyhow::{bail, Context, Result};
+use imgui::{StyleColor, Ui};
+use windows::Devices::Radios::{Radio, RadioAccessStatus, RadioKind, RadioState};
+use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
+use crate::consts::*;
+use crate::fonts::has_glyph;
+use crate::i18n::tr;
+use crate::widgets::Widget;
+use crate::win32::brightness::Brightness;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Tile {
+    WiFi,
+    Bluetooth,
+    Airplane, // every radio off
+}
+
+/// Whether each tile is on, `None` when it can't be controlled.
+#[derive(Clone, Copy, Default)]
+struct Tiles {
+    wifi: Option<bool>,
+    bluetooth: Option<bool>,
+    airplane: Option<bool>,
+}
+
+impl Tiles {
+    fn get_mut(&mut self, tile: Tile) -> &mut Option<bool> {
+        match tile {
+            Tile::WiFi => &mut self.wifi,
+            Tile::Bluetooth => &mut self.bluetooth,
+            Tile::Airplane => &mut self.airplane,
+        }
+    }
+}
+
+fn radios() -> Result<Vec<Radio>> {
+    let radios = Radio::GetRadiosAsync()
+        .and_then(|operation| operation.get())
+        .context("Failed to get radios")?;
+
+    Ok(radios.into_iter().collect())
+}
+
+/// `failed` tiles stay disabled, e.g. when there's
+/// a Bluetooth radio that can't be switched.
+fn tiles(radios: &[Radio], failed: &[Tile]) -> Tiles {
+    let on = |kind: Option<RadioKind>| {
+        let states: Vec<bool> = radios
+            .iter()
+            .filter(|radio| kind.is_none() || radio.Kind().ok() == kind)
+            .map(|radio| radio.State().is_ok_and(|state| state == RadioState::On))
+            .collect();
+
+        (!states.is_empty()).then(|| states.contains(&true))
+    };
+
+    let mut tiles = Tiles {
+        wifi: on(Some(RadioKind::WiFi)),
+        bluetooth: on(Some(RadioKind::Bluetooth)),
+        airplane: on(None).map(|any_on| !any_on),
+    };
+
+    for tile in failed {
+        *tiles.get_mut(*tile) = None;
+    }
+
+    tiles
+}
+
+fn set_tile(radios: &[Radio], tile: Tile, on: bool) -> Result<()> {
+    let (kind, state) = match tile {
+        Tile::WiFi => (Some(RadioKind::WiFi), on),
+        Tile::Bluetooth => (Some(RadioKind::Bluetooth), on),
+        Tile::Airplane => (None, !on),
+    };
+
+    let state = if state { RadioState::On } else { RadioState::Off };
+
+    for radio in radios.iter().filter(|radio| kind.is_none() || radio.Kind().ok() == kind) {
+        let access = radio
+            .SetStateAsync(state)
+            .and_then(|operation| operation.get())
+            .context("Failed to switch radio")?;
+
+        if access != RadioAccessStatus::Allowed {
+            bail!("Switching the radio isn't allowed");
+        }
+    }
+
+    Ok(())
+}
+
+/// Polls the radios on a background thread, as their asynchronous
+/// calls block until the driver answers. The thread ends with the sender.
+fn spawn_watcher(updates: Sender<Tiles>) -> Result<Sender<(Tile, bool)>> {
+    let (toggles, requested) = channel::<(Tile, bool)>();
+
+    thread::Builder::new()
+        .name("quick settings".to_string())
+        .spawn(move || {
+            // blocking on WinRT operations needs a multithreaded apartment
+            let _ = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+
+            let access = Radio::RequestAccessAsync().and_then(|operation| operation.get());
+
+            // every tile stays disabled
+            if !matches!(access, Ok(RadioAccessStatus::Allowed)) {
+                log::warn!("Access to the radios was denied");
+                let _ = updates.send(Tiles::default());
+                return;
+            }
+
+            let mut failed = Vec::new();
+
+            loop {
+                let radios = radios().inspect_err(|e| log::debug!("{e:?}")).unwrap_or_default();
+
+                if updates.send(tiles(&radios, &failed)).is_err() {
+                    break;
+                }
+
+                // polled again straight after a toggle
+                match requested.recv_timeout(QUICK_SETTINGS_INTERVAL) {
+                    Ok((tile, on)) => {
+                        if let Err(e) = set_tile(&radios, tile, on) {
+                            log::warn!("{e:?}");
+                            failed.push(tile);
+                        }
+                    }
+
+                    Err(RecvTimeoutError::Timeout) => { }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        })
+        .context("Failed to spawn quick settings thread")?;
+
+    Ok(toggles)
+}
+
+/// Wi-Fi, Bluetooth and airplane mode tiles with a brightness
+/// slider, hidden when there's nothing it can control.
+pub struct QuickSettings {
+    toggles: Option<Sender<(Tile, bool)>>, // `None` when the watcher couldn't start
+    updates: Receiver<Tiles>,
+    tiles: Tiles,
+    brightness: Brightness,
+}
+
+impl QuickSettings {
+    pub fn new() -> Self {
+        let (sender, updates) = channel();
+
+        let toggles = spawn_watcher(sender)
+            .inspect_err(|e| log::warn!("{e:?}"))
+            .ok();
+
+        Self {
+            toggles,
+            updates,
+            tiles: Tiles::default(),
+            brightness: Brightness::new(),
+        }
+    }
+
+    fn toggle(&mut self, tile: Tile, on: bool) {
+        // shown straight away, the next update confirms it
+        *self.tiles.get_mut(tile) = Some(on);
+
+        if let Some(toggles) = &self.toggles {
+            let _ = toggles.send((tile, on));
+        }
+    }
+}
+
+impl Widget for QuickSettings {
+    fn update(&mut self, _dt: Duration) {
+        while let Ok(tiles) = self.updates.try_recv() {
+            self.tiles = tiles;
+        }
+
+        self.brightness.update();
+    }
+
+    fn visible(&self) -> bool {
+        let Tiles { wifi, bluetooth, airplane } = self.tiles;
+        wifi.is_some() || bluetooth.is_some() || airplane.is_some() || self.brightness.level().is_some()
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        let label = if has_glyph(ui, ICON_SLIDERS) { ICON_SLIDERS.to_string() } else { tr("Quick").to_string() };
+
+        if ui.button(format!("{label}##_quick_settings")) {
+            ui.open_popup(QUICK_SETTINGS_POPUP_ID);
+        }
+
+        let mut toggled = None;
+
+        ui.popup(QUICK_SETTINGS_POPUP_ID, || {
+            let tiles = [
+                (Tile::WiFi, tr("Wi-Fi"), self.tiles.wifi),
+                (Tile::Bluetooth, tr("Bluetooth"), self.tiles.bluetooth),
+                (Tile::Airplane, tr("Airplane mode"), self.tiles.airplane),
+            ];
+
+            for (index, (tile, name, state)) in tiles.into_iter().enumerate() {
+                if index > 0 {
+                    ui.same_line();
+                }
+
+                let on = state.unwrap_or(false);
+                let _disabled = ui.begin_disabled(state.is_none());
+
+                // tiles that are on look pressed
+                let _color = on.then(|| ui.push_style_color(StyleColor::Button, ui.style_color(StyleColor::ButtonActive)));
+
+                let status = if on { tr("On") } else { tr("Off") };
+
+                if ui.button_with_size(format!("{name}\n{status}##_quick_settings_{index}"), QUICK_SETTINGS_TILE_SIZE) {
+                    toggled = Some((tile, !on));
+                }
+            }
+
+            if let Some(level) = self.brightness.level() {
+                let mut percent = level * 100f32;
+
+                if ui
+                    .slider_config(format!("{}##_quick_settings_brightness", tr("Brightness")), 0f32, 100f32)
+                    .display_format("%.0f%%")
+                    .build(&mut percent)
+                {
+                    self.brightness.set_level(percent / 100f32);
+                }
+            }
+        });
+
+        if let Some((tile, on)) = toggled {
+            self.toggle(tile, on);
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use anyhow::{Context, Result};
+use windows::core::{w, HSTRING, PCWSTR};
+use windows::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+use windows::Win32::System::Registry::{RegDeleteKeyValueW, RegGetValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ, RRF_RT_REG_SZ};
+
+const RUN_KEY: PCWSTR = w!("Software\\Microsoft\\Windows\\CurrentVersion\\Run");
+const VALUE_NAME: PCWSTR = w!("VeilDE-rs");
+
+/// Whether VeilDE is registered to start with the user session.
+pub fn is_enabled() -> bool {
+    unsafe { RegGetValueW(HKEY_CURRENT_USER, RUN_KEY, VALUE_NAME, RRF_RT_REG_SZ, None, None, None).is_ok() }
+}
+
+/// Registers the current executable to start with the user session, or removes it.
+pub fn set_enabled(enabled: bool) -> Result<()> {
+    if !enabled {
+        let result = unsafe { RegDeleteKeyValueW(HKEY_CURRENT_USER, RUN_KEY, VALUE_NAME) };
+
+        // already unregistered
+        if result == ERROR_FILE_NOT_FOUND {
+            return Ok(());
+        }
+
+        return result.ok().context("Failed to remove startup entry");
+    }
+
+    let executable = std::env::current_exe().context("Failed to get executable path")?;
+
+    // quoted, as the path may contain spaces
+    let command = HSTRING::from(format!("\"{}\"", executable.display()));
+    let data = command.as_wide();
+
+    unsafe {
+        RegSetKeyValueW(
+            HKEY_CURRENT_USER,
+            RUN_KEY,
+            VALUE_NAME,
+            REG_SZ.0,
+            Some(data.as_ptr().cast()),
+            ((data.len() + 1) * size_of::<u16>()) as u32 // with the null terminator
+        ).ok().context("Failed to add startup entry")
+    }
+}
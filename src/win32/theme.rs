@@ -0,0 +1,36 @@
+use windows::core::{w, BOOL};
+use windows::Win32::Graphics::Dwm::DwmGetColorizationColor;
+use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
+use crate::config::Color;
+
+/// Whether apps should use the light theme, `None` when the setting is missing.
+pub fn apps_use_light_theme() -> Option<bool> {
+    let mut value = 0u32;
+    let mut size = size_of::<u32>() as u32;
+
+    unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            w!("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize"),
+            w!("AppsUseLightTheme"),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut value as *mut _ as *mut _),
+            Some(&mut size)
+        ).ok().ok()?;
+    }
+
+    Some(value != 0)
+}
+
+/// The accent color used for window frames, opaque.
+pub fn accent_color() -> Option<Color> {
+    let mut color = 0u32; // 0xAARRGGBB
+    let mut opaque = BOOL::default();
+
+    unsafe { DwmGetColorizationColor(&mut color, &mut opaque).ok()? };
+
+    let channel = |shift: u32| ((color >> shift) & 0xFF) as f32 / 255f32;
+
+    Some(Color([channel(16), channel(8), channel(0), 1f32]))
+}
@@ -0,0 +1,82 @@
+use image::imageops::thumbnail;
+use image::RgbaImage;
+use windows::Win32::Foundation::{HWND, RECT};
+use windows::Win32::Graphics::Gdi::{
+    CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits, ReleaseDC, SelectObject, BITMAPINFO,
+    BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+};
+use windows::Win32::Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS};
+use windows::Win32::UI::WindowsAndMessaging::{GetWindowRect, IsIconic};
+
+// not in `windows`, asks DWM for what's on screen
+// rather than the window painting itself, which
+// hardware accelerated windows draw black
+const PW_RENDERFULLCONTENT: PRINT_WINDOW_FLAGS = PRINT_WINDOW_FLAGS(2);
+
+/// Captures what `hwnd` shows, scaled down to fit in `max_size`.
+/// `None` when it's minimized or refuses to be captured.
+pub fn capture_window(hwnd: HWND, max_size: [u32; 2]) -> Option<RgbaImage> {
+    unsafe {
+        if IsIconic(hwnd).as_bool() {
+            return None;
+        }
+
+        let mut rect = RECT::default();
+        GetWindowRect(hwnd, &mut rect).ok()?;
+
+        let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+
+        if width <= 0 || height <= 0 {
+            return None;
+        }
+
+        let screen = GetDC(None);
+        let dc = CreateCompatibleDC(Some(screen));
+        let bitmap = CreateCompatibleBitmap(screen, width, height);
+        let previous = SelectObject(dc, bitmap.into());
+
+        let mut header = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                biHeight: -height, // top-down
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        let printed = PrintWindow(hwnd, dc, PW_RENDERFULLCONTENT).as_bool();
+
+        // the bitmap can't be selected while it's read
+        SelectObject(dc, previous);
+
+        let lines = if printed {
+            GetDIBits(dc, bitmap, 0, height as u32, Some(pixels.as_mut_ptr().cast()), &mut header, DIB_RGB_COLORS)
+        } else {
+            0
+        };
+
+        let _ = DeleteObject(bitmap.into());
+        let _ = DeleteDC(dc);
+        ReleaseDC(None, screen);
+
+        if lines == 0 {
+            return None;
+        }
+
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2); // BGRA to RGBA
+            pixel[3] = 255; // GDI leaves alpha undefined
+        }
+
+        let image = RgbaImage::from_raw(width as u32, height as u32, pixels)?;
+        let scale = (max_size[0] as f32 / width as f32).min(max_size[1] as f32 / height as f32).min(1f32);
+        let size = [(width as f32 * scale).max(1f32) as u32, (height as f32 * scale).max(1f32) as u32];
+
+        Some(thumbnail(&image, size[0], size[1]))
+    }
+}
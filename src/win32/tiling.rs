@@ -0,0 +1,63 @@
+use anyhow::{Context, Result};
+use windows::Win32::Foundation::{HWND, RECT};
+use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_EXTENDED_FRAME_BOUNDS};
+use windows::Win32::Graphics::Gdi::{MonitorFromWindow, HMONITOR, MONITOR_DEFAULTTONEAREST};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetWindowRect, IsIconic, IsZoomed, SetWindowPos, ShowWindow, SWP_NOACTIVATE, SWP_NOZORDER, SW_RESTORE,
+};
+use winit::monitor::MonitorHandle;
+use winit::platform::windows::MonitorHandleExtWindows;
+use crate::layout::{tiles, Layout};
+use crate::win32::open_windows::open_windows;
+
+/// Grows `tile` by the window's invisible resize borders, so its visible frame fills the tile.
+fn with_frame_borders(hwnd: HWND, [x, y, width, height]: [i32; 4]) -> [i32; 4] {
+    let mut window = RECT::default();
+    let mut frame = RECT::default();
+
+    let borders = unsafe {
+        GetWindowRect(hwnd, &mut window).is_ok() && DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_EXTENDED_FRAME_BOUNDS,
+            &mut frame as *mut _ as *mut _,
+            size_of::<RECT>() as u32
+        ).is_ok()
+    };
+
+    if !borders {
+        return [x, y, width, height];
+    }
+
+    let (left, top) = (frame.left - window.left, frame.top - window.top);
+    let (right, bottom) = (window.right - frame.right, window.bottom - frame.bottom);
+
+    [x - left, y - top, width + left + right, height + top + bottom]
+}
+
+/// Moves the open windows on `monitor` into `layout`'s tiles of `area`
+/// (x, y, width and height in screen pixels), leaving minimized ones alone.
+pub fn tile_windows(monitor: &MonitorHandle, layout: Layout, area: [i32; 4], gap: i32) -> Result<()> {
+    let monitor = HMONITOR(monitor.hmonitor() as *mut _);
+    let windows: Vec<_> = open_windows()
+        .into_iter()
+        .filter(|window| unsafe {
+            !IsIconic(window.hwnd).as_bool() && MonitorFromWindow(window.hwnd, MONITOR_DEFAULTTONEAREST) == monitor
+        })
+        .collect();
+
+    for (window, tile) in windows.iter().zip(tiles(layout, windows.len(), area, gap)) {
+        let [x, y, width, height] = with_frame_borders(window.hwnd, tile);
+
+        unsafe {
+            // maximized windows would stay maximized
+            if IsZoomed(window.hwnd).as_bool() {
+                let _ = ShowWindow(window.hwnd, SW_RESTORE);
+            }
+
+            SetWindowPos(window.hwnd, None, x, y, width, height, SWP_NOZORDER | SWP_NOACTIVATE)
+                .with_context(|| format!("Failed to move window '{}'", window.title))?;
+        }
+    }
+
+    Ok(())
+}
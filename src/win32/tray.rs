@@ -0,0 +1,345 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use anyhow::{bail, Context, Result};
+use imgui::{MouseButton, Ui};
+use imgui_glow_renderer::glow;
+use imgui_glow_renderer::glow::{HasContext, NativeTexture};
+use windows::core::{w, PCWSTR};
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    CreateCompatibleDC, DeleteDC, DeleteObject, GetDIBits, GetObjectW, BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+};
+use windows::Win32::System::DataExchange::COPYDATASTRUCT;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Shell::{NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD, NIM_DELETE, NIM_MODIFY};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, GetIconInfo, PostMessageW, RegisterClassW, RegisterWindowMessageW, UnregisterClassW,
+    HICON, HWND_BROADCAST, ICONINFO, WM_COPYDATA, WM_LBUTTONUP, WM_RBUTTONUP, WNDCLASSW, WS_EX_TOOLWINDOW, WS_POPUP,
+};
+use crate::consts::*;
+use crate::i18n::tr;
+use crate::renderer::{imgui_texture_id, upload_rgba_texture};
+use crate::widgets::Widget;
+
+const TRAY_CLASS: PCWSTR = w!("Shell_TrayWnd");
+
+// `Shell_NotifyIcon` sends its data to the
+// tray window through WM_COPYDATA, tagged
+// with this id and prefixed by a signature
+const SHELL_TRAY_DATA: usize = 1;
+const SHELL_TRAY_SIGNATURE: u32 = 0x34753423;
+
+/// The start of the undocumented `SHELLTRAYDATA` layout, handles
+/// are always sent as 32 bits so 32 and 64 bit processes agree.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct ShellTrayData {
+    signature: u32,
+    message: u32,
+    size: u32,
+    hwnd: u32,
+    id: u32,
+    flags: u32,
+    callback_message: u32,
+    icon: u32,
+    tip: [u16; 128],
+}
+
+pub struct TrayIconImage {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+/// A notification area icon registered by another application.
+pub struct TrayIcon {
+    pub owner: HWND,
+    pub id: u32,
+    pub callback_message: u32,
+    pub image: Option<TrayIconImage>,
+    pub tip: String,
+}
+
+impl TrayIcon {
+    /// Sends a click to the owning application, using the
+    /// original (pre `NOTIFYICON_VERSION_4`) message layout.
+    pub fn click(&self, message: u32) {
+        unsafe {
+            // the owner may have exited without removing its icon
+            let _ = PostMessageW(
+                Some(self.owner),
+                self.callback_message,
+                WPARAM(self.id as usize),
+                LPARAM(message as isize)
+            );
+        }
+    }
+}
+
+thread_local! {
+    // the tray window procedure runs on the
+    // event loop thread, as do the widgets
+    static ICONS: RefCell<Vec<TrayIcon>> = const { RefCell::new(Vec::new()) };
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Converts an icon to RGBA pixels, icons without an alpha channel are made opaque.
+fn icon_image(icon: HICON) -> Option<TrayIconImage> {
+    unsafe {
+        let mut info = ICONINFO::default();
+        GetIconInfo(icon, &mut info).ok()?;
+
+        let mut bitmap = BITMAP::default();
+        let result = if GetObjectW(info.hbmColor.into(), size_of::<BITMAP>() as i32, Some(&mut bitmap as *mut _ as *mut _)) == 0 {
+            None
+        } else {
+            let (width, height) = (bitmap.bmWidth as u32, bitmap.bmHeight as u32);
+            let mut header = BITMAPINFO {
+                bmiHeader: BITMAPINFOHEADER {
+                    biSize: size_of::<BITMAPINFOHEADER>() as u32,
+                    biWidth: width as i32,
+                    biHeight: -(height as i32), // top-down
+                    biPlanes: 1,
+                    biBitCount: 32,
+                    biCompression: BI_RGB.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            let mut pixels = vec![0u8; (width * height * 4) as usize];
+            let dc = CreateCompatibleDC(None);
+            let lines = GetDIBits(dc, info.hbmColor, 0, height, Some(pixels.as_mut_ptr().cast()), &mut header, DIB_RGB_COLORS);
+            let _ = DeleteDC(dc);
+
+            (lines != 0).then(|| {
+                let opaque = pixels.chunks_exact(4).all(|pixel| pixel[3] == 0);
+
+                for pixel in pixels.chunks_exact_mut(4) {
+                    pixel.swap(0, 2); // BGRA to RGBA
+
+                    if opaque {
+                        pixel[3] = 255;
+                    }
+                }
+
+                TrayIconImage { width, height, rgba: pixels }
+            })
+        };
+
+        let _ = DeleteObject(info.hbmColor.into());
+        let _ = DeleteObject(info.hbmMask.into());
+
+        result
+    }
+}
+
+fn handle_tray_data(data: &ShellTrayData) {
+    let owner = HWND(data.hwnd as usize as *mut _);
+    let flags = data.flags;
+
+    ICONS.with_borrow_mut(|icons| {
+        let index = icons.iter().position(|icon| icon.owner == owner && icon.id == data.id);
+
+        match (data.message, index) {
+            (message, None) if message == NIM_ADD.0 => icons.push(TrayIcon {
+                owner,
+                id: data.id,
+                callback_message: 0,
+                image: None,
+                tip: String::new(),
+            }),
+
+            (message, Some(index)) if message == NIM_DELETE.0 => {
+                icons.remove(index);
+                GENERATION.set(GENERATION.get() + 1);
+                return;
+            }
+
+            (message, Some(_)) if message == NIM_MODIFY.0 || message == NIM_ADD.0 => { }
+
+            _ => return,
+        }
+
+        let Some(icon) = icons.iter_mut().find(|icon| icon.owner == owner && icon.id == data.id) else {
+            return;
+        };
+
+        if flags & NIF_MESSAGE.0 != 0 {
+            icon.callback_message = data.callback_message;
+        }
+
+        if flags & NIF_ICON.0 != 0 {
+            icon.image = icon_image(HICON(data.icon as usize as *mut _));
+        }
+
+        if flags & NIF_TIP.0 != 0 {
+            let length = data.tip.iter().position(|&c| c == 0).unwrap_or(data.tip.len());
+            icon.tip = String::from_utf16_lossy(&data.tip[..length]);
+        }
+
+        GENERATION.set(GENERATION.get() + 1);
+    });
+}
+
+unsafe extern "system" fn window_proc(hwnd: HWND, message: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if message == WM_COPYDATA {
+        let copy = unsafe { &*(lparam.0 as *const COPYDATASTRUCT) };
+
+        if copy.dwData == SHELL_TRAY_DATA && copy.cbData as usize >= size_of::<ShellTrayData>() {
+            let data = unsafe { std::ptr::read_unaligned(copy.lpData as *const ShellTrayData) };
+
+            if data.signature == SHELL_TRAY_SIGNATURE {
+                handle_tray_data(&data);
+                return LRESULT(1);
+            }
+        }
+    }
+
+    unsafe { DefWindowProcW(hwnd, message, wparam, lparam) }
+}
+
+/// A `Shell_TrayWnd` window receiving icons from `Shell_NotifyIcon`.
+///
+/// Applications send their icons to the first `Shell_TrayWnd`, so this only
+/// receives icons when VeilDE replaces explorer as the shell.
+pub struct TrayHost {
+    hwnd: HWND,
+}
+
+impl TrayHost {
+    pub fn new() -> Result<Self> {
+        unsafe {
+            let instance = GetModuleHandleW(None).context("Failed to get module handle")?;
+
+            let class = WNDCLASSW {
+                lpfnWndProc: Some(window_proc),
+                hInstance: instance.into(),
+                lpszClassName: TRAY_CLASS,
+                ..Default::default()
+            };
+
+            if RegisterClassW(&class) == 0 {
+                bail!("Failed to register tray window class");
+            }
+
+            let hwnd = CreateWindowExW(
+                WS_EX_TOOLWINDOW,
+                TRAY_CLASS,
+                w!(""),
+                WS_POPUP,
+                0,
+                0,
+                0,
+                0,
+                None,
+                None,
+                Some(instance.into()),
+                None
+            ).context("Failed to create tray window")?;
+
+            // applications add their icons again when a taskbar is created
+            let taskbar_created = RegisterWindowMessageW(w!("TaskbarCreated"));
+            let _ = PostMessageW(Some(HWND_BROADCAST), taskbar_created, WPARAM(0), LPARAM(0));
+
+            Ok(Self { hwnd })
+        }
+    }
+}
+
+impl Drop for TrayHost {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = DestroyWindow(self.hwnd);
+
+            if let Ok(instance) = GetModuleHandleW(None) {
+                let _ = UnregisterClassW(TRAY_CLASS, Some(instance.into()));
+            }
+        }
+    }
+}
+
+/// Taskbar widget showing the icons received by the `TrayHost`.
+pub struct TrayIcons {
+    gl: Rc<glow::Context>,
+    textures: Vec<Option<NativeTexture>>, // parallel to `ICONS`
+    generation: Option<u64>,
+}
+
+impl TrayIcons {
+    pub fn new(gl: Rc<glow::Context>) -> Self {
+        Self {
+            gl,
+            textures: Vec::new(),
+            generation: None,
+        }
+    }
+
+    fn delete_textures(&mut self) {
+        for texture in self.textures.drain(..).flatten() {
+            unsafe { self.gl.delete_texture(texture); }
+        }
+    }
+
+    fn upload_textures(&mut self) {
+        self.delete_textures();
+
+        self.textures = ICONS.with_borrow(|icons| {
+            icons
+                .iter()
+                .map(|icon| {
+                    let image = icon.image.as_ref()?;
+                    upload_rgba_texture(&self.gl, image.width, image.height, &image.rgba).ok()
+                })
+                .collect()
+        });
+
+        self.generation = Some(GENERATION.get());
+    }
+}
+
+impl Widget for TrayIcons {
+    fn render(&mut self, ui: &Ui) {
+        if self.generation != Some(GENERATION.get()) {
+            self.upload_textures();
+        }
+
+        let size = [ui.text_line_height(); 2];
+
+        ICONS.with_borrow(|icons| {
+            for (index, (icon, texture)) in icons.iter().zip(&self.textures).enumerate() {
+                if index > 0 {
+                    ui.same_line();
+                }
+
+                let id = format!("_tray_icon_{index}");
+                let left_clicked = match texture {
+                    Some(texture) => ui.image_button(&id, imgui_texture_id(*texture), size),
+                    None => ui.button_with_size(format!("?##{id}"), size),
+                };
+
+                if left_clicked {
+                    icon.click(WM_LBUTTONUP);
+                }
+
+                if ui.is_item_clicked_with_button(MouseButton::Right) {
+                    icon.click(WM_RBUTTONUP);
+                }
+
+                if ui.is_item_hovered() && !icon.tip.is_empty() {
+                    ui.tooltip_text(&icon.tip);
+                }
+            }
+
+            if icons.is_empty() {
+                ui.text_disabled(tr(TRAY_EMPTY_TEXT));
+            }
+        });
+    }
+}
+
+impl Drop for TrayIcons {
+    fn drop(&mut self) {
+        self.delete_textures();
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+use std::slice;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+use anyhow::{bail, Context, Result};
+use imgui::{StyleColor, Ui};
+use windows::Win32::Media::Audio::{
+    eConsole, eRender, IAudioCaptureClient, IAudioClient, IMMDeviceEnumerator, MMDeviceEnumerator, AUDCLNT_BUFFERFLAGS_SILENT,
+    AUDCLNT_SHAREMODE_SHARED, AUDCLNT_STREAMFLAGS_LOOPBACK
+};
+use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CoTaskMemFree, CLSCTX_ALL, COINIT_MULTITHREADED};
+use crate::consts::*;
+use crate::widgets::Widget;
+
+/// A loopback stream of whatever the default output device plays.
+struct Loopback {
+    client: IAudioClient,
+    capture: IAudioCaptureClient,
+    channels: usize,
+    sample_rate: f32,
+}
+
+impl Loopback {
+    fn default_output() -> Result<Self> {
+        unsafe {
+            let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                .context("Failed to create audio device enumerator")?;
+
+            let device = enumerator
+                .GetDefaultAudioEndpoint(eRender, eConsole)
+                .context("Failed to get default audio output device")?;
+
+            let client = device
+                .Activate::<IAudioClient>(CLSCTX_ALL, None)
+                .context("Failed to activate audio client")?;
+
+            let format = client.GetMixFormat().context("Failed to get audio mix format")?;
+            let (channels, sample_rate, bits) = ((*format).nChannels, (*format).nSamplesPerSec, (*format).wBitsPerSample);
+
+            let result = client.Initialize(
+                AUDCLNT_SHAREMODE_SHARED,
+                AUDCLNT_STREAMFLAGS_LOOPBACK,
+                VISUALIZER_BUFFER_DURATION.as_nanos() as i64 / 100, // in 100 ns units
+                0,
+                format,
+                None
+            );
+
+            CoTaskMemFree(Some(format as *const _));
+            result.context("Failed to initialize audio loopback")?;
+
+            // shared mode mixes in 32-bit floats
+            if bits != 32 || channels == 0 {
+                bail!("Unsupported audio mix format");
+            }
+
+            let capture = client
+                .GetService::<IAudioCaptureClient>()
+                .context("Failed to get audio capture client")?;
+
+            client.Start().context("Failed to start audio loopback")?;
+
+            Ok(Self {
+                client,
+                capture,
+                channels: channels as usize,
+                sample_rate: sample_rate as f32,
+            })
+        }
+    }
+
+    /// Appends what was played since the last call to `samples`
+    /// as mono, returning how many were appended.
+    fn read(&self, samples: &mut VecDeque<f32>) -> Result<usize> {
+        let start = samples.len();
+
+        unsafe {
+            while self.capture.GetNextPacketSize().context("Failed to get audio packet size")? > 0 {
+                let mut data = std::ptr::null_mut();
+                let (mut frames, mut flags) = (0, 0);
+
+                self.capture
+                    .GetBuffer(&mut data, &mut frames, &mut flags, None, None)
+                    .context("Failed to get audio buffer")?;
+
+                if flags & AUDCLNT_BUFFERFLAGS_SILENT.0 as u32 != 0 {
+                    samples.extend(std::iter::repeat_n(0f32, frames as usize));
+                } else {
+                    let data = slice::from_raw_parts(data as *const f32, frames as usize * self.channels);
+
+                    samples.extend(data.chunks_exact(self.channels).map(|frame| frame.iter().sum::<f32>() / self.channels as f32));
+                }
+
+                self.capture.ReleaseBuffer(frames).context("Failed to release audio buffer")?;
+            }
+        }
+
+        Ok(samples.len() - start)
+    }
+}
+
+impl Drop for Loopback {
+    fn drop(&mut self) {
+        unsafe { let _ = self.client.Stop(); }
+    }
+}
+
+/// The level from 0 to 1 of each of `VISUALIZER_BARS` bands, spaced
+/// logarithmically like hearing is. Goertzel's algorithm is cheaper
+/// than a full FFT for this few frequencies.
+fn spectrum(samples: &VecDeque<f32>, sample_rate: f32) -> Vec<f32> {
+    let length = samples.len() as f32;
+
+    // a Hann window, so bands don't leak into each other
+    let windowed: Vec<f32> = samples
+        .iter()
+        .enumerate()
+        .map(|(index, sample)| sample * (0.5 - 0.5 * (2f32 * PI * index as f32 / length).cos()))
+        .collect();
+
+    let highest = VISUALIZER_MAX_FREQUENCY.min(sample_rate / 2f32);
+
+    (0..VISUALIZER_BARS)
+        .map(|bar| {
+            let frequency = VISUALIZER_MIN_FREQUENCY * (highest / VISUALIZER_MIN_FREQUENCY).powf(bar as f32 / (VISUALIZER_BARS - 1) as f32);
+            let coefficient = 2f32 * (2f32 * PI * frequency / sample_rate).cos();
+
+            let (mut previous, mut before) = (0f32, 0f32);
+
+            for sample in &windowed {
+                (previous, before) = (sample + coefficient * previous - before, previous);
+            }
+
+            let power = (previous * previous + before * before - coefficient * previous * before).max(0f32);
+
+            // a full scale sine peaks at a quarter of the window's length
+            let decibels = 20f32 * (power.sqrt() / (length / 4f32)).max(f32::MIN_POSITIVE).log10();
+
+            (1f32 - decibels / VISUALIZER_FLOOR_DECIBELS).clamp(0f32, 1f32)
+        })
+        .collect()
+}
+
+/// Captures on a background thread so reading audio never blocks
+/// a frame. The thread ends when the receiver is dropped or the
+/// device goes away, which disconnects the channel.
+fn spawn_capture() -> Result<Receiver<Vec<f32>>> {
+    let (sender, levels) = channel();
+
+    thread::Builder::new()
+        .name("visualizer".to_string())
+        .spawn(move || {
+            let _ = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+
+            // having no audio device isn't an error
+            // worth notifying, the widget just hides
+            let loopback = match Loopback::default_output() {
+                Ok(loopback) => loopback,
+                Err(e) => {
+                    log::debug!("{e:?}");
+                    return;
+                }
+            };
+
+            let mut samples = VecDeque::with_capacity(VISUALIZER_WINDOW);
+
+            loop {
+                thread::sleep(VISUALIZER_INTERVAL);
+
+                let read = match loopback.read(&mut samples) {
+                    Ok(read) => read,
+                    Err(e) => {
+                        log::debug!("{e:?}");
+                        break;
+                    }
+                };
+
+                // no packets arrive while nothing plays
+                if read == 0 {
+                    let silence = (loopback.sample_rate * VISUALIZER_INTERVAL.as_secs_f32()) as usize;
+                    samples.extend(std::iter::repeat_n(0f32, silence));
+                }
+
+                let excess = samples.len().saturating_sub(VISUALIZER_WINDOW);
+                samples.drain(..excess);
+
+                let levels = match samples.len() < VISUALIZER_WINDOW {
+                    true => vec![0f32; VISUALIZER_BARS],
+                    false => spectrum(&samples, loopback.sample_rate),
+                };
+
+                if sender.send(levels).is_err() {
+                    break;
+                }
+            }
+        })
+        .context("Failed to spawn visualizer thread")?;
+
+    Ok(levels)
+}
+
+/// Bars following what the default output device plays, hidden without one.
+pub struct Visualizer {
+    levels: Option<Receiver<Vec<f32>>>, // `None` until the next retry after the capture ended
+    bars: Vec<f32>,
+    active: bool, // whether any levels arrived from the current capture
+    since_retry: Duration,
+}
+
+impl Visualizer {
+    pub fn new() -> Self {
+        Self {
+            levels: spawn_capture().inspect_err(|e| log::warn!("{e:?}")).ok(),
+            bars: vec![0f32; VISUALIZER_BARS],
+            active: false,
+            since_retry: Duration::ZERO,
+        }
+    }
+}
+
+impl Widget for Visualizer {
+    fn update(&mut self, dt: Duration) {
+        // bars jump up to a level but fall smoothly
+        for bar in &mut self.bars {
+            *bar = (*bar - VISUALIZER_FALL_RATE * dt.as_secs_f32()).max(0f32);
+        }
+
+        let Some(levels) = &self.levels else {
+            self.since_retry += dt;
+
+            // the default device changes when
+            // headphones are plugged in or out
+            if self.since_retry >= VISUALIZER_RETRY_INTERVAL {
+                self.since_retry = Duration::ZERO;
+                self.levels = spawn_capture().inspect_err(|e| log::warn!("{e:?}")).ok();
+            }
+
+            return;
+        };
+
+        let mut disconnected = false;
+
+        loop {
+            match levels.try_recv() {
+                Ok(levels) => {
+                    self.active = true;
+
+                    for (bar, level) in self.bars.iter_mut().zip(levels) {
+                        *bar = bar.max(level);
+                    }
+                }
+
+                Err(TryRecvError::Empty) => break,
+
+                Err(TryRecvError::Disconnected) => {
+                    disconnected = true;
+                    break;
+                }
+            }
+        }
+
+        if disconnected {
+            self.levels = None;
+            self.active = false;
+        }
+    }
+
+    fn visible(&self) -> bool {
+        self.active
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        let height = ui.frame_height();
+        let width = VISUALIZER_BARS as f32 * (VISUALIZER_BAR_WIDTH + VISUALIZER_BAR_GAP) - VISUALIZER_BAR_GAP;
+        let [x, y] = ui.cursor_screen_pos();
+
+        let draw_list = ui.get_window_draw_list();
+        let color = ui.style_color(StyleColor::PlotHistogram);
+
+        for (index, level) in self.bars.iter().enumerate() {
+            let left = x + index as f32 * (VISUALIZER_BAR_WIDTH + VISUALIZER_BAR_GAP);
+
+            // a sliver shows even in silence
+            let top = y + height - (height * level).max(1f32);
+
+            draw_list
+                .add_rect([left, top], [left + VISUALIZER_BAR_WIDTH, y + height], color)
+                .filled(true)
+                .build();
+        }
+
+        // takes up the space drawn over in the layout
+        ui.dummy([width, height]);
+    }
+}
@@ -0,0 +1,159 @@
+use std::ptr::null;
+use std::time::Duration;
+use anyhow::{Context, Result};
+use imgui::Ui;
+use windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume;
+use windows::Win32::Media::Audio::{eConsole, eRender, IMMDeviceEnumerator, MMDeviceEnumerator};
+use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_APARTMENTTHREADED};
+use crate::consts::*;
+use crate::fonts::has_glyph;
+use crate::i18n::tr;
+use crate::widgets::Widget;
+
+/// The master volume of the default output device.
+struct Endpoint {
+    volume: IAudioEndpointVolume,
+}
+
+impl Endpoint {
+    fn default_output() -> Result<Self> {
+        unsafe {
+            // winit already initializes COM on the event loop
+            // thread for drag and drop, this is a no-op then
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+            let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                .context("Failed to create audio device enumerator")?;
+
+            let device = enumerator
+                .GetDefaultAudioEndpoint(eRender, eConsole)
+                .context("Failed to get default audio output device")?;
+
+            Ok(Self {
+                volume: device
+                    .Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None)
+                    .context("Failed to activate audio endpoint volume")?,
+            })
+        }
+    }
+
+    /// The volume from 0 to 1 and whether it's muted.
+    fn state(&self) -> Result<(f32, bool)> {
+        unsafe {
+            Ok((
+                self.volume.GetMasterVolumeLevelScalar().context("Failed to get volume")?,
+                self.volume.GetMute().context("Failed to get mute state")?.as_bool(),
+            ))
+        }
+    }
+
+    fn set_level(&self, level: f32) -> Result<()> {
+        unsafe { self.volume.SetMasterVolumeLevelScalar(level, null()).context("Failed to set volume") }
+    }
+
+    fn set_mute(&self, mute: bool) -> Result<()> {
+        unsafe { self.volume.SetMute(mute, null()).context("Failed to set mute state") }
+    }
+}
+
+/// Master volume slider and mute toggle, hidden when there's no output device.
+pub struct Volume {
+    endpoint: Option<Endpoint>,
+    level: f32,
+    muted: bool,
+    since_refresh: Option<Duration>, // `None` until the first refresh
+}
+
+impl Volume {
+    pub fn new() -> Self {
+        Self {
+            endpoint: None,
+            level: 0f32,
+            muted: false,
+            since_refresh: None,
+        }
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        // the default device changes when
+        // headphones are plugged in or out
+        let endpoint = Endpoint::default_output()?;
+        (self.level, self.muted) = endpoint.state()?;
+        self.endpoint = Some(endpoint);
+
+        Ok(())
+    }
+}
+
+impl Widget for Volume {
+    fn update(&mut self, dt: Duration) {
+        if let Some(since_refresh) = self.since_refresh.as_mut() {
+            *since_refresh += dt;
+
+            if *since_refresh < VOLUME_INTERVAL {
+                return;
+            }
+        }
+
+        // having no audio device isn't an error
+        // worth notifying, the widget just hides
+        if self.refresh().is_err() {
+            self.endpoint = None;
+        }
+
+        self.since_refresh = Some(Duration::ZERO);
+    }
+
+    fn visible(&self) -> bool {
+        self.endpoint.is_some()
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        let Some(endpoint) = &self.endpoint else {
+            return;
+        };
+
+        let icon = match (self.muted, self.level) {
+            (true, _) => ICON_VOLUME_MUTE,
+            (false, level) if level < 0.5 => ICON_VOLUME_LOW,
+            _ => ICON_VOLUME_HIGH,
+        };
+
+        let label = match (has_glyph(ui, icon), self.muted) {
+            (true, _) => format!("{icon} {:.0}%", self.level * 100f32),
+            (false, true) => "Muted".to_string(),
+            (false, false) => format!("Vol {:.0}%", self.level * 100f32),
+        };
+
+        if ui.button(format!("{label}##_volume")) {
+            ui.open_popup(VOLUME_POPUP_ID);
+        }
+
+        let mut result = Ok(());
+
+        ui.popup(VOLUME_POPUP_ID, || {
+            let mut percent = self.level * 100f32;
+
+            if ui
+                .slider_config("##_volume_slider", 0f32, 100f32)
+                .display_format("%.0f%%")
+                .build(&mut percent)
+            {
+                self.level = percent / 100f32;
+                result = endpoint.set_level(self.level);
+            }
+
+            if ui.checkbox(tr("Mute"), &mut self.muted) {
+                result = endpoint.set_mute(self.muted);
+            }
+        });
+
+        // the device was likely removed, pick
+        // up the new default on the next frame
+        if let Err(e) = result {
+            log::warn!("{e:?}");
+            self.endpoint = None;
+            self.since_refresh = None;
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use imgui::{StyleColor, Ui};
+use crate::config::Config;
+use crate::widgets::Widget;
+use crate::win32::workspaces;
+
+/// Taskbar widget with a button per workspace, switching to it when clicked.
+pub struct WorkspaceSwitcher {
+    count: usize,
+}
+
+impl WorkspaceSwitcher {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            count: config.workspace_count.max(1),
+        }
+    }
+}
+
+impl Widget for WorkspaceSwitcher {
+    fn essential(&self) -> bool {
+        true
+    }
+
+    fn render(&mut self, ui: &Ui) {
+        let current = workspaces::current();
+        let active = ui.style_color(StyleColor::ButtonActive);
+
+        for workspace in 0..self.count {
+            if workspace > 0 {
+                ui.same_line();
+            }
+
+            let highlight = (workspace == current).then(|| ui.push_style_color(StyleColor::Button, active));
+
+            if ui.button(format!("{}##_workspace_{workspace}", workspace + 1)) {
+                workspaces::switch(workspace);
+            }
+
+            drop(highlight);
+        }
+    }
+
+    fn apply_config(&mut self, config: &Config) {
+        self.count = config.workspace_count.max(1);
+
+        // the windows of removed workspaces come back
+        if workspaces::current() >= self.count {
+            workspaces::restore();
+        }
+    }
+}
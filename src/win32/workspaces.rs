@@ -0,0 +1,79 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::{IsWindow, ShowWindow, SW_HIDE, SW_SHOWNA};
+use crate::win32::open_windows::open_windows;
+
+/// Workspaces emulated by hiding the windows of every workspace but the current one,
+/// as Windows doesn't let other programs switch virtual desktops.
+struct Workspaces {
+    current: usize,
+    hidden: HashMap<usize, Vec<HWND>>, // by workspace, while it isn't current
+}
+
+thread_local! {
+    // shared by every monitor's switcher
+    static WORKSPACES: RefCell<Workspaces> = RefCell::new(Workspaces {
+        current: 0,
+        hidden: HashMap::new(),
+    });
+}
+
+pub fn current() -> usize {
+    WORKSPACES.with_borrow(|workspaces| workspaces.current)
+}
+
+/// Hides the current workspace's windows and shows those of `workspace`.
+pub fn switch(workspace: usize) {
+    WORKSPACES.with_borrow_mut(|workspaces| {
+        if workspace == workspaces.current {
+            return;
+        }
+
+        // whatever is open belongs to the workspace being left
+        let windows = open_windows()
+            .into_iter()
+            .map(|window| window.hwnd)
+            .collect::<Vec<_>>();
+
+        for hwnd in &windows {
+            unsafe { let _ = ShowWindow(*hwnd, SW_HIDE); }
+        }
+
+        workspaces.hidden.insert(workspaces.current, windows);
+        workspaces.current = workspace;
+
+        show(workspaces.hidden.remove(&workspace).unwrap_or_default());
+    });
+}
+
+/// Shows the windows of every workspace, so none stay hidden after VeilDE exits.
+/// Also called from the panic hook, so it does nothing
+/// when the panic happened while switching workspaces.
+pub fn restore() {
+    let _ = WORKSPACES.try_with(|workspaces| {
+        let Ok(mut workspaces) = workspaces.try_borrow_mut() else {
+            return;
+        };
+
+        for (_, windows) in workspaces.hidden.drain() {
+            show(windows);
+        }
+
+        workspaces.current = 0;
+    });
+}
+
+fn show(windows: Vec<HWND>) {
+    for hwnd in windows {
+        unsafe {
+            // closed while hidden
+            if !IsWindow(Some(hwnd)).as_bool() {
+                continue;
+            }
+
+            // without taking focus from VeilDE
+            let _ = ShowWindow(hwnd, SW_SHOWNA);
+        }
+    }
+}